@@ -219,11 +219,29 @@ impl CallArgs {
     }
 }
 
+/// Parse the arguments declared by a mixin or function, along with the `{`
+/// that begins its body
 pub(crate) fn eat_func_args<I: Iterator<Item = Token>>(
     toks: &mut PeekMoreIterator<I>,
     scope: &Scope,
     super_selector: &Selector,
 ) -> SassResult<FuncArgs> {
+    let (args, close_paren_span) = eat_func_params(toks, scope, super_selector)?;
+    devour_whitespace(toks);
+    match toks.next() {
+        Some(v) if v.kind == '{' => {}
+        Some(..) | None => return Err(("expected \"{\".", close_paren_span).into()),
+    };
+    Ok(args)
+}
+
+/// Parse a list of declared arguments up to and including the closing `)`,
+/// returning them along with the span of the `)`
+pub(crate) fn eat_func_params<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    scope: &Scope,
+    super_selector: &Selector,
+) -> SassResult<(FuncArgs, Span)> {
     let mut args: Vec<FuncArg> = Vec::new();
     let mut close_paren_span: Span = toks.peek().unwrap().pos();
 
@@ -242,7 +260,7 @@ pub(crate) fn eat_func_args<I: Iterator<Item = Token>>(
         devour_whitespace(toks);
         let (kind, span) = match toks.next() {
             Some(Token { kind, pos }) => (kind, pos),
-            _ => todo!("unexpected eof"),
+            None => return Err(("expected \")\".", name.span).into()),
         };
         match kind {
             ':' => {
@@ -321,12 +339,7 @@ pub(crate) fn eat_func_args<I: Iterator<Item = Token>>(
         }
         devour_whitespace(toks);
     }
-    devour_whitespace(toks);
-    match toks.next() {
-        Some(v) if v.kind == '{' => {}
-        Some(..) | None => return Err(("expected \"{\".", close_paren_span).into()),
-    };
-    Ok(FuncArgs(args))
+    Ok((FuncArgs(args), close_paren_span))
 }
 
 pub(crate) fn eat_call_args<I: Iterator<Item = Token>>(
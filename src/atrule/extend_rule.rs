@@ -0,0 +1,70 @@
+use codemap::Span;
+
+use peekmore::{PeekMore, PeekMoreIterator};
+
+use crate::error::SassResult;
+use crate::scope::Scope;
+use crate::selector::{extension_targets, Selector, SelectorKind};
+use crate::utils::{
+    devour_whitespace, eat_ident_no_interpolation, read_until_semicolon_or_closing_curly_brace,
+};
+use crate::Token;
+
+/// A parsed `@extend` rule
+///
+/// `@extend .a, %b !optional;`
+#[derive(Debug, Clone)]
+pub(crate) struct Extend {
+    /// The resolved selector of the style rule containing the `@extend`
+    pub selector: Selector,
+    pub targets: Vec<SelectorKind>,
+    pub is_optional: bool,
+    pub span: Span,
+}
+
+impl Extend {
+    pub fn from_tokens<I: Iterator<Item = Token>>(
+        toks: &mut PeekMoreIterator<I>,
+        scope: &Scope,
+        super_selector: &Selector,
+        kind_span: Span,
+    ) -> SassResult<Extend> {
+        if super_selector.is_empty() {
+            return Err(("@extend may only be used within style rules.", kind_span).into());
+        }
+
+        let mut target = read_until_semicolon_or_closing_curly_brace(toks)?;
+        if let Some(Token { kind: ';', .. }) = toks.peek() {
+            toks.next();
+        }
+        devour_whitespace(toks);
+
+        let span = target
+            .iter()
+            .fold(kind_span, |span, tok| span.merge(tok.pos()));
+
+        let mut is_optional = false;
+        if let Some(idx) = target.iter().position(|tok| tok.kind == '!') {
+            let mut flag = target.split_off(idx).into_iter().skip(1).peekmore();
+            let name = eat_ident_no_interpolation(&mut flag, false, span)?;
+            devour_whitespace(&mut flag);
+            if !name.node.eq_ignore_ascii_case("optional") || flag.peek().is_some() {
+                return Err(("Expected \"optional\".", name.span).into());
+            }
+            is_optional = true;
+        }
+
+        let target =
+            Selector::from_tokens(&mut target.into_iter().peekmore(), scope, super_selector)?;
+        if target.is_empty() {
+            return Err(("Expected selector.", span).into());
+        }
+
+        Ok(Extend {
+            selector: super_selector.clone(),
+            targets: extension_targets(&target, span)?,
+            is_optional,
+            span,
+        })
+    }
+}
@@ -0,0 +1,114 @@
+use codemap::{Span, Spanned};
+
+use peekmore::PeekMoreIterator;
+
+use super::use_rule::{expect_semicolon, parse_configuration, parse_url};
+use crate::common::Identifier;
+use crate::error::SassResult;
+use crate::modules::{Configuration, Forwarding, MemberNames, Visibility};
+use crate::utils::{devour_whitespace_or_comment, eat_ident_no_interpolation};
+use crate::Token;
+
+/// A parsed `@forward` rule
+///
+/// `@forward "src/buttons" as btn-* hide btn-reset, $btn-gap;`
+#[derive(Debug, Clone)]
+pub(crate) struct Forward {
+    pub url: Spanned<String>,
+    pub forwarding: Forwarding,
+    pub config: Configuration,
+}
+
+impl Forward {
+    pub fn from_tokens<I: Iterator<Item = Token>>(
+        toks: &mut PeekMoreIterator<I>,
+        span_before: Span,
+    ) -> SassResult<Forward> {
+        let url = parse_url(toks, span_before)?;
+
+        devour_whitespace_or_comment(toks)?;
+
+        let mut prefix = None;
+        let mut visibility = None;
+        let mut config = None;
+
+        while let Some(Token { kind, .. }) = toks.peek() {
+            if !kind.is_ascii_alphabetic() {
+                break;
+            }
+            let ident = eat_ident_no_interpolation(toks, false, url.span)?;
+            devour_whitespace_or_comment(toks)?;
+            match ident.node.to_ascii_lowercase().as_str() {
+                "as" if prefix.is_none() && visibility.is_none() && config.is_none() => {
+                    prefix = Some(parse_prefix(toks, ident.span)?);
+                }
+                "show" if visibility.is_none() && config.is_none() => {
+                    visibility = Some(Visibility::Show(parse_member_names(toks, ident.span)?));
+                }
+                "hide" if visibility.is_none() && config.is_none() => {
+                    visibility = Some(Visibility::Hide(parse_member_names(toks, ident.span)?));
+                }
+                "with" if config.is_none() => {
+                    config = Some(parse_configuration(toks, ident.span, true)?);
+                }
+                _ => return Err(("expected \";\".", ident.span).into()),
+            }
+            devour_whitespace_or_comment(toks)?;
+        }
+
+        expect_semicolon(toks)?;
+
+        Ok(Forward {
+            url,
+            forwarding: Forwarding {
+                prefix,
+                visibility: visibility.unwrap_or(Visibility::All),
+            },
+            config: config.unwrap_or_default(),
+        })
+    }
+}
+
+/// Parse the `prefix-*` in `as prefix-*`
+fn parse_prefix<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    span_before: Span,
+) -> SassResult<Identifier> {
+    let prefix = eat_ident_no_interpolation(toks, false, span_before)?;
+    match toks.next() {
+        Some(Token { kind: '*', .. }) => Ok(prefix.node.into()),
+        Some(Token { pos, .. }) => Err(("expected \"*\".", pos).into()),
+        None => Err(("expected \"*\".", prefix.span).into()),
+    }
+}
+
+/// Parse the comma separated list of names following `show` or `hide`
+fn parse_member_names<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    span_before: Span,
+) -> SassResult<MemberNames> {
+    let mut names = MemberNames::default();
+    loop {
+        match toks.peek() {
+            Some(Token { kind: '$', pos }) => {
+                let pos = *pos;
+                toks.next();
+                let name = eat_ident_no_interpolation(toks, false, pos)?;
+                names.variables.insert(name.node.into());
+            }
+            Some(..) => {
+                let name = eat_ident_no_interpolation(toks, false, span_before)?;
+                names.callables.insert(name.node.into());
+            }
+            None => return Err(("Expected identifier.", span_before).into()),
+        }
+        devour_whitespace_or_comment(toks)?;
+        match toks.peek() {
+            Some(Token { kind: ',', .. }) => {
+                toks.next();
+                devour_whitespace_or_comment(toks)?;
+            }
+            Some(..) | None => return Ok(names),
+        }
+    }
+}
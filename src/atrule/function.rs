@@ -8,7 +8,9 @@ use peekmore::{PeekMore, PeekMoreIterator};
 
 use crate::args::{eat_func_args, CallArgs, FuncArgs};
 use crate::atrule::AtRule;
+use crate::common::Identifier;
 use crate::error::SassResult;
+use crate::modules::Module;
 use crate::scope::Scope;
 use crate::selector::Selector;
 use crate::unit::Unit;
@@ -17,7 +19,7 @@ use crate::value::{Number, Value};
 use crate::{Stmt, Token};
 
 #[derive(Debug, Clone)]
-pub(crate) struct Function {
+pub struct Function {
     scope: Scope,
     args: FuncArgs,
     body: Vec<Token>,
@@ -33,7 +35,7 @@ impl PartialEq for Function {
 impl Eq for Function {}
 
 impl Function {
-    pub fn new(scope: Scope, args: FuncArgs, body: Vec<Token>, pos: Span) -> Self {
+    pub(crate) fn new(scope: Scope, args: FuncArgs, body: Vec<Token>, pos: Span) -> Self {
         Function {
             scope,
             args,
@@ -42,7 +44,7 @@ impl Function {
         }
     }
 
-    pub fn decl_from_tokens<I: Iterator<Item = Token>>(
+    pub(crate) fn decl_from_tokens<I: Iterator<Item = Token>>(
         toks: &mut PeekMoreIterator<I>,
         scope: Scope,
         super_selector: &Selector,
@@ -65,7 +67,7 @@ impl Function {
         Ok((name, Function::new(scope, args, body, span)))
     }
 
-    pub fn args(
+    pub(crate) fn args(
         &mut self,
         mut args: CallArgs,
         scope: &Scope,
@@ -75,7 +77,7 @@ impl Function {
         for (idx, arg) in self.args.0.iter_mut().enumerate() {
             if arg.is_variadic {
                 let span = args.span();
-                let arg_list = Value::ArgList(args.get_variadic(&scope, super_selector)?);
+                let arg_list = Value::arg_list(args.get_variadic(&scope, super_selector)?);
                 self.scope.insert_var(
                     arg.name.clone(),
                     Spanned {
@@ -102,7 +104,10 @@ impl Function {
         Ok(())
     }
 
-    pub fn eval_body(&mut self, super_selector: &Selector) -> SassResult<Vec<Spanned<Stmt>>> {
+    pub(crate) fn eval_body(
+        &mut self,
+        super_selector: &Selector,
+    ) -> SassResult<Vec<Spanned<Stmt>>> {
         eat_stmts(
             &mut std::mem::take(&mut self.body).into_iter().peekmore(),
             &mut self.scope,
@@ -112,19 +117,47 @@ impl Function {
         )
     }
 
-    pub fn eval(
+    /// Call this function, which is named `name` at the call
+    ///
+    /// Errors evaluating the body record the call, while errors evaluating
+    /// the arguments belong to the caller
+    pub(crate) fn eval(
         mut self,
         args: CallArgs,
         scope: &Scope,
         super_selector: &Selector,
+        name: Spanned<&Identifier>,
     ) -> SassResult<Value> {
         self.args(args, scope, super_selector)?;
+        self.eval_call(super_selector)
+            .map_err(|e| e.in_call(name.node, name.span))
+    }
+
+    fn eval_call(&mut self, super_selector: &Selector) -> SassResult<Value> {
         let stmts = self.eval_body(super_selector)?;
         self.call(super_selector, stmts)?
             .ok_or_else(|| ("Function finished without @return.", self.pos).into())
     }
 
-    pub fn call(
+    /// Evaluate a function that was defined in `module`
+    ///
+    /// Arguments are resolved in the scope of the caller, while the body
+    /// is evaluated in the global scope of the module
+    pub(crate) fn eval_in_module(
+        mut self,
+        args: CallArgs,
+        scope: &Scope,
+        super_selector: &Selector,
+        module: &Module,
+        name: Spanned<&Identifier>,
+    ) -> SassResult<Value> {
+        self.args(args, scope, super_selector)?;
+        module
+            .enter(|| self.eval_call(super_selector))
+            .map_err(|e| e.in_call(name.node, name.span))
+    }
+
+    pub(crate) fn call(
         &mut self,
         super_selector: &Selector,
         stmts: Vec<Spanned<Stmt>>,
@@ -132,9 +165,10 @@ impl Function {
         for stmt in stmts {
             match stmt.node {
                 Stmt::AtRule(AtRule::Return(toks)) => {
-                    return Ok(Some(
-                        Value::from_vec(toks, &self.scope, super_selector, stmt.span)?.node,
-                    ));
+                    // evaluated here rather than by the caller, so that errors
+                    // in the returned expression happen within this call
+                    let value = Value::from_vec(toks, &self.scope, super_selector, stmt.span)?;
+                    return Ok(Some(value.node.eval(value.span)?.node));
                 }
                 Stmt::AtRule(AtRule::For(f)) => {
                     for i in f.iter() {
@@ -0,0 +1,192 @@
+use codemap::{Span, Spanned};
+
+use peekmore::PeekMoreIterator;
+
+use crate::error::SassResult;
+use crate::scope::Scope;
+use crate::selector::Selector;
+use crate::utils::{
+    devour_whitespace, eat_ident_no_interpolation, parse_interpolation, parse_quoted_string,
+    read_until_closing_paren, read_until_closing_quote,
+};
+use crate::Token;
+
+/// A single url within an `@import` rule
+///
+/// `@import "theme", "print.css" print;` contains two imports
+#[derive(Debug, Clone)]
+pub(crate) enum Import {
+    /// A Sass stylesheet, which is loaded and evaluated in place
+    Sass(Spanned<String>),
+    /// A plain CSS import, which is emitted as-is
+    ///
+    /// `"print.css" print`, `url(theme.css)`
+    Css(String),
+}
+
+/// Parse the comma separated urls of an `@import` rule, along with the
+/// semicolon ending it
+pub(crate) fn parse_imports<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    scope: &Scope,
+    super_selector: &Selector,
+    span_before: Span,
+) -> SassResult<Vec<Import>> {
+    let mut imports = Vec::new();
+    loop {
+        devour_whitespace(toks);
+        imports.push(parse_import(toks, scope, super_selector, span_before)?);
+        // a url alone on its line may omit the semicolon
+        if let Some(Token { kind: '\n', .. }) = toks.peek() {
+            break;
+        }
+        devour_whitespace(toks);
+        match toks.peek() {
+            Some(Token { kind: ',', .. }) => {
+                toks.next();
+            }
+            Some(Token { kind: ';', .. }) => {
+                toks.next();
+                break;
+            }
+            Some(Token { kind: '}', .. }) | None => break,
+            Some(Token { pos, .. }) => return Err(("expected \";\".", *pos).into()),
+        }
+    }
+    devour_whitespace(toks);
+    Ok(imports)
+}
+
+fn parse_import<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    scope: &Scope,
+    super_selector: &Selector,
+    span_before: Span,
+) -> SassResult<Import> {
+    // `raw` is the url as it's written in a plain CSS import
+    let (url, raw, is_plain_url) = match toks.peek().cloned() {
+        Some(Token { kind: q @ '"', pos })
+        | Some(Token {
+            kind: q @ '\'',
+            pos,
+        }) => {
+            toks.next();
+            let Spanned { node, span } = parse_quoted_string(toks, scope, q, super_selector, pos)?;
+            let url = node.unquote().to_css_string(span)?.into_owned();
+            let raw = format!("{}{}{}", q, url, q);
+            let is_plain_url = is_plain_css_url(&url);
+            (
+                Spanned {
+                    node: url,
+                    span: pos.merge(span),
+                },
+                raw,
+                is_plain_url,
+            )
+        }
+        Some(Token { kind: 'u', pos }) | Some(Token { kind: 'U', pos }) => {
+            let name = eat_ident_no_interpolation(toks, false, pos)?;
+            if !name.node.eq_ignore_ascii_case("url") {
+                return Err(("Expected string.", name.span).into());
+            }
+            match toks.next() {
+                Some(Token { kind: '(', .. }) => {}
+                _ => return Err(("expected \"(\".", name.span).into()),
+            }
+            let args: String = read_until_closing_paren(toks)?
+                .into_iter()
+                .map(|tok| tok.kind)
+                .collect();
+            let url = format!("{}({}", name.node, args);
+            (
+                Spanned {
+                    node: url.clone(),
+                    span: name.span,
+                },
+                url,
+                true,
+            )
+        }
+        Some(Token { pos, .. }) => return Err(("Expected string.", pos).into()),
+        None => return Err(("Expected string.", span_before).into()),
+    };
+
+    if let Some(Token { kind: '\n', .. }) = toks.peek() {
+        return Ok(if is_plain_url {
+            Import::Css(raw)
+        } else {
+            Import::Sass(url)
+        });
+    }
+    devour_whitespace(toks);
+    let modifiers = parse_modifiers(toks, scope, super_selector)?;
+
+    if !is_plain_url && modifiers.is_empty() {
+        return Ok(Import::Sass(url));
+    }
+
+    let mut css = raw;
+    if !modifiers.is_empty() {
+        css.push(' ');
+        css.push_str(&modifiers);
+    }
+    Ok(Import::Css(css))
+}
+
+/// Whether an import of `url` is always a plain CSS import
+fn is_plain_css_url(url: &str) -> bool {
+    url.ends_with(".css")
+        || url.starts_with("http://")
+        || url.starts_with("https://")
+        || url.starts_with("//")
+}
+
+/// Read the media queries or `supports()` condition following an import's
+/// url
+fn parse_modifiers<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    scope: &Scope,
+    super_selector: &Selector,
+) -> SassResult<String> {
+    let mut modifiers = String::new();
+    while let Some(tok) = toks.peek().cloned() {
+        match tok.kind {
+            ',' | ';' | '}' => break,
+            '#' => {
+                toks.next();
+                if let Some(Token { kind: '{', pos }) = toks.peek().cloned() {
+                    toks.next();
+                    let interpolation = parse_interpolation(toks, scope, super_selector, pos)?;
+                    modifiers.push_str(&interpolation.node.to_css_string(interpolation.span)?);
+                } else {
+                    modifiers.push('#');
+                }
+                continue;
+            }
+            '(' => {
+                toks.next();
+                modifiers.push('(');
+                modifiers.extend(read_until_closing_paren(toks)?.into_iter().map(|t| t.kind));
+                continue;
+            }
+            q @ '"' | q @ '\'' => {
+                toks.next();
+                modifiers.push(q);
+                modifiers.extend(
+                    read_until_closing_quote(toks, q)?
+                        .into_iter()
+                        .map(|t| t.kind),
+                );
+                continue;
+            }
+            '\n' | ' ' | '\t' => {
+                devour_whitespace(toks);
+                modifiers.push(' ');
+                continue;
+            }
+            c => modifiers.push(c),
+        }
+        toks.next();
+    }
+    Ok(modifiers.trim_end().to_owned())
+}
@@ -0,0 +1,225 @@
+use std::rc::Rc;
+
+use codemap::{Span, Spanned};
+
+use peekmore::PeekMoreIterator;
+
+use super::parse::ruleset_eval;
+use crate::context::{current, State};
+use crate::error::SassResult;
+use crate::scope::Scope;
+use crate::selector::Selector;
+use crate::utils::{devour_whitespace, parse_interpolation};
+use crate::{Stmt, Token};
+
+/// A parsed `@keyframes` rule, or a vendor prefixed variant such as
+/// `@-webkit-keyframes`
+#[derive(Debug, Clone)]
+pub(crate) struct Keyframes {
+    /// The name of the at-rule itself, e.g. `keyframes`
+    pub name: String,
+    /// The name of the animation
+    pub params: String,
+    /// The keyframe blocks, along with anything else written directly
+    /// within the rule, which is only checked when printing
+    pub body: Vec<Spanned<Stmt>>,
+}
+
+/// A single block within `@keyframes`
+///
+/// `from, 50% { ... }`
+#[derive(Debug, Clone)]
+pub(crate) struct KeyframesRuleSet {
+    pub selector: Vec<String>,
+    pub body: Vec<Spanned<Stmt>>,
+}
+
+impl Keyframes {
+    pub fn from_tokens<I: Iterator<Item = Token>>(
+        toks: &mut PeekMoreIterator<I>,
+        name: String,
+        scope: &mut Scope,
+        super_selector: &Selector,
+        kind_span: Span,
+        content: Option<&[Spanned<Stmt>]>,
+    ) -> SassResult<Keyframes> {
+        let params = interpolated_text(toks, scope, super_selector)?;
+        if params.is_empty() {
+            return Err(("Expected identifier.", kind_span).into());
+        }
+        toks.next();
+
+        // keyframe selectors are never combined with the parent selector
+        let mut body = Vec::new();
+        with_keyframes(true, || {
+            ruleset_eval(toks, scope, &Selector::new(), false, content, &mut body)
+        })?;
+        devour_whitespace(toks);
+
+        Ok(Keyframes { name, params, body })
+    }
+}
+
+/// Run `f` with blocks read as keyframe blocks if `in_keyframes`, or as
+/// style rules otherwise
+///
+/// The outer setting is restored however `f` is left, even if it panics
+fn with_keyframes<T, F: FnOnce() -> SassResult<T>>(in_keyframes: bool, f: F) -> SassResult<T> {
+    struct Restore(Rc<State>, bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            self.0.in_keyframes.set(self.1);
+        }
+    }
+
+    let state = current();
+    let outer = state.in_keyframes.replace(in_keyframes);
+    let _restore = Restore(state, outer);
+    f()
+}
+
+/// Whether blocks are currently read as keyframe blocks, because they are
+/// directly within `@keyframes`
+pub(crate) fn in_keyframes() -> bool {
+    current().in_keyframes.get()
+}
+
+/// Parse the selector of a keyframe block, up to its `{`
+///
+/// `from, 50%`
+pub(crate) fn parse_keyframes_selector<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    scope: &Scope,
+    super_selector: &Selector,
+    span: Span,
+) -> SassResult<Vec<String>> {
+    interpolated_text(toks, scope, super_selector)?
+        .split(',')
+        .map(|selector| parse_keyframe_selector(selector, span))
+        .collect()
+}
+
+/// Evaluate the body of a keyframe block once its selector and `{` have
+/// been read
+///
+/// Blocks within the body are style rules again, which aren't allowed there
+pub(crate) fn eat_keyframe_block<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    scope: &mut Scope,
+    selector: Vec<String>,
+    content: Option<&[Spanned<Stmt>]>,
+) -> SassResult<Stmt> {
+    let mut body = Vec::new();
+    with_keyframes(false, || {
+        ruleset_eval(toks, scope, &Selector::new(), false, content, &mut body)
+    })?;
+    Ok(Stmt::KeyframesRuleSet(Box::new(KeyframesRuleSet {
+        selector,
+        body,
+    })))
+}
+
+/// Whether an at-rule name is `keyframes` with a vendor prefix
+///
+/// `-webkit-keyframes`
+pub(crate) fn is_vendor_keyframes(name: &str) -> bool {
+    name.starts_with('-') && name[1..].ends_with("-keyframes")
+}
+
+/// Read up to, but not including, the `{` that opens a block, resolving any
+/// interpolation and collapsing whitespace
+fn interpolated_text<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    scope: &Scope,
+    super_selector: &Selector,
+) -> SassResult<String> {
+    let mut text = String::new();
+    while let Some(tok) = toks.peek().cloned() {
+        match tok.kind {
+            '{' => break,
+            '#' => {
+                toks.next();
+                if let Some(Token { kind: '{', pos }) = toks.peek().cloned() {
+                    toks.next();
+                    let interpolation = parse_interpolation(toks, scope, super_selector, pos)?;
+                    text.push_str(&interpolation.node.to_css_string(interpolation.span)?);
+                } else {
+                    text.push('#');
+                }
+                continue;
+            }
+            '\n' | ' ' | '\t' => {
+                devour_whitespace(toks);
+                text.push(' ');
+                continue;
+            }
+            _ => text.push(tok.kind),
+        }
+        toks.next();
+    }
+    Ok(text.trim().to_owned())
+}
+
+/// Parse a single keyframe selector, either `from`, `to`, or a percentage
+fn parse_keyframe_selector(selector: &str, span: Span) -> SassResult<String> {
+    let selector = selector.trim();
+    let lower = selector.to_ascii_lowercase();
+    if lower == "from" || lower == "to" {
+        return Ok(lower);
+    }
+
+    let mut chars = selector.chars().peekable();
+    match chars.peek() {
+        Some(c) if c.is_alphabetic() => return Err(("Expected \"to\" or \"from\".", span).into()),
+        Some('+') | Some('-') => {
+            chars.next();
+        }
+        _ => {}
+    }
+
+    let mut has_digits = false;
+    while let Some(c) = chars.peek() {
+        if !c.is_ascii_digit() {
+            break;
+        }
+        has_digits = true;
+        chars.next();
+    }
+    if let Some('.') = chars.peek() {
+        chars.next();
+        has_digits = false;
+        while let Some(c) = chars.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            has_digits = true;
+            chars.next();
+        }
+    }
+    if !has_digits {
+        return Err(("Expected number.", span).into());
+    }
+
+    if let Some('e') | Some('E') = chars.peek() {
+        chars.next();
+        if let Some('+') | Some('-') = chars.peek() {
+            chars.next();
+        }
+        match chars.peek() {
+            Some(c) if c.is_ascii_digit() => {}
+            _ => return Err(("Expected digit.", span).into()),
+        }
+        while let Some(c) = chars.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            chars.next();
+        }
+    }
+
+    match chars.next() {
+        Some('%') if chars.next().is_none() => Ok(selector.to_owned()),
+        _ => Err(("expected \"%\".", span).into()),
+    }
+}
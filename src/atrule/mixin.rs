@@ -8,8 +8,10 @@ use peekmore::{PeekMore, PeekMoreIterator};
 use super::ruleset_eval;
 
 use crate::args::{eat_call_args, eat_func_args, CallArgs, FuncArgs};
-use crate::atrule::AtRule;
+use crate::atrule::{eat_keyframe_block, AtRule};
+use crate::common::Identifier;
 use crate::error::SassResult;
+use crate::modules::{get_global_module_mixin, get_module};
 use crate::scope::Scope;
 use crate::selector::Selector;
 use crate::utils::{
@@ -70,7 +72,7 @@ impl Mixin {
                 self.scope.insert_var(
                     mem::take(&mut arg.name),
                     Spanned {
-                        node: Value::ArgList(args.get_variadic(scope, super_selector)?),
+                        node: Value::arg_list(args.get_variadic(scope, super_selector)?),
                         span,
                     },
                 )?;
@@ -92,12 +94,15 @@ impl Mixin {
         Ok(self)
     }
 
+    /// Include this mixin, which is named `name` at the `@include`
     pub fn call(
         mut self,
         super_selector: &Selector,
         content: Option<&[Spanned<Stmt>]>,
+        name: Spanned<&Identifier>,
     ) -> SassResult<Vec<Spanned<Stmt>>> {
         self.eval(super_selector, content)
+            .map_err(|e| e.in_call(name.node, name.span))
     }
 
     fn eval(
@@ -132,7 +137,6 @@ impl Mixin {
                     AtRule::Return(..) => {
                         return Err(("This at-rule is not allowed here.", span).into())
                     }
-                    AtRule::Debug(..) | AtRule::Warn(..) => todo!(),
                     r => stmts.push(Spanned {
                         node: Stmt::AtRule(r),
                         span,
@@ -165,6 +169,10 @@ impl Mixin {
                         span,
                     });
                 }
+                Expr::KeyframesRuleSet(selector) => stmts.push(Spanned {
+                    node: eat_keyframe_block(&mut self.body, &mut self.scope, selector, content)?,
+                    span,
+                }),
                 Expr::VariableDecl(name, val) => {
                     self.scope.insert_var(&name, *val)?;
                 }
@@ -186,7 +194,15 @@ pub(crate) fn eat_include<I: Iterator<Item = Token>>(
     span_before: Span,
 ) -> SassResult<Vec<Spanned<Stmt>>> {
     devour_whitespace_or_comment(toks)?;
-    let name = eat_ident(toks, scope, super_selector, span_before)?;
+    let mut name = eat_ident(toks, scope, super_selector, span_before)?;
+
+    let namespace = if let Some(Token { kind: '.', .. }) = toks.peek() {
+        toks.next();
+        let member = eat_ident(toks, scope, super_selector, name.span)?;
+        Some(mem::replace(&mut name, member))
+    } else {
+        None
+    };
 
     devour_whitespace_or_comment(toks)?;
 
@@ -249,10 +265,37 @@ pub(crate) fn eat_include<I: Iterator<Item = Token>>(
         }
     }
 
-    let mixin = scope.get_mixin(name)?;
+    let ident = Spanned {
+        node: Identifier::from(&name.node),
+        span: name.span,
+    };
+
+    let (mixin, module) = match namespace {
+        Some(namespace) => {
+            let module = get_module(Spanned {
+                node: &namespace.node,
+                span: namespace.span,
+            })?;
+            let (mixin, module) = module.get_mixin(ident.clone())?;
+            (mixin, Some(module))
+        }
+        None => match scope.get_mixin(name.clone()) {
+            Ok(mixin) => (mixin, None),
+            Err(e) => match get_global_module_mixin(&ident.node) {
+                Some((mixin, module)) => (mixin, Some(module)),
+                None => return Err(e),
+            },
+        },
+    };
+
+    let mixin = mixin.args(args, scope, super_selector)?;
+    let name = Spanned {
+        node: &ident.node,
+        span: ident.span,
+    };
 
-    let rules = mixin
-        .args(args, scope, super_selector)?
-        .call(super_selector, Some(&this_content))?;
-    Ok(rules)
+    match module {
+        Some(module) => module.enter(|| mixin.call(super_selector, Some(&this_content), name)),
+        None => mixin.call(super_selector, Some(&this_content), name),
+    }
 }
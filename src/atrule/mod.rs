@@ -2,7 +2,8 @@ use codemap::{Span, Spanned};
 
 use peekmore::{PeekMore, PeekMoreIterator};
 
-use crate::error::SassResult;
+use crate::error::{SassError, SassResult};
+use crate::logger::{log, LogKind};
 use crate::scope::Scope;
 use crate::selector::Selector;
 use crate::utils::{
@@ -13,31 +14,48 @@ use crate::value::Value;
 use crate::{RuleSet, Stmt, Token};
 
 use each_rule::{parse_each, Each};
+pub(crate) use extend_rule::Extend;
 use for_rule::For;
+pub(crate) use forward_rule::Forward;
 pub(crate) use function::Function;
 pub(crate) use if_rule::If;
+pub(crate) use import_rule::{parse_imports, Import};
+pub(crate) use keyframes::KeyframesRuleSet;
+pub(crate) use keyframes::{
+    eat_keyframe_block, in_keyframes, is_vendor_keyframes, parse_keyframes_selector, Keyframes,
+};
 pub(crate) use kind::AtRuleKind;
-use media::Media;
+pub(crate) use media::Media;
 pub(crate) use mixin::{eat_include, Mixin};
 use parse::{eat_stmts, eat_stmts_at_root, ruleset_eval};
-use unknown::UnknownAtRule;
+pub(crate) use supports::Supports;
+pub(crate) use unknown::UnknownAtRule;
+pub(crate) use use_rule::Use;
 use while_rule::{parse_while, While};
 
 mod each_rule;
+mod extend_rule;
 mod for_rule;
+mod forward_rule;
 mod function;
 mod if_rule;
+mod import_rule;
+mod keyframes;
 mod kind;
 mod media;
 mod mixin;
 mod parse;
+mod supports;
 mod unknown;
+mod use_rule;
 mod while_rule;
 
 #[derive(Debug, Clone)]
 pub(crate) enum AtRule {
-    Warn(Spanned<String>),
-    Debug(Spanned<String>),
+    /// `@warn`, whose message has already been logged
+    Warn,
+    /// `@debug`, whose message has already been logged
+    Debug,
     Mixin(String, Box<Mixin>),
     Function(String, Box<Function>),
     Return(Vec<Token>),
@@ -50,7 +68,13 @@ pub(crate) enum AtRule {
     Include(Vec<Spanned<Stmt>>),
     If(If),
     Media(Media),
+    Supports(Supports),
+    Keyframes(Keyframes),
     AtRoot(Vec<Spanned<Stmt>>),
+    Extend(Extend),
+    /// The plain CSS imports of a single `@import` rule, which are emitted
+    /// as-is
+    Import(Vec<String>),
 }
 
 impl AtRule {
@@ -75,7 +99,10 @@ impl AtRule {
                     kind_span,
                 )?;
 
-                return Err((message.inspect(span)?.to_string(), span.merge(kind_span)).into());
+                return Err(SassError::runtime(
+                    message.inspect(span)?,
+                    span.merge(kind_span),
+                ));
             }
             AtRuleKind::Warn => {
                 let Spanned {
@@ -87,17 +114,18 @@ impl AtRule {
                     super_selector,
                     kind_span,
                 )?;
-                span.merge(kind_span);
+                log(
+                    LogKind::Warning,
+                    kind_span.merge(span),
+                    message.to_css_string(span)?.into(),
+                );
                 if let Some(Token { kind: ';', .. }) = toks.peek() {
-                    kind_span.merge(toks.next().unwrap().pos());
+                    toks.next();
                 }
                 devour_whitespace(toks);
                 Spanned {
-                    node: AtRule::Warn(Spanned {
-                        node: message.to_css_string(span)?.into(),
-                        span,
-                    }),
-                    span,
+                    node: AtRule::Warn,
+                    span: kind_span.merge(span),
                 }
             }
             AtRuleKind::Debug => {
@@ -110,17 +138,18 @@ impl AtRule {
                     super_selector,
                     kind_span,
                 )?;
-                span.merge(kind_span);
+                log(
+                    LogKind::Debug,
+                    kind_span.merge(span),
+                    message.inspect(span)?.into(),
+                );
                 if let Some(Token { kind: ';', .. }) = toks.peek() {
-                    kind_span.merge(toks.next().unwrap().pos());
+                    toks.next();
                 }
                 devour_whitespace(toks);
                 Spanned {
-                    node: AtRule::Debug(Spanned {
-                        node: message.inspect(span)?.into(),
-                        span,
-                    }),
-                    span,
+                    node: AtRule::Debug,
+                    span: kind_span.merge(span),
                 }
             }
             AtRuleKind::Mixin => {
@@ -227,6 +256,17 @@ impl AtRule {
                 span: kind_span,
             },
             AtRuleKind::While => parse_while(toks, kind_span)?,
+            AtRuleKind::Unknown(name) if is_vendor_keyframes(&name) => Spanned {
+                node: AtRule::Keyframes(Keyframes::from_tokens(
+                    toks,
+                    name,
+                    scope,
+                    super_selector,
+                    kind_span,
+                    content,
+                )?),
+                span: kind_span,
+            },
             AtRuleKind::Unknown(name) => Spanned {
                 node: AtRule::Unknown(UnknownAtRule::from_tokens(
                     toks,
@@ -262,12 +302,56 @@ impl AtRule {
                 )?),
                 span: kind_span,
             },
-            AtRuleKind::Import => todo!("@import not yet implemented"),
-            AtRuleKind::Forward => todo!("@forward not yet implemented"),
-            AtRuleKind::Supports => todo!("@supports not yet implemented"),
-            AtRuleKind::Keyframes => todo!("@keyframes not yet implemented"),
-            AtRuleKind::Extend => todo!("@extend not yet implemented"),
-            AtRuleKind::Use => todo!("@use not yet implemented"),
+            AtRuleKind::Import => {
+                let mut css_imports = Vec::new();
+                for import in parse_imports(toks, scope, super_selector, kind_span)? {
+                    match import {
+                        Import::Css(css) => css_imports.push(css),
+                        // todo: nested imports need the code map and options,
+                        // which are only available at the top level
+                        Import::Sass(url) => {
+                            return Err((
+                                "Nested imports of Sass stylesheets aren't supported yet.",
+                                url.span,
+                            )
+                                .into())
+                        }
+                    }
+                }
+                Spanned {
+                    node: AtRule::Import(css_imports),
+                    span: kind_span,
+                }
+            }
+            AtRuleKind::Forward => {
+                return Err(("This at-rule is not allowed here.", kind_span).into())
+            }
+            AtRuleKind::Supports => Spanned {
+                node: AtRule::Supports(Supports::from_tokens(
+                    toks,
+                    scope,
+                    super_selector,
+                    kind_span,
+                    content,
+                )?),
+                span: kind_span,
+            },
+            AtRuleKind::Keyframes => Spanned {
+                node: AtRule::Keyframes(Keyframes::from_tokens(
+                    toks,
+                    "keyframes".to_owned(),
+                    scope,
+                    super_selector,
+                    kind_span,
+                    content,
+                )?),
+                span: kind_span,
+            },
+            AtRuleKind::Extend => Spanned {
+                node: AtRule::Extend(Extend::from_tokens(toks, scope, super_selector, kind_span)?),
+                span: kind_span,
+            },
+            AtRuleKind::Use => return Err(("This at-rule is not allowed here.", kind_span).into()),
         })
     }
 }
@@ -2,7 +2,7 @@ use codemap::Spanned;
 
 use peekmore::PeekMoreIterator;
 
-use super::AtRule;
+use super::{eat_keyframe_block, AtRule};
 
 use crate::error::SassResult;
 use crate::scope::{global_var_exists, insert_global_var, Scope};
@@ -30,12 +30,14 @@ pub(crate) fn eat_stmts<I: Iterator<Item = Token>>(
             ),
             Expr::MixinDecl(..) | Expr::FunctionDecl(..) => todo!(),
             Expr::Selector(selector) => {
-                let rules = eat_stmts(
+                let mut rules = Vec::new();
+                ruleset_eval(
                     toks,
                     scope,
                     &super_selector.zip(&selector),
                     at_root,
                     content,
+                    &mut rules,
                 )?;
                 stmts.push(
                     Stmt::RuleSet(RuleSet {
@@ -46,6 +48,9 @@ pub(crate) fn eat_stmts<I: Iterator<Item = Token>>(
                     .span(span),
                 );
             }
+            Expr::KeyframesRuleSet(selector) => {
+                stmts.push(eat_keyframe_block(toks, scope, selector, content)?.span(span))
+            }
             //TODO: refactor handling of `Expr::VariableDecl`, as most is already handled in `eat_expr`
             Expr::VariableDecl(name, val) => {
                 if at_root && global_var_exists(&name) {
@@ -102,6 +107,9 @@ pub(crate) fn eat_stmts_at_root<I: Iterator<Item = Token>>(
                     .span(span),
                 );
             }
+            Expr::KeyframesRuleSet(selector) => {
+                stmts.push(eat_keyframe_block(toks, scope, selector, content)?.span(span))
+            }
             Expr::VariableDecl(name, val) => {
                 scope.insert_var(&name, *val)?;
             }
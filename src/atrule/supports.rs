@@ -0,0 +1,248 @@
+use codemap::{Span, Spanned};
+
+use peekmore::PeekMoreIterator;
+
+use super::parse::ruleset_eval;
+use crate::error::SassResult;
+use crate::scope::Scope;
+use crate::selector::Selector;
+use crate::utils::{
+    devour_whitespace, eat_ident_no_interpolation, is_name_start, parse_interpolation,
+    read_until_closing_paren,
+};
+use crate::value::Value;
+use crate::{RuleSet, Stmt, Token};
+
+#[derive(Debug, Clone)]
+pub(crate) struct Supports {
+    pub params: String,
+    pub body: Vec<Spanned<Stmt>>,
+}
+
+impl Supports {
+    pub fn from_tokens<I: Iterator<Item = Token>>(
+        toks: &mut PeekMoreIterator<I>,
+        scope: &mut Scope,
+        super_selector: &Selector,
+        kind_span: Span,
+        content: Option<&[Spanned<Stmt>]>,
+    ) -> SassResult<Supports> {
+        let params = parse_condition(toks, scope, super_selector, kind_span)?;
+        devour_whitespace(toks);
+        match toks.next() {
+            Some(Token { kind: '{', .. }) => {}
+            Some(Token { pos, .. }) => return Err(("expected \"{\".", pos).into()),
+            None => return Err(("expected \"{\".", kind_span).into()),
+        }
+
+        let mut raw_body = Vec::new();
+        ruleset_eval(toks, scope, super_selector, false, content, &mut raw_body)?;
+        let mut rules = Vec::with_capacity(raw_body.len());
+        let mut body = Vec::new();
+
+        for stmt in raw_body {
+            match stmt.node {
+                Stmt::Style(..) => body.push(stmt),
+                _ => rules.push(stmt),
+            }
+        }
+
+        if super_selector.is_empty() {
+            body.append(&mut rules);
+        } else {
+            body = vec![Spanned {
+                node: Stmt::RuleSet(RuleSet {
+                    selector: super_selector.clone(),
+                    rules: body,
+                    super_selector: Selector::new(),
+                }),
+                span: kind_span,
+            }];
+            body.append(&mut rules);
+        }
+
+        Ok(Supports { params, body })
+    }
+}
+
+/// Parse a supports condition up to, but not including, the `{` or `)` that
+/// ends it
+///
+/// `not (display: grid)`, `(display: grid) and (gap: 1px)`
+fn parse_condition<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    scope: &Scope,
+    super_selector: &Selector,
+    span: Span,
+) -> SassResult<String> {
+    devour_whitespace(toks);
+    let mut condition = match toks.peek() {
+        Some(Token { kind, pos }) if is_name_start(*kind) => {
+            let pos = *pos;
+            let name = eat_ident_no_interpolation(toks, false, pos)?.node;
+            if name.eq_ignore_ascii_case("not") {
+                devour_whitespace(toks);
+                return Ok(format!(
+                    "not {}",
+                    parse_in_parens(toks, scope, super_selector, pos)?
+                ));
+            }
+            parse_function(name, toks, pos)?
+        }
+        _ => parse_in_parens(toks, scope, super_selector, span)?,
+    };
+
+    // `and` and `or` can't be mixed without parentheses, so every operator
+    // must be the same as the first
+    let mut first_operator: Option<String> = None;
+    loop {
+        devour_whitespace(toks);
+        let pos = match toks.peek() {
+            Some(Token { kind: '{', .. }) | Some(Token { kind: ')', .. }) | None => break,
+            Some(Token { pos, .. }) => *pos,
+        };
+        let operator = eat_ident_no_interpolation(toks, false, pos)?;
+        let operator = operator.node.to_ascii_lowercase();
+        match &first_operator {
+            Some(first) if *first != operator => {
+                return Err((format!("expected \"{}\".", first), pos).into())
+            }
+            Some(..) => {}
+            None if operator == "and" || operator == "or" => {
+                first_operator = Some(operator.clone())
+            }
+            None => return Err(("expected \"and\" or \"or\".", pos).into()),
+        }
+        devour_whitespace(toks);
+        condition.push(' ');
+        condition.push_str(&operator);
+        condition.push(' ');
+        condition.push_str(&parse_in_parens(toks, scope, super_selector, pos)?);
+    }
+
+    Ok(condition)
+}
+
+/// Parse a parenthesized condition, a declaration, or an interpolated
+/// condition
+///
+/// `(not (display: grid))`, `(display: $display)`, `#{$condition}`
+fn parse_in_parens<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    scope: &Scope,
+    super_selector: &Selector,
+    span: Span,
+) -> SassResult<String> {
+    let pos = match toks.peek().cloned() {
+        Some(Token { kind: '(', pos }) => {
+            toks.next();
+            pos
+        }
+        Some(Token { kind: '#', pos }) => {
+            toks.next();
+            return match toks.next() {
+                Some(Token { kind: '{', pos }) => {
+                    let interpolation = parse_interpolation(toks, scope, super_selector, pos)?;
+                    Ok(interpolation.node.to_css_string(interpolation.span)?.into())
+                }
+                _ => Err(("expected \"(\".", pos).into()),
+            };
+        }
+        Some(Token { kind, pos }) if is_name_start(kind) => {
+            let name = eat_ident_no_interpolation(toks, false, pos)?.node;
+            return parse_function(name, toks, pos);
+        }
+        Some(Token { pos, .. }) => return Err(("expected \"(\".", pos).into()),
+        None => return Err(("expected \"(\".", span).into()),
+    };
+    devour_whitespace(toks);
+
+    let mut name = String::new();
+    match toks.peek().cloned() {
+        // a nested condition, rather than a declaration
+        Some(Token { kind: '(', .. }) => {
+            let condition = parse_condition(toks, scope, super_selector, pos)?;
+            expect_closing_paren(toks, pos)?;
+            return Ok(format!("({})", condition));
+        }
+        Some(Token { kind, pos }) if is_name_start(kind) => {
+            name = eat_ident_no_interpolation(toks, false, pos)?.node;
+            devour_whitespace(toks);
+            if name.eq_ignore_ascii_case("not") {
+                if let Some(Token { kind: '(', .. }) = toks.peek() {
+                    let condition = parse_in_parens(toks, scope, super_selector, pos)?;
+                    expect_closing_paren(toks, pos)?;
+                    return Ok(format!("(not {})", condition));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    loop {
+        match toks.next() {
+            Some(Token { kind: ':', .. }) => break,
+            Some(Token { kind: '#', .. }) => {
+                if let Some(Token { kind: '{', pos }) = toks.peek().cloned() {
+                    toks.next();
+                    let interpolation = parse_interpolation(toks, scope, super_selector, pos)?;
+                    name.push_str(&interpolation.node.to_css_string(interpolation.span)?);
+                } else {
+                    name.push('#');
+                }
+            }
+            Some(Token { kind: ')', pos }) | Some(Token { kind: '{', pos }) => {
+                return Err(("expected \":\".", pos).into())
+            }
+            Some(Token { kind, .. }) => name.push(kind),
+            None => return Err(("expected \":\".", pos).into()),
+        }
+    }
+    devour_whitespace(toks);
+
+    let mut value = read_until_closing_paren(toks)?;
+    let end = match value.pop() {
+        Some(Token { kind: ')', pos }) => pos,
+        _ => return Err(("expected \")\".", pos).into()),
+    };
+    let value = Value::from_vec(value, scope, super_selector, end)?;
+
+    Ok(format!(
+        "({}: {})",
+        name.trim(),
+        value.node.to_css_string(value.span)?
+    ))
+}
+
+/// Parse a function call such as `selector(.a > .b)`, whose arguments are
+/// passed through untouched
+fn parse_function<I: Iterator<Item = Token>>(
+    name: String,
+    toks: &mut PeekMoreIterator<I>,
+    span: Span,
+) -> SassResult<String> {
+    match toks.next() {
+        Some(Token { kind: '(', .. }) => {}
+        _ => return Err(("expected \"(\".", span).into()),
+    }
+    let args: String = read_until_closing_paren(toks)?
+        .into_iter()
+        .map(|tok| tok.kind)
+        .collect();
+    if !args.ends_with(')') {
+        return Err(("expected \")\".", span).into());
+    }
+    Ok(format!("{}({}", name, args))
+}
+
+fn expect_closing_paren<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    span: Span,
+) -> SassResult<()> {
+    devour_whitespace(toks);
+    match toks.next() {
+        Some(Token { kind: ')', .. }) => Ok(()),
+        Some(Token { pos, .. }) => Err(("expected \")\".", pos).into()),
+        None => Err(("expected \")\".", span).into()),
+    }
+}
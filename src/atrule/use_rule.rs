@@ -0,0 +1,242 @@
+use codemap::{Span, Spanned};
+
+use peekmore::PeekMoreIterator;
+
+use crate::error::SassResult;
+use crate::modules::{Configuration, Namespace};
+use crate::scope::Scope;
+use crate::selector::Selector;
+use crate::utils::{
+    devour_whitespace, devour_whitespace_or_comment, eat_ident_no_interpolation, is_ident,
+    parse_quoted_string, read_until_closing_paren, read_until_closing_quote,
+    read_until_closing_square_brace,
+};
+use crate::value::Value;
+use crate::Token;
+
+/// A parsed `@use` rule
+///
+/// `@use "src/corners" as c with ($radius: 3px);`
+#[derive(Debug, Clone)]
+pub(crate) struct Use {
+    pub url: Spanned<String>,
+    pub namespace: Spanned<Namespace>,
+    pub config: Configuration,
+}
+
+impl Use {
+    pub fn from_tokens<I: Iterator<Item = Token>>(
+        toks: &mut PeekMoreIterator<I>,
+        span_before: Span,
+    ) -> SassResult<Use> {
+        let url = parse_url(toks, span_before)?;
+
+        devour_whitespace_or_comment(toks)?;
+
+        let mut namespace = None;
+        let mut config = None;
+
+        while let Some(Token { kind, .. }) = toks.peek() {
+            if !kind.is_ascii_alphabetic() {
+                break;
+            }
+            let ident = eat_ident_no_interpolation(toks, false, url.span)?;
+            devour_whitespace_or_comment(toks)?;
+            match ident.node.to_ascii_lowercase().as_str() {
+                "as" if namespace.is_none() && config.is_none() => {
+                    namespace = Some(parse_namespace(toks, ident.span)?);
+                }
+                "with" if config.is_none() => {
+                    config = Some(parse_configuration(toks, ident.span, false)?);
+                }
+                _ => return Err(("expected \";\".", ident.span).into()),
+            }
+            devour_whitespace_or_comment(toks)?;
+        }
+
+        expect_semicolon(toks)?;
+
+        let namespace = match namespace {
+            Some(n) => n,
+            None => default_namespace(&url)?,
+        };
+
+        Ok(Use {
+            url,
+            namespace,
+            config: config.unwrap_or_default(),
+        })
+    }
+}
+
+pub(super) fn parse_url<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    span_before: Span,
+) -> SassResult<Spanned<String>> {
+    devour_whitespace(toks);
+    match toks.next() {
+        Some(Token { kind: q @ '"', pos })
+        | Some(Token {
+            kind: q @ '\'',
+            pos,
+        }) => {
+            let Spanned { node, span } =
+                parse_quoted_string(toks, &Scope::new(), q, &Selector::new(), pos)?;
+            Ok(Spanned {
+                node: node.unquote().to_css_string(span)?.into_owned(),
+                span: pos.merge(span),
+            })
+        }
+        Some(Token { pos, .. }) => Err(("Expected string.", pos).into()),
+        None => Err(("Expected string.", span_before).into()),
+    }
+}
+
+pub(super) fn expect_semicolon<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+) -> SassResult<()> {
+    match toks.peek() {
+        Some(Token { kind: ';', .. }) => {
+            toks.next();
+        }
+        Some(Token { pos, .. }) => return Err(("expected \";\".", *pos).into()),
+        None => {}
+    }
+    Ok(())
+}
+
+fn parse_namespace<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    span_before: Span,
+) -> SassResult<Spanned<Namespace>> {
+    match toks.peek() {
+        Some(Token { kind: '*', pos }) => {
+            let pos = *pos;
+            toks.next();
+            Ok(Spanned {
+                node: Namespace::Global,
+                span: pos,
+            })
+        }
+        Some(..) => {
+            let name = eat_ident_no_interpolation(toks, false, span_before)?;
+            Ok(name.map_node(Namespace::Named))
+        }
+        None => Err(("Expected identifier.", span_before).into()),
+    }
+}
+
+/// Parse the variables in a `with (...)` clause
+///
+/// Values are evaluated immediately, in the scope of the loading stylesheet.
+/// Only `@forward` allows values to be marked `!default`.
+pub(super) fn parse_configuration<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    span_before: Span,
+    allow_default: bool,
+) -> SassResult<Configuration> {
+    let mut config = Configuration::default();
+
+    match toks.next() {
+        Some(Token { kind: '(', .. }) => {}
+        Some(Token { pos, .. }) => return Err(("expected \"(\".", pos).into()),
+        None => return Err(("expected \"(\".", span_before).into()),
+    }
+
+    loop {
+        devour_whitespace_or_comment(toks)?;
+        let span_before = match toks.next() {
+            Some(Token { kind: ')', .. }) if !config.is_empty() => break,
+            Some(Token { kind: '$', pos }) => pos,
+            Some(Token { pos, .. }) => return Err(("expected \"$\".", pos).into()),
+            None => return Err(("expected \"$\".", span_before).into()),
+        };
+        let name = eat_ident_no_interpolation(toks, false, span_before)?;
+        devour_whitespace_or_comment(toks)?;
+        match toks.next() {
+            Some(Token { kind: ':', .. }) => {}
+            Some(Token { pos, .. }) => return Err(("expected \":\".", pos).into()),
+            None => return Err(("expected \":\".", name.span).into()),
+        }
+        devour_whitespace_or_comment(toks)?;
+
+        let mut val_toks = Vec::new();
+        let mut is_last = false;
+        let mut is_default = false;
+        while let Some(tok) = toks.next() {
+            match tok.kind {
+                ',' => break,
+                '!' if allow_default => {
+                    let flag = eat_ident_no_interpolation(toks, false, tok.pos)?;
+                    if !flag.node.eq_ignore_ascii_case("default") {
+                        return Err(("Invalid flag name.", flag.span).into());
+                    }
+                    is_default = true;
+                    devour_whitespace_or_comment(toks)?;
+                }
+                ')' => {
+                    is_last = true;
+                    break;
+                }
+                '(' => {
+                    val_toks.push(tok);
+                    val_toks.extend(read_until_closing_paren(toks)?);
+                }
+                '[' => {
+                    val_toks.push(tok);
+                    val_toks.extend(read_until_closing_square_brace(toks)?);
+                }
+                q @ '"' | q @ '\'' => {
+                    val_toks.push(tok);
+                    val_toks.extend(read_until_closing_quote(toks, q)?);
+                }
+                _ => val_toks.push(tok),
+            }
+        }
+
+        let value = Value::from_vec(val_toks, &Scope::new(), &Selector::new(), name.span)?;
+        let span = name.span.merge(value.span);
+        config.insert(
+            Spanned {
+                node: name.node.into(),
+                span,
+            },
+            value.node.span(span),
+            is_default,
+        )?;
+
+        if is_last {
+            break;
+        }
+    }
+
+    Ok(config)
+}
+
+/// The namespace of a module loaded without an `as` clause is the last
+/// component of its URL, without any leading underscore or file extension
+fn default_namespace(url: &Spanned<String>) -> SassResult<Spanned<Namespace>> {
+    let basename = url.node.rsplit(|c| c == '/' || c == ':').next().unwrap();
+    let basename = basename.split('.').next().unwrap();
+    let basename = if basename.starts_with('_') {
+        &basename[1..]
+    } else {
+        basename
+    };
+
+    if !is_ident(basename) {
+        return Err((
+            format!(
+                "The default namespace \"{}\" is not a valid Sass identifier.\n\nRecommendation: add an \"as\" clause to define an explicit namespace.",
+                basename
+            ),
+            url.span,
+        )
+            .into());
+    }
+
+    Ok(Spanned {
+        node: Namespace::Named(basename.to_owned()),
+        span: url.span,
+    })
+}
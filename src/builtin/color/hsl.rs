@@ -516,3 +516,12 @@ pub(crate) fn declare(f: &mut GlobalFunctionMap) {
     f.insert("complement", Builtin::new(complement));
     f.insert("invert", Builtin::new(invert));
 }
+
+pub(crate) fn declare_module(f: &mut GlobalFunctionMap) {
+    f.insert("hue", Builtin::new(hue));
+    f.insert("saturation", Builtin::new(saturation));
+    f.insert("lightness", Builtin::new(lightness));
+    f.insert("grayscale", Builtin::new(grayscale));
+    f.insert("complement", Builtin::new(complement));
+    f.insert("invert", Builtin::new(invert));
+}
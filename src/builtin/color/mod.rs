@@ -11,3 +11,10 @@ pub(crate) fn declare(f: &mut GlobalFunctionMap) {
     other::declare(f);
     rgb::declare(f);
 }
+
+pub(crate) fn declare_module(f: &mut GlobalFunctionMap) {
+    hsl::declare_module(f);
+    opacity::declare_module(f);
+    other::declare_module(f);
+    rgb::declare_module(f);
+}
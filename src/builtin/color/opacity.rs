@@ -161,3 +161,8 @@ pub(crate) fn declare(f: &mut GlobalFunctionMap) {
     f.insert("transparentize", Builtin::new(transparentize));
     f.insert("fade-out", Builtin::new(fade_out));
 }
+
+pub(crate) fn declare_module(f: &mut GlobalFunctionMap) {
+    f.insert("alpha", Builtin::new(alpha));
+    f.insert("opacity", Builtin::new(opacity));
+}
@@ -352,9 +352,88 @@ fn ie_hex_str(mut args: CallArgs, scope: &Scope, super_selector: &Selector) -> S
     Ok(Value::String(color.to_ie_hex_str(), QuoteKind::None))
 }
 
+fn channel(mut args: CallArgs, scope: &Scope, super_selector: &Selector) -> SassResult<Value> {
+    args.max_args(3)?;
+    let color = match arg!(args, scope, super_selector, 0, "color") {
+        Value::Color(c) => c,
+        v => {
+            return Err((
+                format!("$color: {} is not a color.", v.to_css_string(args.span())?),
+                args.span(),
+            )
+                .into())
+        }
+    };
+    let channel = match arg!(args, scope, super_selector, 1, "channel") {
+        Value::String(s, QuoteKind::Quoted) => s,
+        v => {
+            return Err((
+                format!(
+                    "$channel: Expected {} to be a quoted string.",
+                    v.inspect(args.span())?
+                ),
+                args.span(),
+            )
+                .into())
+        }
+    };
+    let space = match arg!(args, scope, super_selector, 2, "space" = Value::Null) {
+        Value::String(s, QuoteKind::None) => Some(s.to_ascii_lowercase()),
+        Value::Null => None,
+        v => {
+            return Err((
+                format!(
+                    "$space: {} is not an unquoted string.",
+                    v.inspect(args.span())?
+                ),
+                args.span(),
+            )
+                .into())
+        }
+    };
+
+    let in_rgb = space.as_deref().map_or(true, |space| space == "rgb");
+    let in_hsl = space.as_deref().map_or(true, |space| space == "hsl");
+    if !in_rgb && !in_hsl {
+        return Err((
+            format!("$space: Unknown color space \"{}\".", space.unwrap()),
+            args.span(),
+        )
+            .into());
+    }
+
+    Ok(match channel.as_str() {
+        "red" if in_rgb => Value::Dimension(color.red(), Unit::None),
+        "green" if in_rgb => Value::Dimension(color.green(), Unit::None),
+        "blue" if in_rgb => Value::Dimension(color.blue(), Unit::None),
+        "hue" if in_hsl => Value::Dimension(color.hue(), Unit::Deg),
+        "saturation" if in_hsl => Value::Dimension(color.saturation(), Unit::Percent),
+        "lightness" if in_hsl => Value::Dimension(color.lightness(), Unit::Percent),
+        "alpha" => Value::Dimension(color.alpha(), Unit::None),
+        _ => {
+            return Err((
+                format!(
+                    "$channel: Color {} has no channel named {}.",
+                    color, channel
+                ),
+                args.span(),
+            )
+                .into())
+        }
+    })
+}
+
 pub(crate) fn declare(f: &mut GlobalFunctionMap) {
     f.insert("change-color", Builtin::new(change_color));
     f.insert("adjust-color", Builtin::new(adjust_color));
     f.insert("scale-color", Builtin::new(scale_color));
     f.insert("ie-hex-str", Builtin::new(ie_hex_str));
 }
+
+pub(crate) fn declare_module(f: &mut GlobalFunctionMap) {
+    f.insert("change", Builtin::new(change_color));
+    f.insert("adjust", Builtin::new(adjust_color));
+    f.insert("scale", Builtin::new(scale_color));
+    f.insert("ie-hex-str", Builtin::new(ie_hex_str));
+    f.insert("channel", Builtin::new(channel));
+}
@@ -441,3 +441,10 @@ pub(crate) fn declare(f: &mut GlobalFunctionMap) {
     f.insert("blue", Builtin::new(blue));
     f.insert("mix", Builtin::new(mix));
 }
+
+pub(crate) fn declare_module(f: &mut GlobalFunctionMap) {
+    f.insert("red", Builtin::new(red));
+    f.insert("green", Builtin::new(green));
+    f.insert("blue", Builtin::new(blue));
+    f.insert("mix", Builtin::new(mix));
+}
@@ -317,6 +317,21 @@ fn zip(args: CallArgs, scope: &Scope, super_selector: &Selector) -> SassResult<V
     Ok(Value::List(result, ListSeparator::Comma, Brackets::None))
 }
 
+fn slash(args: CallArgs, scope: &Scope, super_selector: &Selector) -> SassResult<Value> {
+    let span = args.span();
+    let elements = args
+        .get_variadic(scope, super_selector)?
+        .into_iter()
+        .map(|x| Ok(x.node.eval(span)?.node))
+        .collect::<SassResult<Vec<Value>>>()?;
+
+    if elements.len() < 2 {
+        return Err(("At least two elements are required.", span).into());
+    }
+
+    Ok(Value::List(elements, ListSeparator::Slash, Brackets::None))
+}
+
 pub(crate) fn declare(f: &mut GlobalFunctionMap) {
     f.insert("length", Builtin::new(length));
     f.insert("nth", Builtin::new(nth));
@@ -328,3 +343,16 @@ pub(crate) fn declare(f: &mut GlobalFunctionMap) {
     f.insert("index", Builtin::new(index));
     f.insert("zip", Builtin::new(zip));
 }
+
+pub(crate) fn declare_module(f: &mut GlobalFunctionMap) {
+    f.insert("length", Builtin::new(length));
+    f.insert("nth", Builtin::new(nth));
+    f.insert("separator", Builtin::new(list_separator));
+    f.insert("set-nth", Builtin::new(set_nth));
+    f.insert("append", Builtin::new(append));
+    f.insert("join", Builtin::new(join));
+    f.insert("is-bracketed", Builtin::new(is_bracketed));
+    f.insert("index", Builtin::new(index));
+    f.insert("zip", Builtin::new(zip));
+    f.insert("slash", Builtin::new(slash));
+}
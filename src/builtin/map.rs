@@ -1,5 +1,7 @@
 use super::{Builtin, GlobalFunctionMap};
 
+use codemap::Span;
+
 use crate::args::CallArgs;
 use crate::common::{Brackets, ListSeparator};
 use crate::error::SassResult;
@@ -21,7 +23,7 @@ fn map_get(mut args: CallArgs, scope: &Scope, super_selector: &Selector) -> Sass
                 .into())
         }
     };
-    Ok(map.get(&key, args.span())?.unwrap_or(Value::Null))
+    Ok(map.lookup(&key, args.span())?.unwrap_or(Value::Null))
 }
 
 fn map_has_key(mut args: CallArgs, scope: &Scope, super_selector: &Selector) -> SassResult<Value> {
@@ -38,7 +40,7 @@ fn map_has_key(mut args: CallArgs, scope: &Scope, super_selector: &Selector) ->
                 .into())
         }
     };
-    Ok(Value::bool(map.get(&key, args.span())?.is_some()))
+    Ok(Value::bool(map.lookup(&key, args.span())?.is_some()))
 }
 
 fn map_keys(mut args: CallArgs, scope: &Scope, super_selector: &Selector) -> SassResult<Value> {
@@ -128,6 +130,52 @@ fn map_remove(mut args: CallArgs, scope: &Scope, super_selector: &Selector) -> S
     Ok(Value::Map(map))
 }
 
+fn map_deep_merge(
+    mut args: CallArgs,
+    scope: &Scope,
+    super_selector: &Selector,
+) -> SassResult<Value> {
+    args.max_args(2)?;
+    let map1 = match arg!(args, scope, super_selector, 0, "map1") {
+        Value::Map(m) => m,
+        Value::List(v, ..) if v.is_empty() => SassMap::new(),
+        v => {
+            return Err((
+                format!("$map1: {} is not a map.", v.to_css_string(args.span())?),
+                args.span(),
+            )
+                .into())
+        }
+    };
+    let map2 = match arg!(args, scope, super_selector, 1, "map2") {
+        Value::Map(m) => m,
+        Value::List(v, ..) if v.is_empty() => SassMap::new(),
+        v => {
+            return Err((
+                format!("$map2: {} is not a map.", v.to_css_string(args.span())?),
+                args.span(),
+            )
+                .into())
+        }
+    };
+    Ok(Value::Map(deep_merge(map1, map2, args.span())?))
+}
+
+/// Like `map-merge`, except that nested maps present in both maps are
+/// merged recursively
+fn deep_merge(mut map1: SassMap, map2: SassMap, span: Span) -> SassResult<SassMap> {
+    for (key, value) in map2 {
+        let value = match (map1.clone().lookup(&key, span)?, value) {
+            (Some(Value::Map(existing)), Value::Map(new)) => {
+                Value::Map(deep_merge(existing, new, span)?)
+            }
+            (.., value) => value,
+        };
+        map1.insert(key, value);
+    }
+    Ok(map1)
+}
+
 pub(crate) fn declare(f: &mut GlobalFunctionMap) {
     f.insert("map-get", Builtin::new(map_get));
     f.insert("map-has-key", Builtin::new(map_has_key));
@@ -136,3 +184,13 @@ pub(crate) fn declare(f: &mut GlobalFunctionMap) {
     f.insert("map-merge", Builtin::new(map_merge));
     f.insert("map-remove", Builtin::new(map_remove));
 }
+
+pub(crate) fn declare_module(f: &mut GlobalFunctionMap) {
+    f.insert("get", Builtin::new(map_get));
+    f.insert("has-key", Builtin::new(map_has_key));
+    f.insert("keys", Builtin::new(map_keys));
+    f.insert("values", Builtin::new(map_values));
+    f.insert("merge", Builtin::new(map_merge));
+    f.insert("remove", Builtin::new(map_remove));
+    f.insert("deep-merge", Builtin::new(map_deep_merge));
+}
@@ -1,3 +1,4 @@
+use super::meta::{unit, unitless};
 use super::{Builtin, GlobalFunctionMap};
 
 #[cfg(feature = "random")]
@@ -188,6 +189,37 @@ fn random(mut args: CallArgs, scope: &Scope, super_selector: &Selector) -> SassR
     ))
 }
 
+fn div(mut args: CallArgs, scope: &Scope, super_selector: &Selector) -> SassResult<Value> {
+    args.max_args(2)?;
+    let number1 = match arg!(args, scope, super_selector, 0, "number1") {
+        v @ Value::Dimension(..) => v,
+        v => {
+            return Err((
+                format!(
+                    "$number1: {} is not a number.",
+                    v.to_css_string(args.span())?
+                ),
+                args.span(),
+            )
+                .into())
+        }
+    };
+    let number2 = match arg!(args, scope, super_selector, 1, "number2") {
+        v @ Value::Dimension(..) => v,
+        v => {
+            return Err((
+                format!(
+                    "$number2: {} is not a number.",
+                    v.to_css_string(args.span())?
+                ),
+                args.span(),
+            )
+                .into())
+        }
+    };
+    number1.div(number2, args.span())
+}
+
 pub(crate) fn declare(f: &mut GlobalFunctionMap) {
     f.insert("percentage", Builtin::new(percentage));
     f.insert("round", Builtin::new(round));
@@ -198,3 +230,30 @@ pub(crate) fn declare(f: &mut GlobalFunctionMap) {
     #[cfg(feature = "random")]
     f.insert("random", Builtin::new(random));
 }
+
+pub(crate) fn declare_module(f: &mut GlobalFunctionMap) {
+    f.insert("percentage", Builtin::new(percentage));
+    f.insert("round", Builtin::new(round));
+    f.insert("ceil", Builtin::new(ceil));
+    f.insert("floor", Builtin::new(floor));
+    f.insert("abs", Builtin::new(abs));
+    f.insert("compatible", Builtin::new(comparable));
+    f.insert("is-unitless", Builtin::new(unitless));
+    f.insert("unit", Builtin::new(unit));
+    f.insert("div", Builtin::new(div));
+    #[cfg(feature = "random")]
+    f.insert("random", Builtin::new(random));
+}
+
+pub(crate) fn module_variables() -> Vec<(&'static str, Value)> {
+    vec![
+        (
+            "pi",
+            Value::Dimension(Number::from(std::f64::consts::PI), Unit::None),
+        ),
+        (
+            "e",
+            Value::Dimension(Number::from(std::f64::consts::E), Unit::None),
+        ),
+    ]
+}
@@ -1,10 +1,11 @@
-use super::{Builtin, GlobalFunctionMap, GLOBAL_FUNCTIONS};
+use super::{global_fn, Builtin, GlobalFunctionMap};
 
 use codemap::Spanned;
 
 use crate::args::CallArgs;
 use crate::common::QuoteKind;
 use crate::error::SassResult;
+use crate::modules::{get_module, ModuleFunction};
 use crate::scope::global_var_exists;
 use crate::scope::Scope;
 use crate::selector::Selector;
@@ -57,7 +58,11 @@ fn feature_exists(
     }
 }
 
-fn unit(mut args: CallArgs, scope: &Scope, super_selector: &Selector) -> SassResult<Value> {
+pub(super) fn unit(
+    mut args: CallArgs,
+    scope: &Scope,
+    super_selector: &Selector,
+) -> SassResult<Value> {
     args.max_args(1)?;
     let unit = match arg!(args, scope, super_selector, 0, "number") {
         Value::Dimension(_, u) => u.to_string(),
@@ -84,7 +89,11 @@ fn type_of(mut args: CallArgs, scope: &Scope, super_selector: &Selector) -> Sass
     ))
 }
 
-fn unitless(mut args: CallArgs, scope: &Scope, super_selector: &Selector) -> SassResult<Value> {
+pub(super) fn unitless(
+    mut args: CallArgs,
+    scope: &Scope,
+    super_selector: &Selector,
+) -> SassResult<Value> {
     args.max_args(1)?;
     Ok(match arg!(args, scope, super_selector, 0, "number") {
         Value::Dimension(_, Unit::None) => Value::True,
@@ -153,12 +162,32 @@ fn function_exists(
     super_selector: &Selector,
 ) -> SassResult<Value> {
     args.max_args(2)?;
-    match arg!(args, scope, super_selector, 0, "name") {
-        Value::String(s, _) => Ok(Value::bool(
-            scope.fn_exists(&s) || GLOBAL_FUNCTIONS.contains_key(s.as_str()),
+    let name = match arg!(args, scope, super_selector, 0, "name") {
+        Value::String(s, _) => s,
+        v => {
+            return Err((
+                format!("$name: {} is not a string.", v.to_css_string(args.span())?),
+                args.span(),
+            )
+                .into())
+        }
+    };
+    match arg!(args, scope, super_selector, 1, "module" = Value::Null) {
+        Value::String(module, ..) => {
+            let module = get_module(Spanned {
+                node: &module,
+                span: args.span(),
+            })?;
+            Ok(Value::bool(module.fn_exists(&name.into())))
+        }
+        Value::Null => Ok(Value::bool(
+            scope.fn_exists(&name) || global_fn(&name.into()).is_some(),
         )),
         v => Err((
-            format!("$name: {} is not a string.", v.to_css_string(args.span())?),
+            format!(
+                "$module: {} is not a string.",
+                v.to_css_string(args.span())?
+            ),
             args.span(),
         )
             .into()),
@@ -201,13 +230,31 @@ fn get_function(mut args: CallArgs, scope: &Scope, super_selector: &Selector) ->
             .into());
     }
 
+    if let Some(module) = module {
+        let module = get_module(Spanned {
+            node: &module,
+            span: args.span(),
+        })?;
+        let func = match module.get_fn(Spanned {
+            node: name.clone().into(),
+            span: args.span(),
+        }) {
+            Ok(ModuleFunction::Builtin(f)) => SassFunction::builtin(f, name.into()),
+            Ok(ModuleFunction::UserDefined(f, module)) => {
+                SassFunction::user_defined(f, name.into(), module.key().cloned())
+            }
+            Err(..) => return Err((format!("Function not found: {}", name), args.span()).into()),
+        };
+        return Ok(Value::Function(func));
+    }
+
     let func = match scope.get_fn(Spanned {
         node: &name,
         span: args.span(),
     }) {
-        Ok(f) => SassFunction::UserDefined(Box::new(f), name.into()),
-        Err(..) => match GLOBAL_FUNCTIONS.get(name.as_str()) {
-            Some(f) => SassFunction::Builtin(f.clone(), name.into()),
+        Ok(f) => SassFunction::user_defined(f, name.into(), None),
+        Err(..) => match global_fn(&name.as_str().into()) {
+            Some(f) => SassFunction::builtin(f, name.into()),
             None => return Err((format!("Function not found: {}", name), args.span()).into()),
         },
     };
@@ -249,3 +296,18 @@ pub(crate) fn declare(f: &mut GlobalFunctionMap) {
     f.insert("get-function", Builtin::new(get_function));
     f.insert("call", Builtin::new(call));
 }
+
+pub(crate) fn declare_module(f: &mut GlobalFunctionMap) {
+    f.insert("feature-exists", Builtin::new(feature_exists));
+    f.insert("type-of", Builtin::new(type_of));
+    f.insert("inspect", Builtin::new(inspect));
+    f.insert("variable-exists", Builtin::new(variable_exists));
+    f.insert(
+        "global-variable-exists",
+        Builtin::new(global_variable_exists),
+    );
+    f.insert("mixin-exists", Builtin::new(mixin_exists));
+    f.insert("function-exists", Builtin::new(function_exists));
+    f.insert("get-function", Builtin::new(get_function));
+    f.insert("call", Builtin::new(call));
+}
@@ -1,9 +1,13 @@
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
+use std::fmt;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use crate::args::CallArgs;
-use crate::error::SassResult;
+use crate::common::Identifier;
+use crate::context::current;
+use crate::error::{SassError, SassResult};
 use crate::scope::Scope;
 use crate::selector::Selector;
 use crate::value::Value;
@@ -23,17 +27,35 @@ pub(crate) type GlobalFunctionMap = HashMap<&'static str, Builtin>;
 
 static FUNCTION_COUNT: AtomicUsize = AtomicUsize::new(0);
 
-// TODO: impl Fn
+type BuiltinBody = dyn Fn(CallArgs, &Scope, &Selector) -> SassResult<Value> + Send + Sync;
+
+/// A function implemented in rust, either one of the functions built into
+/// Sass or one defined by the host application
 #[derive(Clone)]
-pub(crate) struct Builtin(
-    pub fn(CallArgs, &Scope, &Selector) -> SassResult<Value>,
-    usize,
-);
+pub struct Builtin(Arc<BuiltinBody>, usize);
 
 impl Builtin {
-    pub fn new(body: fn(CallArgs, &Scope, &Selector) -> SassResult<Value>) -> Builtin {
+    pub(crate) fn new<F>(body: F) -> Builtin
+    where
+        F: Fn(CallArgs, &Scope, &Selector) -> SassResult<Value> + Send + Sync + 'static,
+    {
         let count = FUNCTION_COUNT.fetch_add(1, Ordering::Relaxed);
-        Self(body, count)
+        Self(Arc::new(body), count)
+    }
+
+    pub(crate) fn call(
+        &self,
+        args: CallArgs,
+        scope: &Scope,
+        super_selector: &Selector,
+    ) -> SassResult<Value> {
+        (self.0)(args, scope, super_selector).map_err(SassError::into_runtime)
+    }
+}
+
+impl fmt::Debug for Builtin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Builtin").field(&self.1).finish()
     }
 }
 
@@ -50,6 +72,49 @@ pub(crate) static GLOBAL_FUNCTIONS: Lazy<GlobalFunctionMap> = Lazy::new(|| {
     map::declare(&mut m);
     math::declare(&mut m);
     meta::declare(&mut m);
+    selector::declare(&mut m);
     string::declare(&mut m);
     m
 });
+
+/// The global function `name`, either one defined by the host application or
+/// one built into Sass
+pub(crate) fn global_fn(name: &Identifier) -> Option<Builtin> {
+    if let Some(f) = current().functions.borrow().get(name) {
+        return Some(f.clone());
+    }
+    GLOBAL_FUNCTIONS.get(name.as_str()).cloned()
+}
+
+/// The functions of each built-in module, e.g. `sass:math`, keyed by the
+/// name of the module
+///
+/// These are mostly the global functions under shorter names, along with a
+/// few functions only available through the module system, such as
+/// `math.div()`
+pub(crate) static BUILTIN_MODULES: Lazy<HashMap<&'static str, GlobalFunctionMap>> =
+    Lazy::new(|| {
+        let mut m = HashMap::new();
+        m.insert("color", module(color::declare_module));
+        m.insert("list", module(list::declare_module));
+        m.insert("map", module(map::declare_module));
+        m.insert("math", module(math::declare_module));
+        m.insert("meta", module(meta::declare_module));
+        m.insert("selector", module(selector::declare_module));
+        m.insert("string", module(string::declare_module));
+        m
+    });
+
+fn module(declare: fn(&mut GlobalFunctionMap)) -> GlobalFunctionMap {
+    let mut f = HashMap::new();
+    declare(&mut f);
+    f
+}
+
+/// The variables of the built-in module `name`, e.g. `math.$pi`
+pub(crate) fn builtin_module_variables(name: &str) -> Vec<(&'static str, Value)> {
+    match name {
+        "math" => math::module_variables(),
+        _ => Vec::new(),
+    }
+}
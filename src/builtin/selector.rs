@@ -1 +1,280 @@
+use super::{Builtin, GlobalFunctionMap};
 
+use codemap::Span;
+
+use peekmore::PeekMore;
+
+use crate::args::CallArgs;
+use crate::common::{Brackets, ListSeparator, QuoteKind};
+use crate::error::SassResult;
+use crate::scope::Scope;
+use crate::selector::{ExtendMode, ExtensionStore, Selector};
+use crate::value::Value;
+use crate::Token;
+
+/// The text of a selector passed to a function as a string, a list of
+/// strings, or a comma separated list of lists of strings
+fn selector_string(value: &Value) -> Option<String> {
+    Some(match value {
+        Value::String(s, ..) => s.clone(),
+        Value::List(list, ListSeparator::Comma, ..) => {
+            let mut complexes = Vec::with_capacity(list.len());
+            for complex in list {
+                match complex {
+                    Value::String(s, ..) => complexes.push(s.clone()),
+                    Value::List(compounds, ListSeparator::Space, ..) => {
+                        match selector_string(complex) {
+                            Some(s) if !compounds.is_empty() => complexes.push(s),
+                            _ => return None,
+                        }
+                    }
+                    _ => return None,
+                }
+            }
+            complexes.join(", ")
+        }
+        Value::List(list, ListSeparator::Space, ..) => {
+            let mut compounds = Vec::with_capacity(list.len());
+            for compound in list {
+                match compound {
+                    Value::String(s, ..) => compounds.push(s.clone()),
+                    _ => return None,
+                }
+            }
+            compounds.join(" ")
+        }
+        _ => return None,
+    })
+}
+
+fn parse_selector(
+    value: Value,
+    name: &str,
+    allows_parent: bool,
+    span: Span,
+) -> SassResult<Selector> {
+    let text = match selector_string(&value) {
+        Some(text) => text,
+        None => {
+            return Err((
+                format!(
+                    "${}: {} is not a valid selector: it must be a string,\na list of strings, or a list of lists of strings.",
+                    name,
+                    value.inspect(span)?
+                ),
+                span,
+            )
+                .into())
+        }
+    };
+    let selector = Selector::from_tokens(
+        &mut text.chars().map(|c| Token::new(span, c)).peekmore(),
+        &Scope::new(),
+        &Selector::new(),
+    )?;
+    if selector.is_empty() {
+        return Err((format!("${}: Expected selector.", name), span).into());
+    }
+    if !allows_parent && selector.contains_super_selector() {
+        return Err((
+            format!("${}: Parent selectors aren't allowed here.", name),
+            span,
+        )
+            .into());
+    }
+    Ok(selector)
+}
+
+fn selectors(args: CallArgs, scope: &Scope, super_selector: &Selector) -> SassResult<Vec<Value>> {
+    let span = args.span();
+    let selectors = args
+        .get_variadic(scope, super_selector)?
+        .into_iter()
+        .map(|x| Ok(x.node.eval(span)?.node))
+        .collect::<SassResult<Vec<Value>>>()?;
+    if selectors.is_empty() {
+        return Err(("$selectors: At least one selector must be passed.", span).into());
+    }
+    Ok(selectors)
+}
+
+fn is_superselector(
+    mut args: CallArgs,
+    scope: &Scope,
+    super_selector: &Selector,
+) -> SassResult<Value> {
+    args.max_args(2)?;
+    let span = args.span();
+    let parent = parse_selector(
+        arg!(args, scope, super_selector, 0, "super"),
+        "super",
+        false,
+        span,
+    )?;
+    let child = parse_selector(
+        arg!(args, scope, super_selector, 1, "sub"),
+        "sub",
+        false,
+        span,
+    )?;
+    Ok(Value::bool(parent.is_superselector(&child)))
+}
+
+fn simple_selectors(
+    mut args: CallArgs,
+    scope: &Scope,
+    super_selector: &Selector,
+) -> SassResult<Value> {
+    args.max_args(1)?;
+    let span = args.span();
+    let selector = arg!(args, scope, super_selector, 0, "selector");
+    let simples =
+        match parse_selector(selector.clone(), "selector", false, span)?.simple_selectors() {
+            Some(simples) => simples,
+            None => {
+                return Err((
+                    format!(
+                        "$selector: {} is not a compound selector.",
+                        selector.inspect(span)?
+                    ),
+                    span,
+                )
+                    .into())
+            }
+        };
+    Ok(Value::List(
+        simples
+            .into_iter()
+            .map(|simple| Value::String(simple.to_string(), QuoteKind::None))
+            .collect(),
+        ListSeparator::Comma,
+        Brackets::None,
+    ))
+}
+
+fn selector_parse(
+    mut args: CallArgs,
+    scope: &Scope,
+    super_selector: &Selector,
+) -> SassResult<Value> {
+    args.max_args(1)?;
+    let span = args.span();
+    Ok(parse_selector(
+        arg!(args, scope, super_selector, 0, "selector"),
+        "selector",
+        false,
+        span,
+    )?
+    .into_value())
+}
+
+fn selector_nest(args: CallArgs, scope: &Scope, super_selector: &Selector) -> SassResult<Value> {
+    let span = args.span();
+    let mut parsed = Vec::new();
+    for (idx, selector) in selectors(args, scope, super_selector)?
+        .into_iter()
+        .enumerate()
+    {
+        parsed.push(parse_selector(selector, "selectors", idx != 0, span)?);
+    }
+    Ok(parsed
+        .into_iter()
+        .fold(Selector::new(), |parent, child| parent.zip(&child))
+        .into_value())
+}
+
+fn selector_append(args: CallArgs, scope: &Scope, super_selector: &Selector) -> SassResult<Value> {
+    let span = args.span();
+    let mut parsed = selectors(args, scope, super_selector)?
+        .into_iter()
+        .map(|selector| parse_selector(selector, "selectors", false, span));
+    let mut parent = parsed.next().unwrap()?;
+    for child in parsed {
+        parent = parent.append(&child?, span)?;
+    }
+    Ok(parent.into_value())
+}
+
+fn extend_or_replace(
+    mut args: CallArgs,
+    scope: &Scope,
+    super_selector: &Selector,
+    mode: ExtendMode,
+) -> SassResult<Value> {
+    args.max_args(3)?;
+    let span = args.span();
+    let selector = parse_selector(
+        arg!(args, scope, super_selector, 0, "selector"),
+        "selector",
+        false,
+        span,
+    )?;
+    let target = parse_selector(
+        arg!(args, scope, super_selector, 1, "extendee"),
+        "extendee",
+        false,
+        span,
+    )?;
+    let source = parse_selector(
+        arg!(args, scope, super_selector, 2, "extender"),
+        "extender",
+        false,
+        span,
+    )?;
+    Ok(ExtensionStore::extend_or_replace(&selector, &source, &target, mode, span)?.into_value())
+}
+
+fn selector_extend(args: CallArgs, scope: &Scope, super_selector: &Selector) -> SassResult<Value> {
+    extend_or_replace(args, scope, super_selector, ExtendMode::Normal)
+}
+
+fn selector_replace(args: CallArgs, scope: &Scope, super_selector: &Selector) -> SassResult<Value> {
+    extend_or_replace(args, scope, super_selector, ExtendMode::Replace)
+}
+
+fn selector_unify(
+    mut args: CallArgs,
+    scope: &Scope,
+    super_selector: &Selector,
+) -> SassResult<Value> {
+    args.max_args(2)?;
+    let span = args.span();
+    let selector1 = parse_selector(
+        arg!(args, scope, super_selector, 0, "selector1"),
+        "selector1",
+        false,
+        span,
+    )?;
+    let selector2 = parse_selector(
+        arg!(args, scope, super_selector, 1, "selector2"),
+        "selector2",
+        false,
+        span,
+    )?;
+    Ok(match selector1.unify(&selector2) {
+        Some(unified) => unified.into_value(),
+        None => Value::Null,
+    })
+}
+
+pub(crate) fn declare(f: &mut GlobalFunctionMap) {
+    f.insert("is-superselector", Builtin::new(is_superselector));
+    f.insert("simple-selectors", Builtin::new(simple_selectors));
+    f.insert("selector-parse", Builtin::new(selector_parse));
+    f.insert("selector-nest", Builtin::new(selector_nest));
+    f.insert("selector-append", Builtin::new(selector_append));
+    f.insert("selector-extend", Builtin::new(selector_extend));
+    f.insert("selector-replace", Builtin::new(selector_replace));
+    f.insert("selector-unify", Builtin::new(selector_unify));
+}
+
+pub(crate) fn declare_module(f: &mut GlobalFunctionMap) {
+    f.insert("is-superselector", Builtin::new(is_superselector));
+    f.insert("simple-selectors", Builtin::new(simple_selectors));
+    f.insert("parse", Builtin::new(selector_parse));
+    f.insert("nest", Builtin::new(selector_nest));
+    f.insert("append", Builtin::new(selector_append));
+    f.insert("extend", Builtin::new(selector_extend));
+    f.insert("replace", Builtin::new(selector_replace));
+    f.insert("unify", Builtin::new(selector_unify));
+}
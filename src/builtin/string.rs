@@ -368,3 +368,16 @@ pub(crate) fn declare(f: &mut GlobalFunctionMap) {
     #[cfg(feature = "random")]
     f.insert("unique-id", Builtin::new(unique_id));
 }
+
+pub(crate) fn declare_module(f: &mut GlobalFunctionMap) {
+    f.insert("to-upper-case", Builtin::new(to_upper_case));
+    f.insert("to-lower-case", Builtin::new(to_lower_case));
+    f.insert("length", Builtin::new(str_length));
+    f.insert("quote", Builtin::new(quote));
+    f.insert("unquote", Builtin::new(unquote));
+    f.insert("slice", Builtin::new(str_slice));
+    f.insert("index", Builtin::new(str_index));
+    f.insert("insert", Builtin::new(str_insert));
+    #[cfg(feature = "random")]
+    f.insert("unique-id", Builtin::new(unique_id));
+}
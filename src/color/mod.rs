@@ -26,14 +26,14 @@ use num_traits::{One, Signed, ToPrimitive, Zero};
 mod name;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
-pub(crate) struct Color {
+pub struct Color {
     rgba: Rgba,
     hsla: Option<Hsla>,
     repr: String,
 }
 
 impl Color {
-    pub const fn new_rgba(
+    pub(crate) const fn new_rgba(
         red: Number,
         green: Number,
         blue: Number,
@@ -72,7 +72,7 @@ struct Rgba {
 }
 
 impl Rgba {
-    pub const fn new(red: Number, green: Number, blue: Number, alpha: Number) -> Self {
+    pub(crate) const fn new(red: Number, green: Number, blue: Number, alpha: Number) -> Self {
         Rgba {
             red,
             green,
@@ -81,7 +81,7 @@ impl Rgba {
         }
     }
 
-    pub fn alpha(&self) -> Number {
+    pub(crate) fn alpha(&self) -> Number {
         self.alpha.clone()
     }
 }
@@ -95,7 +95,12 @@ struct Hsla {
 }
 
 impl Hsla {
-    pub const fn new(hue: Number, saturation: Number, luminance: Number, alpha: Number) -> Self {
+    pub(crate) const fn new(
+        hue: Number,
+        saturation: Number,
+        luminance: Number,
+        alpha: Number,
+    ) -> Self {
         Hsla {
             hue,
             saturation,
@@ -104,26 +109,26 @@ impl Hsla {
         }
     }
 
-    pub fn hue(&self) -> Number {
+    pub(crate) fn hue(&self) -> Number {
         self.hue.clone()
     }
 
-    pub fn saturation(&self) -> Number {
+    pub(crate) fn saturation(&self) -> Number {
         self.saturation.clone()
     }
 
-    pub fn luminance(&self) -> Number {
+    pub(crate) fn luminance(&self) -> Number {
         self.luminance.clone()
     }
 
-    pub fn alpha(&self) -> Number {
+    pub(crate) fn alpha(&self) -> Number {
         self.alpha.clone()
     }
 }
 
 // RGBA color functions
 impl Color {
-    pub fn new(red: u8, green: u8, blue: u8, alpha: u8, repr: String) -> Self {
+    pub(crate) fn new(red: u8, green: u8, blue: u8, alpha: u8, repr: String) -> Self {
         Color {
             rgba: Rgba::new(red.into(), green.into(), blue.into(), alpha.into()),
             hsla: None,
@@ -133,6 +138,9 @@ impl Color {
 
     /// Create a new `Color` with just RGBA values.
     /// Color representation is created automatically.
+    ///
+    /// The red, green and blue channels are clamped between 0 and 255, and
+    /// the alpha channel between 0 and 1
     pub fn from_rgba(
         mut red: Number,
         mut green: Number,
@@ -163,7 +171,7 @@ impl Color {
     /// Mix two colors together with weight
     /// Algorithm adapted from
     /// <https://github.com/sass/dart-sass/blob/0d0270cb12a9ac5cce73a4d0785fecb00735feee/lib/src/functions/color.dart#L718>
-    pub fn mix(self, other: &Color, weight: Number) -> Self {
+    pub(crate) fn mix(self, other: &Color, weight: Number) -> Self {
         let weight = weight.clamp(0, 100);
         let normalized_weight = weight.clone() * Number::from(2) - Number::one();
         let alpha_distance = self.alpha() - other.alpha();
@@ -190,7 +198,7 @@ impl Color {
 /// HSLA color functions
 /// Algorithms adapted from <http://www.niwa.nu/2013/05/math-behind-colorspace-conversions-rgb-hsl/>
 impl Color {
-    /// Calculate hue from RGBA values
+    /// Calculate hue from RGBA values, in degrees
     pub fn hue(&self) -> Number {
         if let Some(h) = &self.hsla {
             return h.hue();
@@ -220,7 +228,7 @@ impl Color {
         (hue * Number::from(60)).round()
     }
 
-    /// Calculate saturation from RGBA values
+    /// Calculate saturation from RGBA values, as a percentage
     pub fn saturation(&self) -> Number {
         if let Some(h) = &self.hsla {
             return h.saturation() * Number::from(100);
@@ -247,7 +255,7 @@ impl Color {
         (s * Number::from(100)).round()
     }
 
-    /// Calculate luminance from RGBA values
+    /// Calculate luminance from RGBA values, as a percentage
     pub fn lightness(&self) -> Number {
         if let Some(h) = &self.hsla {
             return h.luminance() * Number::from(100);
@@ -261,7 +269,7 @@ impl Color {
         (((min + max) / Number::from(2)) * Number::from(100)).round()
     }
 
-    pub fn as_hsla(&self) -> (Number, Number, Number, Number) {
+    pub(crate) fn as_hsla(&self) -> (Number, Number, Number, Number) {
         if let Some(h) = &self.hsla {
             return (h.hue(), h.saturation(), h.luminance(), h.alpha());
         }
@@ -305,32 +313,35 @@ impl Color {
         (hue, saturation, lightness, self.alpha())
     }
 
-    pub fn adjust_hue(&self, degrees: Number) -> Self {
+    pub(crate) fn adjust_hue(&self, degrees: Number) -> Self {
         let (hue, saturation, luminance, alpha) = self.as_hsla();
         Color::from_hsla(hue + degrees, saturation, luminance, alpha)
     }
 
-    pub fn lighten(&self, amount: Number) -> Self {
+    pub(crate) fn lighten(&self, amount: Number) -> Self {
         let (hue, saturation, luminance, alpha) = self.as_hsla();
         Color::from_hsla(hue, saturation, luminance + amount, alpha)
     }
 
-    pub fn darken(&self, amount: Number) -> Self {
+    pub(crate) fn darken(&self, amount: Number) -> Self {
         let (hue, saturation, luminance, alpha) = self.as_hsla();
         Color::from_hsla(hue, saturation, luminance - amount, alpha)
     }
 
-    pub fn saturate(&self, amount: Number) -> Self {
+    pub(crate) fn saturate(&self, amount: Number) -> Self {
         let (hue, saturation, luminance, alpha) = self.as_hsla();
         Color::from_hsla(hue, saturation + amount, luminance, alpha)
     }
 
-    pub fn desaturate(&self, amount: Number) -> Self {
+    pub(crate) fn desaturate(&self, amount: Number) -> Self {
         let (hue, saturation, luminance, alpha) = self.as_hsla();
         Color::from_hsla(hue, saturation - amount, luminance, alpha)
     }
 
     /// Create RGBA representation from HSLA values
+    ///
+    /// The hue is in degrees, while the saturation, lightness and alpha
+    /// channel are clamped between 0 and 1
     pub fn from_hsla(hue: Number, saturation: Number, luminance: Number, alpha: Number) -> Self {
         let mut hue = if hue > Number::from(360) {
             hue % Number::from(360)
@@ -413,7 +424,7 @@ impl Color {
         Color::new_hsla(red, green, blue, alpha, hsla, repr)
     }
 
-    pub fn invert(&self, weight: Number) -> Self {
+    pub(crate) fn invert(&self, weight: Number) -> Self {
         if weight.is_zero() {
             return self.clone();
         }
@@ -425,7 +436,7 @@ impl Color {
         inverse.mix(self, weight)
     }
 
-    pub fn complement(&self) -> Self {
+    pub(crate) fn complement(&self) -> Self {
         let (hue, saturation, luminance, alpha) = self.as_hsla();
         let hue = if hue > Number::from(180) {
             Number::from(360) - hue
@@ -448,28 +459,28 @@ impl Color {
     }
 
     /// Change `alpha` to value given
-    pub fn with_alpha(self, alpha: Number) -> Self {
+    pub(crate) fn with_alpha(self, alpha: Number) -> Self {
         Color::from_rgba(self.red(), self.green(), self.blue(), alpha)
     }
 
     /// Makes a color more opaque.
     /// Takes a color and a number between 0 and 1,
     /// and returns a color with the opacity increased by that amount.
-    pub fn fade_in(self, amount: Number) -> Self {
+    pub(crate) fn fade_in(self, amount: Number) -> Self {
         Color::from_rgba(self.red(), self.green(), self.blue(), self.alpha() + amount)
     }
 
     /// Makes a color more transparent.
     /// Takes a color and a number between 0 and 1,
     /// and returns a color with the opacity decreased by that amount.
-    pub fn fade_out(self, amount: Number) -> Self {
+    pub(crate) fn fade_out(self, amount: Number) -> Self {
         Color::from_rgba(self.red(), self.green(), self.blue(), self.alpha() - amount)
     }
 }
 
 /// Other color functions
 impl Color {
-    pub fn to_ie_hex_str(&self) -> String {
+    pub(crate) fn to_ie_hex_str(&self) -> String {
         format!(
             "#{:X}{:X}{:X}{:X}",
             (self.alpha() * Number::from(255)).round().to_integer(),
@@ -478,20 +489,57 @@ impl Color {
             self.blue().to_integer()
         )
     }
-}
 
-/// Get the proper representation from RGBA values
-fn repr(red: &Number, green: &Number, blue: &Number, alpha: &Number) -> String {
-    fn into_u8(channel: &Number) -> u8 {
-        if channel > &Number::from(255) {
-            255_u8
-        } else if channel.is_negative() {
-            0_u8
+    /// The shortest representation of this color, used in compressed output
+    ///
+    /// This is whichever is shorter of its name and its hex code, which is
+    /// itself shortened to three digits where possible. Translucent colors
+    /// are written with `rgba()`, unless they are `transparent`
+    pub(crate) fn to_compressed_string(&self) -> String {
+        let red = into_u8(&self.red());
+        let green = into_u8(&self.green());
+        let blue = into_u8(&self.blue());
+        let alpha = self.alpha();
+
+        if alpha < Number::one() {
+            let rgba = format!(
+                "rgba({},{},{},{})",
+                red,
+                green,
+                blue,
+                alpha.to_compressed_string()
+            );
+            return match NAMED_COLORS.get_by_rgba([red, green, blue, 0]) {
+                Some(name) if alpha.is_zero() && name.len() < rgba.len() => (*name).to_string(),
+                _ => rgba,
+            };
+        }
+
+        let hex = if [red, green, blue].iter().all(|c| c >> 4 == c & 0xF) {
+            format!("#{:x}{:x}{:x}", red & 0xF, green & 0xF, blue & 0xF)
         } else {
-            channel.round().to_integer().to_u8().unwrap_or(255)
+            format!("#{:0>2x}{:0>2x}{:0>2x}", red, green, blue)
+        };
+        match NAMED_COLORS.get_by_rgba([red, green, blue, 0xFF]) {
+            Some(name) if name.len() < hex.len() => (*name).to_string(),
+            _ => hex,
         }
     }
+}
+
+/// Clamp a color channel to a `u8`
+fn into_u8(channel: &Number) -> u8 {
+    if channel > &Number::from(255) {
+        255_u8
+    } else if channel.is_negative() {
+        0_u8
+    } else {
+        channel.round().to_integer().to_u8().unwrap_or(255)
+    }
+}
 
+/// Get the proper representation from RGBA values
+fn repr(red: &Number, green: &Number, blue: &Number, alpha: &Number) -> String {
     let red_u8 = into_u8(red);
     let green_u8 = into_u8(green);
     let blue_u8 = into_u8(blue);
@@ -10,23 +10,23 @@ pub(crate) struct NamedColorMap {
 }
 
 impl NamedColorMap {
-    pub fn with_capacity(capacity: usize) -> Self {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
         Self {
             name_to_rgba: HashMap::with_capacity(capacity),
             rgba_to_name: HashMap::with_capacity(capacity),
         }
     }
 
-    pub fn insert(&mut self, name: &'static str, rgba: [u8; 4]) {
+    pub(crate) fn insert(&mut self, name: &'static str, rgba: [u8; 4]) {
         self.name_to_rgba.insert(name, rgba);
         self.rgba_to_name.insert(rgba, name);
     }
 
-    pub fn get_by_name(&self, name: &str) -> Option<&[u8; 4]> {
+    pub(crate) fn get_by_name(&self, name: &str) -> Option<&[u8; 4]> {
         self.name_to_rgba.get(name)
     }
 
-    pub fn get_by_rgba(&self, rgba: [u8; 4]) -> Option<&&str> {
+    pub(crate) fn get_by_rgba(&self, rgba: [u8; 4]) -> Option<&&str> {
         self.rgba_to_name.get(&rgba)
     }
 }
@@ -46,7 +46,7 @@ impl Op {
     /// Do not rely on the number itself, but rather the size relative to other numbers
     ///
     /// If precedence is equal, the leftmost operation is evaluated first
-    pub fn precedence(self) -> usize {
+    pub(crate) fn precedence(self) -> usize {
         match self {
             Self::And | Self::Or | Self::Not => 0,
             Self::Equal
@@ -62,7 +62,7 @@ impl Op {
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
-pub(crate) enum QuoteKind {
+pub enum QuoteKind {
     Quoted,
     None,
 }
@@ -78,34 +78,38 @@ impl Display for QuoteKind {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum Brackets {
+pub enum Brackets {
     None,
     Bracketed,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum ListSeparator {
+pub enum ListSeparator {
     Space,
     Comma,
+    /// Only created by `list.slash()`
+    Slash,
 }
 
 impl ListSeparator {
-    pub fn as_str(self) -> &'static str {
+    pub(crate) fn as_str(self) -> &'static str {
         match self {
             Self::Space => " ",
             Self::Comma => ", ",
+            Self::Slash => " / ",
         }
     }
 
-    pub fn name(self) -> &'static str {
+    pub(crate) fn name(self) -> &'static str {
         match self {
             Self::Space => "space",
             Self::Comma => "comma",
+            Self::Slash => "slash",
         }
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub(crate) struct QualifiedName {
     pub ident: String,
     pub namespace: Option<String>,
@@ -121,7 +125,7 @@ impl Display for QualifiedName {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub(crate) struct Identifier(String);
+pub struct Identifier(String);
 
 impl From<String> for Identifier {
     fn from(s: String) -> Identifier {
@@ -154,7 +158,7 @@ impl Default for Identifier {
 }
 
 impl Identifier {
-    pub fn into_inner(self) -> String {
-        self.0
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
     }
 }
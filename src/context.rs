@@ -0,0 +1,138 @@
+//! # Compilation context
+//!
+//! Everything a compilation builds up while evaluating a stylesheet: the
+//! files it has read, the global scope, and the modules it has loaded.
+//!
+//! Each compilation creates its own `Context`, so nothing declared while
+//! compiling one stylesheet is visible when compiling the next. While a
+//! compilation is running its state is made current for the thread running
+//! it, which is how evaluation deep inside the parser reaches the global
+//! scope without it being passed to every function. Compilations on
+//! different threads never share any state.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use codemap::{CodeMap, Span};
+
+use crate::builtin::Builtin;
+use crate::common::Identifier;
+use crate::logger::LogKind;
+use crate::modules::{Configuration, Module, Modules};
+use crate::options::{Options, DEFAULT_PRECISION};
+use crate::scope::Scope;
+
+thread_local!(
+    /// The state of the compilation running on this thread, if any
+    static CURRENT: RefCell<Option<Rc<State>>> = RefCell::new(None)
+);
+
+/// The state of a single compilation
+pub(crate) struct Context {
+    /// Every file read during the compilation, for resolving spans
+    map: CodeMap,
+    state: Rc<State>,
+}
+
+/// The parts of a compilation that are reached during evaluation
+#[derive(Debug)]
+pub(crate) struct State {
+    /// The global scope of the stylesheet currently being evaluated
+    ///
+    /// While the mixins and functions of a module are evaluated this is the
+    /// module's own scope, shared rather than copied so that the variables
+    /// they assign are kept
+    pub global_scope: RefCell<Rc<RefCell<Scope>>>,
+    /// The modules visible to the stylesheet currently being evaluated
+    pub modules: RefCell<Modules>,
+    /// Modules that have already been evaluated, keyed by their canonical
+    /// path
+    ///
+    /// A module is only ever evaluated once per compilation, no matter how
+    /// many times it is loaded
+    pub loaded_modules: RefCell<HashMap<PathBuf, Rc<Module>>>,
+    /// The configuration of the module currently being evaluated
+    pub module_config: RefCell<Configuration>,
+    /// The maximum number of digits written after the decimal point
+    pub precision: Cell<usize>,
+    /// Functions defined by the host application
+    pub functions: RefCell<HashMap<Identifier, Builtin>>,
+    /// Whether the body of a `@keyframes` rule is being evaluated, in which
+    /// case blocks are keyframe blocks such as `50% { ... }` rather than
+    /// style rules
+    pub in_keyframes: Cell<bool>,
+    /// Messages printed by `@warn` and `@debug` that haven't been passed to
+    /// the logger yet
+    pub messages: RefCell<Vec<(LogKind, Span, String)>>,
+}
+
+impl Context {
+    pub fn new(options: &Options) -> Self {
+        Context {
+            map: CodeMap::new(),
+            state: Rc::new(State {
+                global_scope: RefCell::new(Rc::new(RefCell::new(Scope::new()))),
+                modules: RefCell::new(Modules::new()),
+                loaded_modules: RefCell::new(HashMap::new()),
+                module_config: RefCell::new(Configuration::default()),
+                // at least one digit is always written
+                precision: Cell::new(options.precision.max(1)),
+                functions: RefCell::new(HashMap::new()),
+                in_keyframes: Cell::new(false),
+                messages: RefCell::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Run `f` with this context as the current compilation
+    ///
+    /// Whatever compilation was current beforehand is restored afterwards,
+    /// even if `f` panics
+    pub fn enter<T, F: FnOnce(&mut CodeMap) -> T>(&mut self, f: F) -> T {
+        struct Restore(Option<Rc<State>>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+                CURRENT.with(|c| c.replace(previous));
+            }
+        }
+
+        let _restore = Restore(CURRENT.with(|c| c.replace(Some(Rc::clone(&self.state)))));
+        f(&mut self.map)
+    }
+}
+
+impl State {
+    /// The global scope of the stylesheet currently being evaluated
+    pub fn global_scope(&self) -> Rc<RefCell<Scope>> {
+        Rc::clone(&self.global_scope.borrow())
+    }
+}
+
+/// The state of the compilation running on this thread
+///
+/// # Panics
+///
+/// If no compilation is running. Everything that evaluates Sass runs as part
+/// of a compilation, so this is a bug in `grass`.
+pub(crate) fn current() -> Rc<State> {
+    CURRENT.with(|c| match &*c.borrow() {
+        Some(state) => Rc::clone(state),
+        None => unreachable!("Sass was evaluated outside of a compilation"),
+    })
+}
+
+/// The number of digits after the decimal point that numbers are written
+/// with
+///
+/// Numbers may be written outside of a compilation, in which case the
+/// default precision is used
+pub(crate) fn precision() -> usize {
+    CURRENT.with(|c| match &*c.borrow() {
+        Some(state) => state.precision.get(),
+        None => DEFAULT_PRECISION,
+    })
+}
@@ -1,44 +1,270 @@
+use std::borrow::Cow;
 use std::error::Error;
 use std::fmt::{self, Display};
 use std::io;
 use std::string::FromUtf8Error;
 
-use codemap::{Span, SpanLoc};
+use codemap::{CodeMap, Span, SpanLoc};
+
+use crate::common::Identifier;
 
 pub type SassResult<T> = Result<T, SassError>;
 
+/// A position in a stylesheet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// The name of the stylesheet, which is its path or canonical URL, or
+    /// `stdin` for a stylesheet compiled from a string
+    pub file: String,
+    /// The line, starting from 1
+    pub line: usize,
+    /// The column, starting from 1
+    pub column: usize,
+}
+
+impl From<SpanLoc> for SourceLocation {
+    fn from(loc: SpanLoc) -> Self {
+        SourceLocation {
+            file: loc.file.name().to_owned(),
+            line: loc.begin.line + 1,
+            column: loc.begin.column + 1,
+        }
+    }
+}
+
+/// A mixin or function that was being evaluated when an error occurred
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackFrame {
+    /// Where in the stylesheet evaluation had reached
+    pub location: SourceLocation,
+    /// The name of the mixin or function, or `None` for the root stylesheet
+    pub member: Option<String>,
+}
+
+/// An error compiling a stylesheet
+///
+/// Its `Display` implementation shows the error in the same format as
+/// `dart-sass`, with the line of the stylesheet it occurred on
 #[derive(Debug)]
 pub struct SassError {
-    kind: SassErrorKind,
+    repr: Repr,
+}
+
+/// The kinds of error that can occur while compiling a stylesheet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SassErrorKind {
+    /// The stylesheet isn't valid Sass
+    Parse,
+    /// The stylesheet is valid, but evaluating it failed, such as by calling
+    /// a function with the wrong arguments or with `@error`
+    ///
+    /// As stylesheets are evaluated as they're parsed, an error parsing the
+    /// arguments of a built-in function is also a runtime error
+    Runtime,
+    /// A stylesheet couldn't be read
+    Io,
+    /// A stylesheet isn't valid UTF-8
+    Encoding,
 }
 
 impl SassError {
-    pub(crate) fn raw(self) -> (String, Span) {
-        match self.kind {
-            SassErrorKind::Raw(string, span) => (string, span),
-            e => todo!("unable to get raw of {:?}", e),
+    pub fn kind(&self) -> SassErrorKind {
+        match &self.repr {
+            Repr::Raw { kind, .. } | Repr::Located { kind, .. } => *kind,
+            Repr::Io { .. } => SassErrorKind::Io,
+            Repr::Encoding { .. } => SassErrorKind::Encoding,
+        }
+    }
+
+    /// The message describing this error, without its location
+    pub fn message(&self) -> Cow<'_, str> {
+        match &self.repr {
+            Repr::Raw { message, .. }
+            | Repr::Located { message, .. }
+            | Repr::Encoding { message, .. } => Cow::Borrowed(message),
+            Repr::Io { error, .. } => Cow::Owned(error.to_string()),
         }
     }
 
-    pub(crate) fn from_loc(message: String, loc: SpanLoc) -> Self {
+    /// The name of the stylesheet this error occurred in, or of the file
+    /// that couldn't be read, if known
+    pub fn file(&self) -> Option<&str> {
+        match &self.repr {
+            Repr::Located { start, .. } => Some(&start.file),
+            Repr::Io { file, .. } | Repr::Encoding { file, .. } => file.as_deref(),
+            Repr::Raw { .. } => None,
+        }
+    }
+
+    /// Where in a stylesheet this error starts, if it occurred in one
+    pub fn location(&self) -> Option<&SourceLocation> {
+        match &self.repr {
+            Repr::Located { start, .. } => Some(start),
+            _ => None,
+        }
+    }
+
+    /// Where in a stylesheet this error ends, if it occurred in one
+    ///
+    /// This is the position just after the last character the error refers
+    /// to
+    pub fn end_location(&self) -> Option<&SourceLocation> {
+        match &self.repr {
+            Repr::Located { end, .. } => Some(end),
+            _ => None,
+        }
+    }
+
+    /// The line of the stylesheet this error starts on, if it occurred in
+    /// one
+    pub fn source_line(&self) -> Option<&str> {
+        match &self.repr {
+            Repr::Located { source_line, .. } => Some(source_line),
+            _ => None,
+        }
+    }
+
+    /// The mixins and functions that were being evaluated when this error
+    /// occurred, innermost first, followed by the root stylesheet
+    ///
+    /// This is empty if the error didn't occur inside a mixin or function
+    pub fn trace(&self) -> &[StackFrame] {
+        match &self.repr {
+            Repr::Located { trace, .. } => trace,
+            _ => &[],
+        }
+    }
+
+    /// An error that happened while evaluating a stylesheet, rather than
+    /// while parsing it
+    pub(crate) fn runtime<M: Into<String>>(message: M, span: Span) -> Self {
         SassError {
-            kind: SassErrorKind::ParseError { message, loc },
+            repr: Repr::Raw {
+                message: message.into(),
+                span,
+                kind: SassErrorKind::Runtime,
+                calls: Vec::new(),
+            },
+        }
+    }
+
+    /// Mark this error as having happened while evaluating a stylesheet,
+    /// if it hasn't yet been located
+    pub(crate) fn into_runtime(self) -> Self {
+        match self.repr {
+            Repr::Raw {
+                message,
+                span,
+                calls,
+                ..
+            } => SassError {
+                repr: Repr::Raw {
+                    message,
+                    span,
+                    kind: SassErrorKind::Runtime,
+                    calls,
+                },
+            },
+            repr => SassError { repr },
+        }
+    }
+
+    /// Record that this error happened while reading the file `name`
+    pub(crate) fn in_file(mut self, name: &str) -> Self {
+        if let Repr::Io { file, .. } | Repr::Encoding { file, .. } = &mut self.repr {
+            *file = Some(name.to_owned());
+        }
+        self
+    }
+
+    /// Record that this error happened while evaluating the mixin or
+    /// function `name`, which was called at `span`
+    pub(crate) fn in_call(mut self, name: &Identifier, span: Span) -> Self {
+        if let Repr::Raw { calls, .. } = &mut self.repr {
+            calls.push((name.to_string(), span));
+        }
+        self
+    }
+
+    /// Find the location in `map` of an error that has only a span
+    ///
+    /// Errors that have already been located, or that didn't happen in a
+    /// stylesheet, are unchanged
+    pub(crate) fn locate(self, map: &CodeMap) -> Self {
+        let (message, span, kind, calls) = match self.repr {
+            Repr::Raw {
+                message,
+                span,
+                kind,
+                calls,
+            } => (message, span, kind, calls),
+            repr => return SassError { repr },
+        };
+        // each mixin or function was called from the one after it, and the
+        // outermost from the root stylesheet
+        let mut trace = Vec::with_capacity(calls.len() + 1);
+        if !calls.is_empty() {
+            let mut location = span;
+            for (member, call_span) in calls {
+                trace.push(StackFrame {
+                    location: SourceLocation::from(map.look_up_span(location)),
+                    member: Some(member),
+                });
+                location = call_span;
+            }
+            trace.push(StackFrame {
+                location: SourceLocation::from(map.look_up_span(location)),
+                member: None,
+            });
+        }
+        let loc = map.look_up_span(span);
+        SassError {
+            repr: Repr::Located {
+                message,
+                kind,
+                trace,
+                source_line: loc.file.source_line(loc.begin.line).to_owned(),
+                end: SourceLocation {
+                    file: loc.file.name().to_owned(),
+                    line: loc.end.line + 1,
+                    column: loc.end.column + 1,
+                },
+                start: SourceLocation::from(loc),
+            },
         }
     }
 }
 
 #[derive(Debug)]
-enum SassErrorKind {
-    /// A raw error with no additional metadata
-    /// It contains only a `String` message and
-    /// a span
-    Raw(String, Span),
-    ParseError {
+enum Repr {
+    /// An error that has only a message and a span, before its location
+    /// has been found in the `CodeMap`
+    Raw {
+        message: String,
+        span: Span,
+        kind: SassErrorKind,
+        /// The mixins and functions the error happened inside of, along
+        /// with where they were called, innermost first
+        calls: Vec<(String, Span)>,
+    },
+    Located {
+        message: String,
+        kind: SassErrorKind,
+        trace: Vec<StackFrame>,
+        start: SourceLocation,
+        end: SourceLocation,
+        source_line: String,
+    },
+    Io {
+        error: io::Error,
+        /// The file that couldn't be read
+        file: Option<String>,
+    },
+    Encoding {
         message: String,
-        loc: SpanLoc,
+        /// The file that isn't valid UTF-8
+        file: Option<String>,
     },
-    IoError(io::Error),
-    FromUtf8Error(String),
 }
 
 impl Display for SassError {
@@ -47,31 +273,54 @@ impl Display for SassError {
     // TODO: integrate with codemap-diagnostics
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let (message, loc) = match &self.kind {
-            SassErrorKind::ParseError { message, loc } => (message, loc),
-            SassErrorKind::FromUtf8Error(s) => return writeln!(f, "Error: {}", s),
-            SassErrorKind::IoError(s) => return writeln!(f, "Error: {}", s),
-            SassErrorKind::Raw(..) => todo!(),
+        let (message, start, end, source_line, trace) = match &self.repr {
+            Repr::Located {
+                message,
+                start,
+                end,
+                source_line,
+                trace,
+                ..
+            } => (message, start, end, source_line, trace),
+            Repr::Raw { message, .. } | Repr::Encoding { message, .. } => {
+                return writeln!(f, "Error: {}", message)
+            }
+            Repr::Io { error, .. } => return writeln!(f, "Error: {}", error),
         };
-        let line = loc.begin.line + 1;
-        let col = loc.begin.column + 1;
         writeln!(f, "Error: {}", message)?;
-        let padding = vec![' '; format!("{}", line).len() + 1]
+        let padding = vec![' '; format!("{}", start.line).len() + 1]
             .iter()
             .collect::<String>();
         writeln!(f, "{}|", padding)?;
-        writeln!(f, "{} | {}", line, loc.file.source_line(loc.begin.line))?;
+        writeln!(f, "{} | {}", start.line, source_line)?;
         writeln!(
             f,
             "{}| {}{}",
             padding,
-            vec![' '; loc.begin.column].iter().collect::<String>(),
-            vec!['^'; loc.end.column.max(loc.begin.column) - loc.begin.column.min(loc.end.column)]
+            vec![' '; start.column - 1].iter().collect::<String>(),
+            vec!['^'; end.column.max(start.column) - start.column.min(end.column)]
                 .iter()
                 .collect::<String>()
         )?;
         writeln!(f, "{}|", padding)?;
-        writeln!(f, "./{}:{}:{}", loc.file.name(), line, col)?;
+        if trace.is_empty() {
+            writeln!(f, "./{}:{}:{}", start.file, start.line, start.column)?;
+            return Ok(());
+        }
+        let locations = trace
+            .iter()
+            .map(|frame| {
+                let location = &frame.location;
+                format!("{} {}:{}", location.file, location.line, location.column)
+            })
+            .collect::<Vec<String>>();
+        let width = locations.iter().map(String::len).max().unwrap_or(0);
+        for (location, frame) in locations.iter().zip(trace) {
+            match &frame.member {
+                Some(member) => writeln!(f, "  {:width$}  {}()", location, member, width = width)?,
+                None => writeln!(f, "  {:width$}  root stylesheet", location, width = width)?,
+            }
+        }
         Ok(())
     }
 }
@@ -80,7 +329,7 @@ impl From<io::Error> for SassError {
     #[inline]
     fn from(error: io::Error) -> Self {
         SassError {
-            kind: SassErrorKind::IoError(error),
+            repr: Repr::Io { error, file: None },
         }
     }
 }
@@ -89,28 +338,42 @@ impl From<FromUtf8Error> for SassError {
     #[inline]
     fn from(error: FromUtf8Error) -> Self {
         SassError {
-            kind: SassErrorKind::FromUtf8Error(format!(
-                "Invalid UTF-8 character \"\\x{:X?}\"",
-                error.as_bytes()[0]
-            )),
+            repr: Repr::Encoding {
+                message: format!("Invalid UTF-8 character \"\\x{:X?}\"", error.as_bytes()[0]),
+                file: None,
+            },
         }
     }
 }
 
+/// A parse error, as most errors with a message and span are; errors that
+/// happen during evaluation are made with `SassError::runtime`
 impl From<(&str, Span)> for SassError {
     #[inline]
     fn from(error: (&str, Span)) -> SassError {
         SassError {
-            kind: SassErrorKind::Raw(error.0.to_owned(), error.1),
+            repr: Repr::Raw {
+                message: error.0.to_owned(),
+                span: error.1,
+                kind: SassErrorKind::Parse,
+                calls: Vec::new(),
+            },
         }
     }
 }
 
+/// A parse error, as most errors with a message and span are; errors that
+/// happen during evaluation are made with `SassError::runtime`
 impl From<(String, Span)> for SassError {
     #[inline]
     fn from(error: (String, Span)) -> SassError {
         SassError {
-            kind: SassErrorKind::Raw(error.0, error.1),
+            repr: Repr::Raw {
+                message: error.0,
+                span: error.1,
+                kind: SassErrorKind::Parse,
+                calls: Vec::new(),
+            },
         }
     }
 }
@@ -120,4 +383,11 @@ impl Error for SassError {
     fn description(&self) -> &'static str {
         "SASS parsing error"
     }
+
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.repr {
+            Repr::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,58 @@
+//! # Predefined global variables
+//!
+//! Global variables defined by the application using `grass` with
+//! [`Options::variable`] and [`Options::define`], or on the command line with
+//! `--define`, which are declared before the stylesheet is evaluated.
+//!
+//! [`Options::variable`]: crate::Options::variable
+//! [`Options::define`]: crate::Options::define
+
+use codemap::{CodeMap, Spanned};
+
+use crate::error::SassResult;
+use crate::lexer::Lexer;
+use crate::options::Options;
+use crate::scope::{insert_global_var, Scope};
+use crate::selector::Selector;
+use crate::value::Value;
+use crate::Token;
+
+/// The value of a predefined global variable
+#[derive(Debug, Clone)]
+pub(crate) enum GlobalVariable {
+    Value(Value),
+    /// SassScript that is evaluated to find the value, such as
+    /// `(primary: red, secondary: blue)`
+    Expression(String),
+}
+
+/// Declare the global variables in `options` in the global scope of the
+/// current compilation
+///
+/// They are declared in order, so an expression may refer to any variable
+/// defined before it. The source of each variable is added to `map` so that
+/// errors evaluating it can be reported.
+pub(crate) fn define_global_variables(map: &mut CodeMap, options: &Options) -> SassResult<()> {
+    for (name, variable) in &options.variables {
+        let value = match variable {
+            GlobalVariable::Value(value) => {
+                let file = map.add_file(format!("${}", name), format!("{:#}", value));
+                Spanned {
+                    node: value.clone(),
+                    span: file.span,
+                }
+            }
+            GlobalVariable::Expression(expression) => {
+                let file = map.add_file(format!("${}", name), expression.clone());
+                Value::from_vec(
+                    Lexer::new(&file).collect::<Vec<Token>>(),
+                    &Scope::new(),
+                    &Selector::new(),
+                    file.span,
+                )?
+            }
+        };
+        insert_global_var(name.as_str(), value)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,156 @@
+//! # Host functions
+//!
+//! Functions implemented in rust by the application using `grass`, which are
+//! registered with [`Options::function`] and can be called from Sass like any
+//! global function.
+//!
+//! [`Options::function`]: crate::Options::function
+
+use std::fmt;
+use std::sync::Arc;
+
+use codemap::CodeMap;
+
+use peekmore::PeekMore;
+
+use crate::args::{eat_func_params, CallArgs, FuncArgs};
+use crate::builtin::Builtin;
+use crate::common::{Brackets, Identifier, ListSeparator};
+use crate::context::current;
+use crate::error::SassResult;
+use crate::lexer::Lexer;
+use crate::options::Options;
+use crate::scope::Scope;
+use crate::selector::Selector;
+use crate::utils::{devour_whitespace, eat_ident_no_interpolation};
+use crate::value::Value;
+use crate::Token;
+
+type HostFunctionBody = dyn Fn(&[Value]) -> Result<Value, String> + Send + Sync;
+
+/// A function defined by the host application, along with the signature
+/// declaring its name and arguments, e.g. `asset-url($path, $hash: true)`
+#[derive(Clone)]
+pub(crate) struct HostFunction {
+    signature: String,
+    body: Arc<HostFunctionBody>,
+}
+
+impl fmt::Debug for HostFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("HostFunction")
+            .field(&self.signature)
+            .finish()
+    }
+}
+
+impl HostFunction {
+    pub fn new<F>(signature: String, body: F) -> Self
+    where
+        F: Fn(&[Value]) -> Result<Value, String> + Send + Sync + 'static,
+    {
+        HostFunction {
+            signature,
+            body: Arc::new(body),
+        }
+    }
+
+    /// Parse the signature of this function, returning its name and a
+    /// builtin that calls it
+    ///
+    /// The signature is added to `map` so that errors in it, or in the
+    /// default values of its arguments, can be reported
+    fn declare(&self, map: &mut CodeMap) -> SassResult<(Identifier, Builtin)> {
+        let file = map.add_file(self.signature.clone(), self.signature.clone());
+        let toks = &mut Lexer::new(&file)
+            .collect::<Vec<Token>>()
+            .into_iter()
+            .peekmore();
+        devour_whitespace(toks);
+        let name = eat_ident_no_interpolation(toks, false, file.span)?;
+        devour_whitespace(toks);
+        let (params, _) = match toks.next() {
+            Some(Token { kind: '(', .. }) if toks.peek().is_some() => {
+                eat_func_params(toks, &Scope::new(), &Selector::new())?
+            }
+            Some(Token { kind: '(', pos }) => return Err(("expected \")\".", pos).into()),
+            Some(Token { pos, .. }) => return Err(("expected \"(\".", pos).into()),
+            None => return Err(("expected \"(\".", name.span).into()),
+        };
+        devour_whitespace(toks);
+        if let Some(Token { pos, .. }) = toks.next() {
+            return Err(("expected no more input.", pos).into());
+        }
+        let body = Arc::clone(&self.body);
+        let builtin = Builtin::new(
+            move |args: CallArgs, scope: &Scope, super_selector: &Selector| {
+                let span = args.span();
+                let values = eval_args(&params, args, scope, super_selector)?;
+                body(&values).map_err(|message| (message, span).into())
+            },
+        );
+        Ok((name.node.into(), builtin))
+    }
+}
+
+/// The value of each argument in `params`, in order
+///
+/// Any variable arguments are passed as a single comma-separated list.
+/// Default values are evaluated in the global scope, and may refer to the
+/// arguments before them
+fn eval_args(
+    params: &FuncArgs,
+    mut args: CallArgs,
+    scope: &Scope,
+    super_selector: &Selector,
+) -> SassResult<Vec<Value>> {
+    let span = args.span();
+    let is_variadic = params.0.last().map_or(false, |param| param.is_variadic);
+    if !is_variadic {
+        args.max_args(params.0.len())?;
+    }
+    let mut values = Vec::with_capacity(params.0.len());
+    let mut params_scope = Scope::new();
+    for (idx, param) in params.0.iter().enumerate() {
+        if param.is_variadic {
+            break;
+        }
+        let value = match args.get(idx, param.name.clone(), scope, super_selector) {
+            Some(v) => v?,
+            None => match &param.default {
+                Some(default) => {
+                    Value::from_vec(default.clone(), &params_scope, super_selector, span)?
+                }
+                None => return Err((format!("Missing argument ${}.", &param.name), span).into()),
+            },
+        };
+        let value = value.node.eval_all(value.span)?.span(value.span);
+        params_scope.insert_var(param.name.clone(), value.clone())?;
+        values.push(value.node);
+    }
+    if is_variadic || !args.is_empty() {
+        // errors if any named arguments don't match a parameter
+        let rest = args.get_variadic(scope, super_selector)?;
+        if is_variadic {
+            values.push(Value::List(
+                rest.into_iter()
+                    .map(|v| v.node.eval_all(v.span))
+                    .collect::<SassResult<Vec<Value>>>()?,
+                ListSeparator::Comma,
+                Brackets::None,
+            ));
+        }
+    }
+    Ok(values)
+}
+
+/// Make the host functions in `options` available to the current
+/// compilation
+pub(crate) fn declare_host_functions(map: &mut CodeMap, options: &Options) -> SassResult<()> {
+    let state = current();
+    for function in &options.functions {
+        let (name, builtin) = function.declare(map)?;
+        state.functions.borrow_mut().insert(name, builtin);
+    }
+    Ok(())
+}
@@ -0,0 +1,234 @@
+//! # Importers
+//!
+//! Importers load the stylesheets referred to by `@import`, `@use` and
+//! `@forward` from somewhere other than the filesystem, such as memory or a
+//! database. They are registered with [`Options::importer`] and consulted in
+//! order before the filesystem is searched.
+//!
+//! [`Options::importer`]: crate::Options::importer
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::stylesheet::Syntax;
+
+/// Loads stylesheets from somewhere other than the filesystem
+///
+/// Each stylesheet an importer can load is identified by a canonical URL.
+/// The same stylesheet must always have the same canonical URL, however it is
+/// referred to, as it is used to make sure a module is only loaded once and
+/// to detect import loops. It is also the name of the stylesheet in errors
+/// and source maps.
+///
+/// ```
+/// use std::io;
+/// use grass::{ImportedStylesheet, Importer, Options, SassResult, StyleSheet, Syntax};
+///
+/// #[derive(Debug)]
+/// struct Colors;
+///
+/// impl Importer for Colors {
+///     fn canonicalize(&self, url: &str, _base: Option<&str>) -> io::Result<Option<String>> {
+///         if url == "colors" {
+///             Ok(Some("colors:main".to_owned()))
+///         } else {
+///             Ok(None)
+///         }
+///     }
+///
+///     fn load(&self, _canonical_url: &str) -> io::Result<ImportedStylesheet> {
+///         Ok(ImportedStylesheet {
+///             contents: "$primary: red;".to_owned(),
+///             syntax: Syntax::Scss,
+///         })
+///     }
+/// }
+///
+/// fn main() -> SassResult<()> {
+///     let css = StyleSheet::new_with_options(
+///         "@use \"colors\";\na {\n  color: colors.$primary;\n}\n".to_string(),
+///         &Options::default().importer(Colors),
+///     )?;
+///     assert_eq!(css, "a {\n  color: red;\n}\n");
+///     Ok(())
+/// }
+/// ```
+pub trait Importer: fmt::Debug + Send + Sync {
+    /// The canonical URL of the stylesheet that `url` refers to, or `None`
+    /// if this importer can't load it
+    ///
+    /// `url` is exactly as written in the `@import`, `@use` or `@forward`
+    /// rule. `base` is the URL of the stylesheet containing the rule, either
+    /// a canonical URL returned by an importer or the path of a file, and is
+    /// `None` for a stylesheet compiled from a string.
+    ///
+    /// An error, such as `url` referring to more than one stylesheet, is
+    /// reported at the rule.
+    fn canonicalize(&self, url: &str, base: Option<&str>) -> io::Result<Option<String>>;
+
+    /// Load the stylesheet with a canonical URL previously returned by
+    /// [`Importer::canonicalize`]
+    fn load(&self, canonical_url: &str) -> io::Result<ImportedStylesheet>;
+}
+
+/// The contents of a stylesheet loaded by an [`Importer`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedStylesheet {
+    pub contents: String,
+    pub syntax: Syntax,
+}
+
+/// An importer of stylesheets held in memory, keyed by their URL
+///
+/// URLs are resolved in the same way as paths on the filesystem: relative
+/// to the importing stylesheet and then to the root, with partials, index
+/// files and extensions all optional. The syntax of each stylesheet is
+/// determined by its extension.
+///
+/// ```
+/// use grass::{MemoryImporter, Options, SassResult, StyleSheet};
+///
+/// fn main() -> SassResult<()> {
+///     let mut importer = MemoryImporter::new();
+///     importer.insert("theme/_colors.scss", "$primary: red;");
+///     importer.insert("theme/index.scss", "@forward \"colors\";");
+///     let css = StyleSheet::new_with_options(
+///         "@use \"theme\";\na {\n  color: theme.$primary;\n}\n".to_string(),
+///         &Options::default().importer(importer),
+///     )?;
+///     assert_eq!(css, "a {\n  color: red;\n}\n");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryImporter {
+    stylesheets: HashMap<String, String>,
+}
+
+impl MemoryImporter {
+    pub fn new() -> Self {
+        MemoryImporter::default()
+    }
+
+    /// Add the stylesheet `contents` at `url`, returning the contents it
+    /// replaces, if any
+    pub fn insert<U: Into<String>, C: Into<String>>(
+        &mut self,
+        url: U,
+        contents: C,
+    ) -> Option<String> {
+        self.stylesheets.insert(url.into(), contents.into())
+    }
+
+    /// The URL of the stylesheet that `url` refers to, if any
+    ///
+    /// `foo` may refer to `foo.sass`, `foo.scss`, `foo.css`, their partials
+    /// such as `_foo.scss`, or to `foo/index.scss`
+    fn find(&self, url: &str) -> io::Result<Option<String>> {
+        if url.ends_with(".sass") || url.ends_with(".scss") || url.ends_with(".css") {
+            return exactly_one(self.try_url(url));
+        }
+        if let Some(found) = exactly_one(self.try_url_with_extensions(url))? {
+            return Ok(Some(found));
+        }
+        // a stylesheet imported by its full name, without an extension
+        if self.stylesheets.contains_key(url) {
+            return Ok(Some(url.to_owned()));
+        }
+        exactly_one(self.try_url_with_extensions(&format!("{}/index", url)))
+    }
+
+    /// The URLs of the stylesheets that are either `url` or its partial,
+    /// `_url`
+    fn try_url(&self, url: &str) -> Vec<String> {
+        vec![partial(url), url.to_owned()]
+            .into_iter()
+            .filter(|url| self.stylesheets.contains_key(url))
+            .collect()
+    }
+
+    /// The URLs of the stylesheets that are `url` with a `.sass` or `.scss`
+    /// extension, falling back to `.css`
+    fn try_url_with_extensions(&self, url: &str) -> Vec<String> {
+        let mut found = self.try_url(&format!("{}.sass", url));
+        found.extend(self.try_url(&format!("{}.scss", url)));
+        if found.is_empty() {
+            found = self.try_url(&format!("{}.css", url));
+        }
+        found
+    }
+}
+
+impl From<HashMap<String, String>> for MemoryImporter {
+    fn from(stylesheets: HashMap<String, String>) -> Self {
+        MemoryImporter { stylesheets }
+    }
+}
+
+impl Importer for MemoryImporter {
+    fn canonicalize(&self, url: &str, base: Option<&str>) -> io::Result<Option<String>> {
+        let dir = base
+            .and_then(|base| base.rfind('/').map(|idx| &base[..=idx]))
+            .unwrap_or("");
+        match self.find(&normalize(&format!("{}{}", dir, url)))? {
+            Some(found) => Ok(Some(found)),
+            None => self.find(&normalize(url)),
+        }
+    }
+
+    fn load(&self, canonical_url: &str) -> io::Result<ImportedStylesheet> {
+        match self.stylesheets.get(canonical_url) {
+            Some(contents) => Ok(ImportedStylesheet {
+                contents: contents.clone(),
+                syntax: Syntax::for_path(Path::new(canonical_url)),
+            }),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No stylesheet at \"{}\".", canonical_url),
+            )),
+        }
+    }
+}
+
+/// The single URL in `found`, erroring if there is more than one
+fn exactly_one(found: Vec<String>) -> io::Result<Option<String>> {
+    if found.len() > 1 {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!(
+                "It's not clear which file to import. Found:\n{}",
+                found
+                    .iter()
+                    .map(|url| format!("  {}", url))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ),
+        ));
+    }
+    Ok(found.into_iter().next())
+}
+
+/// The partial of the stylesheet at `url`, with `_` prepended to its name
+fn partial(url: &str) -> String {
+    match url.rfind('/') {
+        Some(idx) => format!("{}_{}", &url[..=idx], &url[idx + 1..]),
+        None => format!("_{}", url),
+    }
+}
+
+/// Remove `.` and `..` segments from `url`
+fn normalize(url: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in url.split('/') {
+        match segment {
+            "." => {}
+            ".." if matches!(segments.last(), Some(last) if *last != ".." && !last.is_empty()) => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
@@ -1,46 +1,238 @@
-use std::ffi::{OsStr, OsString};
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use codemap::{CodeMap, Spanned};
+use codemap::{CodeMap, Span, Spanned};
 
-use crate::error::SassResult;
+use crate::error::{SassError, SassResult};
+use crate::importer::Importer;
+use crate::options::Options;
 use crate::scope::Scope;
+use crate::stylesheet::Syntax;
 use crate::{Stmt, StyleSheet};
 
-pub(crate) fn import(
+/// A stylesheet referred to by `@import`, `@use` or `@forward`
+#[derive(Debug, Clone)]
+pub(crate) enum Resolved {
+    File(PathBuf),
+    /// A stylesheet with a canonical URL returned by an importer
+    Importer(Arc<dyn Importer>, String),
+}
+
+impl Resolved {
+    /// The path of the stylesheet, or its canonical URL if it was found by
+    /// an importer
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::File(path) => path,
+            Self::Importer(_, url) => Path::new(url),
+        }
+    }
+
+    /// Identifies the stylesheet, no matter how it was referred to
+    pub fn canonical(&self) -> PathBuf {
+        match self {
+            Self::File(path) => path.canonicalize().unwrap_or_else(|_| path.clone()),
+            Self::Importer(_, url) => PathBuf::from(url),
+        }
+    }
+
+    /// The name, contents and syntax of the stylesheet
+    pub fn load(&self, span: Span) -> SassResult<(String, String, Syntax)> {
+        match self {
+            Self::File(path) => match path.to_str() {
+                Some(name) => Ok((name.to_owned(), read_file(name)?, Syntax::for_path(path))),
+                None => Err(SassError::runtime("Invalid UTF-8 in path.", span)),
+            },
+            Self::Importer(importer, url) => match importer.load(url) {
+                Ok(stylesheet) => Ok((url.clone(), stylesheet.contents, stylesheet.syntax)),
+                Err(e) => Err(SassError::runtime(e.to_string(), span)),
+            },
+        }
+    }
+}
+
+/// The contents of the file at `path`
+pub(crate) fn read_file(path: &str) -> SassResult<String> {
+    let bytes = fs::read(path).map_err(|e| SassError::from(e).in_file(path))?;
+    String::from_utf8(bytes).map_err(|e| SassError::from(e).in_file(path))
+}
+
+/// The directories searched for `path`, in order of precedence
+///
+/// Relative imports are first resolved relative to the importing file `ctx`,
+/// then relative to each load path
+fn import_dirs(ctx: &Path, path: &Path, load_paths: &[PathBuf]) -> Vec<PathBuf> {
+    if path.is_absolute() {
+        return vec![PathBuf::new()];
+    }
+    let mut dirs = vec![ctx.parent().unwrap_or_else(|| Path::new("")).to_path_buf()];
+    dirs.extend(load_paths.iter().cloned());
+    dirs
+}
+
+/// The existing files that are either `path` or its partial, `_path`
+fn try_path(path: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+        let partial = path.with_file_name(format!("_{}", name));
+        if partial.is_file() {
+            found.push(partial);
+        }
+    }
+    if path.is_file() {
+        found.push(path.to_path_buf());
+    }
+    found
+}
+
+/// The existing files that are `path` with a `.sass` or `.scss` extension,
+/// falling back to `.css`
+fn try_path_with_extensions(path: &Path) -> Vec<PathBuf> {
+    let mut found = try_path(&with_extension(path, "sass"));
+    found.extend(try_path(&with_extension(path, "scss")));
+    if found.is_empty() {
+        found = try_path(&with_extension(path, "css"));
+    }
+    found
+}
+
+/// Append `extension` to `path`, keeping any dots already in the file name
+fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    PathBuf::from(path)
+}
+
+/// The single file in `found`, erroring if there is more than one
+fn exactly_one(found: Vec<PathBuf>, span: Span) -> SassResult<Option<PathBuf>> {
+    if found.len() > 1 {
+        return Err((
+            format!(
+                "It's not clear which file to import. Found:\n{}",
+                found
+                    .iter()
+                    .map(|path| format!("  {}", path.display()))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            ),
+            span,
+        )
+            .into());
+    }
+    Ok(found.into_iter().next())
+}
+
+/// Find the file within `dir` that `path` refers to
+///
+/// `foo` may refer to `foo.sass`, `foo.scss`, `foo.css`, their partials such
+/// as `_foo.scss`, or to `foo/index.scss`
+fn resolve_in(dir: &Path, path: &Path, span: Span) -> SassResult<Option<PathBuf>> {
+    let path = dir.join(path);
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("sass") | Some("scss") | Some("css") => return exactly_one(try_path(&path), span),
+        _ => {}
+    }
+
+    if let Some(found) = exactly_one(try_path_with_extensions(&path), span)? {
+        return Ok(Some(found));
+    }
+    // a file imported by its full name, without an extension
+    if path.is_file() {
+        return Ok(Some(path));
+    }
+    if path.is_dir() {
+        return exactly_one(try_path_with_extensions(&path.join("index")), span);
+    }
+    Ok(None)
+}
+
+/// Find the file that `path` refers to, relative to the file `ctx` or to one
+/// of `load_paths`
+fn find_import(
     ctx: &Path,
     path: &Path,
-    map: &mut CodeMap,
-) -> SassResult<(Vec<Spanned<Stmt>>, Scope)> {
-    let mut rules = Vec::new();
-    let mut scope = Scope::new();
-    if path.is_absolute() {
-        todo!("absolute import")
-    }
-    let path_buf = ctx.parent().unwrap_or(Path::new("")).join(path);
-    // "todo: will panic if path ended in `..`"
-    let name = path_buf.file_name().unwrap();
-    if path_buf.extension() == Some(OsStr::new(".css")) {
-        // || name.starts_with("http://") || name.starts_with("https://") {
-        todo!("handle css imports")
-    }
-    let mut p1 = path_buf.clone();
-    p1.push(OsString::from("index.scss"));
-    let mut p2 = path_buf.clone();
-    p2.push(OsString::from("_index.scss"));
-    let paths = [
-        path_buf.with_file_name(name).with_extension("scss"),
-        path_buf.with_file_name(format!("_{}.scss", name.to_str().unwrap())),
-        path_buf,
-        p1,
-        p2,
-    ];
-    for name in &paths {
-        if name.is_file() {
-            let (rules2, scope2) = StyleSheet::export_from_path(&name.to_str().unwrap(), map)?;
-            rules.extend(rules2);
-            scope.extend(scope2);
+    load_paths: &[PathBuf],
+    span: Span,
+) -> SassResult<Option<PathBuf>> {
+    for dir in import_dirs(ctx, path, load_paths) {
+        if let Some(found) = resolve_in(&dir, path, span)? {
+            return Ok(Some(found));
         }
     }
-    Ok((rules, scope))
+    Ok(None)
+}
+
+/// Find the stylesheet that `url` refers to from the stylesheet at `ctx`
+///
+/// Each importer is tried in turn before searching the filesystem
+pub(crate) fn resolve(
+    ctx: &Path,
+    url: &str,
+    options: &Options,
+    span: Span,
+) -> SassResult<Option<Resolved>> {
+    let base = ctx.to_str().filter(|base| !base.is_empty());
+    for importer in &options.importers {
+        match importer.canonicalize(url, base) {
+            Ok(Some(canonical)) => {
+                return Ok(Some(Resolved::Importer(Arc::clone(importer), canonical)))
+            }
+            Ok(None) => {}
+            Err(e) => return Err(SassError::runtime(e.to_string(), span)),
+        }
+    }
+    Ok(find_import(ctx, Path::new(url), &options.load_paths, span)?.map(Resolved::File))
+}
+
+/// Error if `path` is one of the files in `loading`, which would otherwise
+/// cause infinite recursion
+///
+/// The error includes the chain of files that led back to `path`
+pub(crate) fn check_not_loading(
+    path: &Path,
+    loading: &[PathBuf],
+    message: &str,
+    span: Span,
+) -> SassResult<()> {
+    // stylesheets found by importers aren't files, so are compared by URL
+    let canonicalize = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let canonical = canonicalize(path);
+    let start = match loading
+        .iter()
+        .position(|file| canonicalize(file) == canonical)
+    {
+        Some(start) => start,
+        None => return Ok(()),
+    };
+    let chain = loading[start..]
+        .iter()
+        .map(PathBuf::as_path)
+        .chain(std::iter::once(path))
+        .map(|file| format!("  {}", file.display()))
+        .collect::<Vec<String>>()
+        .join("\n");
+    Err(SassError::runtime(format!("{}\n{}", message, chain), span))
+}
+
+pub(crate) fn import(
+    ctx: &Path,
+    url: &str,
+    span: Span,
+    map: &mut CodeMap,
+    options: &Options,
+    loading: &mut Vec<PathBuf>,
+) -> SassResult<(Vec<Spanned<Stmt>>, Scope)> {
+    let stylesheet = match resolve(ctx, url, options, span)? {
+        Some(stylesheet) => stylesheet,
+        None => return Err(SassError::runtime("Can't find stylesheet to import.", span)),
+    };
+    check_not_loading(
+        stylesheet.path(),
+        loading,
+        "This file is already being loaded.",
+        span,
+    )?;
+    StyleSheet::export(&stylesheet, map, options, loading, span)
 }
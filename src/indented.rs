@@ -0,0 +1,410 @@
+//! The indented syntax, used by `.sass` files
+//!
+//! Rather than being parsed separately, indented stylesheets are rewritten
+//! into the tokens of the equivalent SCSS, with braces and semicolons inferred
+//! from indentation. Every token keeps the span of the source it came from,
+//! so errors point into the original `.sass` file.
+
+use codemap::Span;
+
+use crate::error::SassResult;
+use crate::utils::{is_name, is_name_start, IsWhitespace};
+use crate::Token;
+
+/// A statement of the indented syntax, which may span several physical
+/// lines if it contains an unclosed bracket or ends in a comma
+#[derive(Debug)]
+struct Line {
+    /// The number of whitespace characters at the start of the line
+    indent: usize,
+    toks: Vec<Token>,
+    /// The newline ending the statement, if this is not the last line
+    newline: Option<Token>,
+    /// Comments never take a semicolon nor open a block
+    is_comment: bool,
+}
+
+/// Rewrite the tokens of a stylesheet written in the indented syntax into
+/// the tokens of the equivalent SCSS
+pub(crate) fn indented_to_scss<I: Iterator<Item = Token>>(toks: I) -> SassResult<Vec<Token>> {
+    let (lines, uses_tabs) = logical_lines(physical_lines(toks))?;
+    let indent_kind = if uses_tabs { "tabs" } else { "spaces" };
+
+    let mut scss = Vec::new();
+    let mut indents = vec![0];
+    for (idx, line) in lines.iter().enumerate() {
+        let span = line.toks[0].pos;
+        if idx == 0 && line.indent != 0 {
+            return Err((
+                "Indenting at the beginning of the document is illegal.",
+                span,
+            )
+                .into());
+        }
+        let end = line.toks[line.toks.len() - 1].pos;
+        let next_indent = lines.get(idx + 1).map_or(0, |next| next.indent);
+
+        if line.is_comment {
+            scss.extend(&line.toks);
+        } else if next_indent > *indents.last().unwrap() {
+            scss.extend(rewrite_statement(&line.toks, false));
+            scss.push(Token::new(end, ' '));
+            scss.push(Token::new(end, '{'));
+            indents.push(next_indent);
+        } else {
+            scss.extend(rewrite_statement(&line.toks, true));
+            scss.push(Token::new(end, ';'));
+        }
+
+        // the indentation of the innermost block the next line is inside of
+        let mut expected = *indents.last().unwrap();
+        while next_indent < *indents.last().unwrap() {
+            expected = indents.pop().unwrap();
+            scss.push(Token::new(end, '}'));
+        }
+        if next_indent != *indents.last().unwrap() {
+            return Err((
+                format!(
+                    "Inconsistent indentation, expected {} {}.",
+                    expected, indent_kind
+                ),
+                lines[idx + 1].toks[0].pos,
+            )
+                .into());
+        }
+
+        scss.push(line.newline.unwrap_or_else(|| Token::new(end, '\n')));
+    }
+
+    Ok(scss)
+}
+
+/// Split `toks` on newlines, keeping the newline at the end of each line
+fn physical_lines<I: Iterator<Item = Token>>(toks: I) -> Vec<Vec<Token>> {
+    let mut lines = Vec::new();
+    let mut line = Vec::new();
+    for tok in toks {
+        let is_newline = tok.kind == '\n';
+        line.push(tok);
+        if is_newline {
+            lines.push(line);
+            line = Vec::new();
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// The number of leading whitespace characters in `line`
+fn indentation(line: &[Token]) -> usize {
+    line.iter()
+        .take_while(|tok| tok.kind == ' ' || tok.kind == '\t')
+        .count()
+}
+
+/// Check that the indentation of `line` uses only one kind of whitespace, and
+/// the same kind as every line before it
+///
+/// `uses_tabs` is decided by the first indented line of the stylesheet
+fn check_indentation(line: &[Token], uses_tabs: &mut Option<bool>) -> SassResult<()> {
+    let indentation = &line[..indentation(line)];
+    let has_tabs = indentation.iter().any(|tok| tok.kind == '\t');
+    let has_spaces = indentation.iter().any(|tok| tok.kind == ' ');
+    match (has_tabs, has_spaces, *uses_tabs) {
+        (true, true, _) => Err(("Tabs and spaces may not be mixed.", indentation[0].pos).into()),
+        (true, false, Some(false)) => {
+            Err(("Expected spaces, was tabs.", indentation[0].pos).into())
+        }
+        (false, true, Some(true)) => Err(("Expected tabs, was spaces.", indentation[0].pos).into()),
+        (false, false, _) => Ok(()),
+        _ => {
+            *uses_tabs = Some(has_tabs);
+            Ok(())
+        }
+    }
+}
+
+/// Remove the leading indentation and the trailing newline and whitespace
+/// from a physical line, returning the newline
+fn trim_line(line: &mut Vec<Token>) -> Option<Token> {
+    let newline = match line.last() {
+        Some(Token { kind: '\n', .. }) => line.pop(),
+        _ => None,
+    };
+    while let Some(Token { kind: ' ', .. }) | Some(Token { kind: '\t', .. }) = line.last() {
+        line.pop();
+    }
+    line.drain(..indentation(line));
+    newline
+}
+
+fn starts_with(toks: &[Token], prefix: &str) -> bool {
+    toks.len() >= prefix.len()
+        && toks
+            .iter()
+            .zip(prefix.chars())
+            .all(|(tok, c)| tok.kind == c)
+}
+
+/// Group physical lines into statements, dropping blank lines and silent
+/// comments
+///
+/// Errors if the indentation of a statement mixes tabs and spaces. Also
+/// returns whether the stylesheet is indented with tabs
+fn logical_lines(physical: Vec<Vec<Token>>) -> SassResult<(Vec<Line>, bool)> {
+    let mut lines = Vec::new();
+    let mut physical = physical.into_iter().peekable();
+    let mut uses_tabs = None;
+
+    while let Some(mut toks) = physical.next() {
+        let indent = indentation(&toks);
+        if !toks.iter().all(|tok| tok.is_whitespace()) {
+            check_indentation(&toks, &mut uses_tabs)?;
+        }
+        let mut newline = trim_line(&mut toks);
+        if toks.is_empty() {
+            continue;
+        }
+
+        if starts_with(&toks, "//") || starts_with(&toks, "/*") {
+            let is_loud = toks[1].kind == '*';
+            let mut children = Vec::new();
+            while let Some(next) = physical.peek() {
+                let is_blank = next.iter().all(|tok| tok.is_whitespace());
+                if !is_blank && indentation(next) <= indent {
+                    break;
+                }
+                children.push(physical.next().unwrap());
+            }
+            if !is_loud {
+                continue;
+            }
+
+            // trailing blank lines aren't part of the comment
+            while let Some(child) = children.last() {
+                if !child.iter().all(|tok| tok.is_whitespace()) {
+                    break;
+                }
+                children.pop();
+            }
+            for mut child in children {
+                if let Some(newline) = newline {
+                    toks.push(newline);
+                }
+                newline = match child.last() {
+                    Some(Token { kind: '\n', .. }) => child.pop(),
+                    _ => None,
+                };
+                while let Some(Token { kind: ' ', .. }) | Some(Token { kind: '\t', .. }) =
+                    child.last()
+                {
+                    child.pop();
+                }
+                toks.extend(child);
+            }
+            if !ends_with_comment_close(&toks) {
+                let end = toks[toks.len() - 1].pos;
+                toks.push(Token::new(end, ' '));
+                toks.push(Token::new(end, '*'));
+                toks.push(Token::new(end, '/'));
+            }
+            lines.push(Line {
+                indent,
+                toks,
+                newline,
+                is_comment: true,
+            });
+            continue;
+        }
+
+        loop {
+            strip_silent_comment(&mut toks);
+            if !continues_on_next_line(&toks) {
+                break;
+            }
+            let mut next = match physical.next() {
+                Some(next) => next,
+                None => break,
+            };
+            if let Some(newline) = newline {
+                toks.push(newline);
+            }
+            newline = trim_line(&mut next);
+            toks.extend(next);
+        }
+
+        if toks.is_empty() {
+            continue;
+        }
+        lines.push(Line {
+            indent,
+            toks,
+            newline,
+            is_comment: false,
+        });
+    }
+
+    Ok((lines, uses_tabs == Some(true)))
+}
+
+fn ends_with_comment_close(toks: &[Token]) -> bool {
+    toks.len() >= 4 && toks[toks.len() - 2].kind == '*' && toks[toks.len() - 1].kind == '/'
+}
+
+/// The number of brackets left unclosed by `toks`
+///
+/// Calls `f` with the index of each token outside of strings and brackets,
+/// stopping early if it returns `true`
+fn scan(toks: &[Token], mut f: impl FnMut(usize) -> bool) -> usize {
+    let mut depth = 0_usize;
+    let mut quote = None;
+    let mut idx = 0;
+    while idx < toks.len() {
+        let kind = toks[idx].kind;
+        match quote {
+            Some(q) => {
+                if kind == '\\' {
+                    idx += 1;
+                } else if kind == q {
+                    quote = None;
+                }
+            }
+            None => match kind {
+                '"' | '\'' => quote = Some(kind),
+                '\\' => idx += 1,
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                _ if depth == 0 => {
+                    if f(idx) {
+                        break;
+                    }
+                }
+                _ => {}
+            },
+        }
+        idx += 1;
+    }
+    depth
+}
+
+/// Remove a `//` comment at the end of a statement
+fn strip_silent_comment(toks: &mut Vec<Token>) {
+    let mut start = None;
+    scan(toks, |idx| {
+        let is_comment = toks[idx].kind == '/'
+            && toks.get(idx + 1).map(|tok| tok.kind) == Some('/')
+            && (idx == 0 || toks[idx - 1].is_whitespace());
+        if is_comment {
+            start = Some(idx);
+        }
+        is_comment
+    });
+    if let Some(start) = start {
+        toks.truncate(start);
+        while let Some(Token { kind: ' ', .. }) | Some(Token { kind: '\t', .. }) = toks.last() {
+            toks.pop();
+        }
+    }
+}
+
+/// Whether a statement continues on the next physical line, either because
+/// it has an unclosed bracket or because it ends in a comma
+fn continues_on_next_line(toks: &[Token]) -> bool {
+    scan(toks, |_| false) > 0 || toks.last().map(|tok| tok.kind) == Some(',')
+}
+
+fn tokens_of(s: &str, span: Span) -> impl Iterator<Item = Token> + '_ {
+    s.chars().map(move |c| Token::new(span, c))
+}
+
+/// Rewrite the syntax exclusive to the indented syntax into its SCSS form
+///
+/// `=foo` is `@mixin foo`, `+foo` is `@include foo`, `:color red` is
+/// `color: red`, and `@import foo` is `@import "foo"`
+fn rewrite_statement(toks: &[Token], is_childless: bool) -> Vec<Token> {
+    let span = toks[0].pos;
+    let next_is_name_start = toks
+        .get(1)
+        .map_or(false, |tok| is_name_start(tok.kind) || tok.kind == '-');
+    match toks[0].kind {
+        '=' => {
+            let mut scss: Vec<Token> = tokens_of("@mixin ", span).collect();
+            scss.extend(&toks[1..]);
+            scss
+        }
+        '+' if next_is_name_start => {
+            let mut scss: Vec<Token> = tokens_of("@include ", span).collect();
+            scss.extend(&toks[1..]);
+            scss
+        }
+        ':' if next_is_name_start && is_childless => {
+            let name_len = toks[1..].iter().take_while(|tok| is_name(tok.kind)).count();
+            let mut scss = toks[1..=name_len].to_vec();
+            scss.push(Token::new(span, ':'));
+            scss.extend(&toks[name_len + 1..]);
+            scss
+        }
+        '@' if starts_with(toks, "@import")
+            && toks.get(7).map_or(false, |tok| tok.is_whitespace()) =>
+        {
+            let mut scss = toks[..7].to_vec();
+            scss.extend(quote_import_urls(&toks[7..]));
+            scss
+        }
+        _ => toks.to_vec(),
+    }
+}
+
+/// Quote each of the comma separated urls of an `@import`, which may be
+/// unquoted in the indented syntax
+fn quote_import_urls(toks: &[Token]) -> Vec<Token> {
+    let mut commas = Vec::new();
+    scan(toks, |idx| {
+        if toks[idx].kind == ',' {
+            commas.push(idx);
+        }
+        false
+    });
+
+    let mut scss = Vec::new();
+    let mut start = 0;
+    for end in commas.into_iter().chain(std::iter::once(toks.len())) {
+        let url = &toks[start..end];
+        let leading = url.iter().take_while(|tok| tok.is_whitespace()).count();
+        let trailing = url[leading..]
+            .iter()
+            .rev()
+            .take_while(|tok| tok.is_whitespace())
+            .count();
+        let trimmed = &url[leading..url.len() - trailing];
+
+        // urls that are already quoted, or that are followed by media
+        // queries, are left untouched
+        let is_unchanged = match trimmed.first() {
+            Some(Token { kind: '"', .. }) | Some(Token { kind: '\'', .. }) | None => true,
+            Some(..) => {
+                starts_with(trimmed, "url(")
+                    || trimmed
+                        .iter()
+                        .any(|tok| tok.is_whitespace() || tok.kind == '#')
+            }
+        };
+
+        scss.extend(&url[..leading]);
+        if is_unchanged {
+            scss.extend(trimmed);
+        } else {
+            scss.push(Token::new(trimmed[0].pos, '"'));
+            scss.extend(trimmed);
+            scss.push(Token::new(trimmed[trimmed.len() - 1].pos, '"'));
+        }
+        scss.extend(&url[url.len() - trailing..]);
+        if end < toks.len() {
+            scss.push(toks[end]);
+        }
+        start = end + 1;
+    }
+    scss
+}
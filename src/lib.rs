@@ -88,31 +88,55 @@ use codemap::{Span, Spanned};
 
 use peekmore::{PeekMore, PeekMoreIterator};
 
-use crate::atrule::{AtRule, AtRuleKind, Function, Mixin};
-pub use crate::error::{SassError, SassResult};
+use crate::atrule::{
+    in_keyframes, parse_keyframes_selector, AtRule, AtRuleKind, Function, KeyframesRuleSet, Mixin,
+};
+pub use crate::color::Color;
+use crate::common::Identifier;
+pub use crate::common::{Brackets, ListSeparator, QuoteKind};
+pub use crate::error::{SassError, SassErrorKind, SassResult, SourceLocation, StackFrame};
+pub use crate::importer::{ImportedStylesheet, Importer, MemoryImporter};
+pub use crate::logger::{
+    CollectingLogger, LogKind, LogMessage, Logger, SilentLogger, StderrLogger,
+};
+use crate::modules::get_module;
+pub use crate::options::Options;
+pub use crate::output::OutputStyle;
 use crate::scope::{insert_global_var, Scope};
 use crate::selector::Selector;
+pub use crate::source_map::{SourceMap, SourceMapUrls};
 use crate::style::Style;
-pub use crate::stylesheet::StyleSheet;
+pub use crate::stylesheet::{StyleSheet, Syntax};
 pub(crate) use crate::token::Token;
+pub use crate::unit::Unit;
 use crate::utils::{
-    devour_whitespace, eat_comment, eat_ident, eat_variable_value, peek_ident_no_interpolation,
-    peek_whitespace, read_until_closing_curly_brace, read_until_closing_paren, read_until_newline,
-    VariableDecl,
+    devour_whitespace, eat_comment, eat_ident, eat_variable_value, is_name, is_name_start,
+    peek_ident_no_interpolation, peek_whitespace, read_until_closing_curly_brace,
+    read_until_closing_paren, read_until_newline, IsWhitespace, VariableDecl,
 };
-use crate::value::Value;
+pub use crate::value::{Number, SassFunction, SassMap, Value};
 
 mod args;
 mod atrule;
 mod builtin;
 mod color;
 mod common;
+mod context;
 mod error;
+mod global_variable;
+mod host_function;
+mod importer;
 mod imports;
+mod indented;
 mod lexer;
+mod logger;
+mod modules;
+mod options;
 mod output;
+mod plain_css;
 mod scope;
 mod selector;
+mod source_map;
 mod style;
 mod stylesheet;
 mod token;
@@ -130,6 +154,8 @@ pub(crate) enum Stmt {
     MultilineComment(String),
     /// A CSS rule: `@charset "UTF-8";`
     AtRule(AtRule),
+    /// A block within `@keyframes`: `50% { top: 0; }`
+    KeyframesRuleSet(Box<KeyframesRuleSet>),
 }
 
 impl Stmt {
@@ -166,6 +192,8 @@ enum Expr {
     Styles(Vec<Style>),
     /// A full selector `a > h1`
     Selector(Selector),
+    /// The selector of a block within `@keyframes`: `from, 50%`
+    KeyframesRuleSet(Vec<String>),
     /// A variable declaration `$var: 1px`
     VariableDecl(String, Box<Spanned<Value>>),
     /// A mixin declaration `@mixin foo {}`
@@ -176,6 +204,24 @@ enum Expr {
     AtRule(AtRule),
 }
 
+/// The namespace of an assignment to a variable of another module, such as
+/// `colors.$primary: blue`, given the tokens before the `$`
+fn assignment_namespace(toks: &[Token]) -> Option<Spanned<String>> {
+    let (dot, namespace) = toks.split_last()?;
+    if dot.kind != '.' {
+        return None;
+    }
+    let start = namespace.iter().position(|tok| !tok.is_whitespace())?;
+    let namespace = &namespace[start..];
+    if !is_name_start(namespace[0].kind) || !namespace.iter().all(|tok| is_name(tok.kind)) {
+        return None;
+    }
+    Some(Spanned {
+        node: namespace.iter().map(|tok| tok.kind).collect(),
+        span: namespace[0].pos.merge(namespace[namespace.len() - 1].pos),
+    })
+}
+
 pub(crate) fn eat_expr<I: Iterator<Item = Token>>(
     toks: &mut PeekMoreIterator<I>,
     scope: &mut Scope,
@@ -187,8 +233,15 @@ pub(crate) fn eat_expr<I: Iterator<Item = Token>>(
         Some(tok) => tok.pos(),
         None => return Ok(None),
     };
+    // leading whitespace isn't part of the expression's span
+    let mut is_leading_whitespace = true;
     while let Some(tok) = toks.peek() {
-        span = span.merge(tok.pos());
+        if is_leading_whitespace {
+            span = tok.pos();
+            is_leading_whitespace = tok.is_whitespace();
+        } else {
+            span = span.merge(tok.pos());
+        }
         match tok.kind {
             ':' => {
                 let tok = toks.next().unwrap();
@@ -268,6 +321,17 @@ pub(crate) fn eat_expr<I: Iterator<Item = Token>>(
             '{' => {
                 toks.next();
                 devour_whitespace(toks);
+                if in_keyframes() {
+                    return Ok(Some(Spanned {
+                        node: Expr::KeyframesRuleSet(parse_keyframes_selector(
+                            &mut values.into_iter().peekmore(),
+                            scope,
+                            super_selector,
+                            span,
+                        )?),
+                        span,
+                    }));
+                }
                 return Ok(Some(Spanned {
                     node: Expr::Selector(Selector::from_tokens(
                         &mut values.into_iter().peekmore(),
@@ -298,6 +362,22 @@ pub(crate) fn eat_expr<I: Iterator<Item = Token>>(
                         default,
                         global,
                     } = eat_variable_value(toks, scope, super_selector, name.span)?;
+                    if let Some(namespace) = assignment_namespace(&values) {
+                        if global {
+                            return Err((
+                                "!global isn't allowed for variables in other modules.",
+                                span,
+                            )
+                                .into());
+                        }
+                        let module = get_module(Spanned {
+                            node: &namespace.node,
+                            span: namespace.span,
+                        })?;
+                        module.set_var(name.map_node(Identifier::from), val, default)?;
+                        values.clear();
+                        continue;
+                    }
                     if global {
                         insert_global_var(&name.node, val.clone())?;
                     }
@@ -347,6 +427,12 @@ pub(crate) fn eat_expr<I: Iterator<Item = Token>>(
                     super_selector,
                     content,
                 )?;
+                if let AtRule::Warn | AtRule::Debug = rule.node {
+                    // the message has been logged, so there is nothing left
+                    // of the rule
+                    is_leading_whitespace = true;
+                    continue;
+                }
                 return Ok(Some(Spanned {
                     node: match rule.node {
                         AtRule::Mixin(name, mixin) => Expr::MixinDecl(name, mixin),
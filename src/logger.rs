@@ -0,0 +1,146 @@
+//! # Loggers
+//!
+//! Loggers receive the messages printed by `@warn` and `@debug`. By default
+//! they are written to stderr, but another logger can be registered with
+//! [`Options::logger`] to show them elsewhere or to inspect them in tests.
+//!
+//! [`Options::logger`]: crate::Options::logger
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use codemap::{CodeMap, Span};
+
+use crate::context::current;
+use crate::error::SourceLocation;
+use crate::options::Options;
+
+/// Receives the messages printed by `@warn` and `@debug`
+///
+/// ```
+/// use grass::{CollectingLogger, LogKind, Options, SassResult, StyleSheet};
+///
+/// fn main() -> SassResult<()> {
+///     let logger = CollectingLogger::new();
+///     StyleSheet::new_with_options(
+///         "@warn deprecated;".to_string(),
+///         &Options::default().logger(logger.clone()),
+///     )?;
+///     let messages = logger.messages();
+///     assert_eq!(messages[0].kind, LogKind::Warning);
+///     assert_eq!(messages[0].message, "deprecated");
+///     assert_eq!(messages[0].location.line, 1);
+///     Ok(())
+/// }
+/// ```
+pub trait Logger: fmt::Debug + Send + Sync {
+    fn log(&self, message: &LogMessage);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogKind {
+    /// Printed by `@warn`
+    Warning,
+    /// Printed by `@debug`
+    Debug,
+}
+
+/// A message printed by `@warn` or `@debug`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogMessage {
+    pub kind: LogKind,
+    pub message: String,
+    /// The location of the rule that printed the message
+    pub location: SourceLocation,
+}
+
+/// Writes messages to stderr, in the same format as `dart-sass`
+///
+/// This is the default logger
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StderrLogger;
+
+impl Logger for StderrLogger {
+    fn log(&self, message: &LogMessage) {
+        let location = &message.location;
+        match message.kind {
+            LogKind::Debug => eprintln!(
+                "{}:{} Debug: {}",
+                location.file, location.line, message.message
+            ),
+            // the warning is labelled with the file it came from, which is
+            // not always the root stylesheet
+            LogKind::Warning => eprintln!(
+                "Warning: {}\n    {} {}:{}  {}",
+                message.message, location.file, location.line, location.column, location.file
+            ),
+        }
+    }
+}
+
+/// Ignores every message
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SilentLogger;
+
+impl Logger for SilentLogger {
+    fn log(&self, _: &LogMessage) {}
+}
+
+/// Keeps every message so that they can be read after compiling
+///
+/// Clones of a `CollectingLogger` share the same messages, so one clone can
+/// be registered with [`Options::logger`] and another used to read them.
+///
+/// [`Options::logger`]: crate::Options::logger
+#[derive(Debug, Clone, Default)]
+pub struct CollectingLogger {
+    messages: Arc<Mutex<Vec<LogMessage>>>,
+}
+
+impl CollectingLogger {
+    pub fn new() -> Self {
+        CollectingLogger::default()
+    }
+
+    /// The messages logged so far, in the order they were logged
+    pub fn messages(&self) -> Vec<LogMessage> {
+        self.messages.lock().unwrap().clone()
+    }
+
+    /// Remove and return the messages logged so far
+    pub fn take(&self) -> Vec<LogMessage> {
+        std::mem::take(&mut *self.messages.lock().unwrap())
+    }
+}
+
+impl Logger for CollectingLogger {
+    fn log(&self, message: &LogMessage) {
+        self.messages.lock().unwrap().push(message.clone());
+    }
+}
+
+/// Hold on to a message printed at `span` until it can be passed to the
+/// logger
+///
+/// Messages are printed deep inside evaluation, where the files needed to
+/// locate them aren't available, so they are passed on by [`flush`] once
+/// the compilation has finished
+pub(crate) fn log(kind: LogKind, span: Span, message: String) {
+    current().messages.borrow_mut().push((kind, span, message));
+}
+
+/// Pass the messages printed so far to the logger in `options`, in the
+/// order they were printed, unless `options` is quiet
+pub(crate) fn flush(map: &CodeMap, options: &Options) {
+    let messages = std::mem::take(&mut *current().messages.borrow_mut());
+    if options.quiet {
+        return;
+    }
+    for (kind, span, message) in messages {
+        options.logger.log(&LogMessage {
+            kind,
+            message,
+            location: SourceLocation::from(map.look_up_span(span)),
+        });
+    }
+}
@@ -1,9 +1,11 @@
-use std::fs::File;
-use std::io::{stdout, BufWriter, Write};
+use std::env;
+use std::fs::{self, File};
+use std::io::{stdin, stdout, BufWriter, Read, Write};
+use std::path::PathBuf;
 
-use clap::{arg_enum, App, Arg};
+use clap::{arg_enum, value_t, App, Arg};
 
-use grass::StyleSheet;
+use grass::{Options, OutputStyle, StyleSheet, Syntax};
 
 arg_enum! {
     #[derive(PartialEq, Debug)]
@@ -50,6 +52,19 @@ fn main() -> std::io::Result<()> {
                 .takes_value(true)
                 .number_of_values(1),
         )
+        .arg(
+            Arg::with_name("DEFINE")
+                .short("D")
+                .long("define")
+                .help("Define a global variable as NAME=VALUE, where VALUE is SassScript. May be passed multiple times.")
+                .multiple(true)
+                .takes_value(true)
+                .number_of_values(1)
+                .validator(|define| match define.find('=') {
+                    Some(idx) if idx > 0 => Ok(()),
+                    _ => Err(format!("expected NAME=VALUE, found \"{}\"", define)),
+                }),
+        )
         .arg(
             Arg::with_name("STYLE")
                 .short("s")
@@ -144,7 +159,7 @@ fn main() -> std::io::Result<()> {
         )
         .arg(
             Arg::with_name("INPUT")
-                .required(true)
+                .required_unless("STDIN")
                 .help("SCSS files"),
         )
         .arg(
@@ -161,28 +176,90 @@ fn main() -> std::io::Result<()> {
         )
         .get_matches();
 
-    if let Some(name) = matches.value_of("INPUT") {
-        if let Some(path) = matches.value_of("OUTPUT") {
-            let mut buf = BufWriter::new(File::open(path).unwrap_or(File::create(path)?));
-            buf.write_all(
-                StyleSheet::from_path(name)
-                    .unwrap_or_else(|e| {
-                        eprintln!("{}", e);
-                        std::process::exit(1)
-                    })
-                    .as_bytes(),
-            )?;
-        } else {
-            let mut stdout = BufWriter::new(stdout());
-            stdout.write_all(
-                StyleSheet::from_path(name)
-                    .unwrap_or_else(|e| {
-                        eprintln!("{}", e);
-                        std::process::exit(1)
-                    })
-                    .as_bytes(),
-            )?;
+    let mut load_paths: Vec<PathBuf> = matches
+        .values_of("LOAD_PATH")
+        .map(|paths| paths.map(PathBuf::from).collect())
+        .unwrap_or_default();
+    // directories in `SASS_PATH` are searched after any passed explicitly
+    if let Some(sass_path) = env::var_os("SASS_PATH") {
+        load_paths.extend(env::split_paths(&sass_path));
+    }
+
+    let (input, output) = if matches.is_present("STDIN") {
+        // with `--stdin`, the only positional argument is the output file
+        (None, matches.value_of("INPUT"))
+    } else {
+        (matches.value_of("INPUT"), matches.value_of("OUTPUT"))
+    };
+
+    let source_map = !matches.is_present("NO_SOURCE_MAP");
+    let embed_source_map = source_map && matches.is_present("EMBED_SOURCE_MAP");
+    let embed_sources = matches.is_present("EMBED_SOURCES");
+    // without an output file, a source map can only be embedded in the CSS
+    let writes_source_map = source_map && !embed_source_map && output.is_some();
+
+    let mut options = Options::default()
+        .style(
+            match value_t!(matches, "STYLE", Style).unwrap_or_else(|e| e.exit()) {
+                Style::Expanded => OutputStyle::Expanded,
+                Style::Compressed => OutputStyle::Compressed,
+            },
+        )
+        .load_paths(&load_paths)
+        .quiet(matches.is_present("QUIET"))
+        .allows_charset(!matches.is_present("NO_CHARSET"))
+        .source_map_urls(
+            match value_t!(matches, "SOURCE_MAP_URLS", SourceMapUrls).unwrap_or_else(|e| e.exit()) {
+                SourceMapUrls::Relative => grass::SourceMapUrls::Relative,
+                SourceMapUrls::Absolute => grass::SourceMapUrls::Absolute,
+            },
+        )
+        .embed_sources(embed_sources)
+        .embed_source_map(embed_source_map);
+    if matches.is_present("PRECISION") {
+        options =
+            options.precision(value_t!(matches, "PRECISION", usize).unwrap_or_else(|e| e.exit()));
+    }
+    if matches.is_present("INDENTED") {
+        options = options.syntax(Syntax::Sass);
+    }
+    if let Some(path) = output {
+        options = options.output_path(path);
+    }
+    for define in matches.values_of("DEFINE").into_iter().flatten() {
+        let idx = define.find('=').unwrap();
+        options = options.define(&define[..idx], &define[idx + 1..]);
+    }
+
+    let result = match input {
+        Some(name) if writes_source_map => StyleSheet::from_path_with_source_map(name, &options)
+            .map(|(css, source_map)| (css, Some(source_map))),
+        Some(name) => StyleSheet::from_path_with_options(name, &options).map(|css| (css, None)),
+        None => {
+            let mut input = String::new();
+            stdin().read_to_string(&mut input)?;
+            if writes_source_map {
+                StyleSheet::new_with_source_map(input, &options)
+                    .map(|(css, source_map)| (css, Some(source_map)))
+            } else {
+                StyleSheet::new_with_options(input, &options).map(|css| (css, None))
+            }
+        }
+    };
+    let (css, source_map) = result.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1)
+    });
+
+    if let Some(path) = output {
+        if let Some(source_map) = source_map {
+            fs::write(format!("{}.map", path), source_map.to_json(embed_sources))?;
         }
+        let mut buf = BufWriter::new(File::create(path)?);
+        buf.write_all(css.as_bytes())?;
+    } else {
+        let mut stdout = BufWriter::new(stdout());
+        stdout.write_all(css.as_bytes())?;
     }
     Ok(())
 }
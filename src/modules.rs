@@ -0,0 +1,578 @@
+//! The module system: stylesheets loaded with `@use` and `@forward`
+//!
+//! Each module is evaluated in its own global scope. The members it defines
+//! are only visible to the loading stylesheet through a namespace, e.g.
+//! `colors.$primary`, or without a namespace when loaded `as *`.
+//!
+//! A module may also re-export the members of other modules with `@forward`.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use codemap::{CodeMap, Span, Spanned};
+
+use crate::args::CallArgs;
+use crate::atrule::{Function, Mixin};
+use crate::builtin::{builtin_module_variables, Builtin, GlobalFunctionMap, BUILTIN_MODULES};
+use crate::common::Identifier;
+use crate::context::current;
+use crate::error::{SassError, SassResult};
+use crate::imports::{check_not_loading, Resolved};
+use crate::options::Options;
+use crate::scope::Scope;
+use crate::selector::Selector;
+use crate::value::Value;
+use crate::{Stmt, StyleSheet};
+
+/// A single evaluated module
+#[derive(Debug, Clone)]
+pub(crate) struct Module {
+    /// The global scope of the module once it has finished evaluating
+    ///
+    /// Its variables may still be assigned by the stylesheets using it, e.g.
+    /// `colors.$primary: blue`
+    scope: Rc<RefCell<Scope>>,
+    /// The modules loaded by this module, needed when calling its mixins
+    /// and functions
+    modules: Modules,
+    /// The functions of a built-in module such as `sass:math`
+    builtins: GlobalFunctionMap,
+    /// The canonical path the module was loaded from, which is `None` for
+    /// built-in modules
+    key: Option<PathBuf>,
+}
+
+/// A function made available by a module
+#[derive(Clone)]
+pub(crate) enum ModuleFunction {
+    Builtin(Builtin),
+    /// A function declared with `@function`, along with the module it was
+    /// declared in
+    UserDefined(Function, Rc<Module>),
+}
+
+impl ModuleFunction {
+    /// Call this function, which is named `name` at the call
+    pub fn call(
+        self,
+        args: CallArgs,
+        scope: &Scope,
+        super_selector: &Selector,
+        name: Spanned<&Identifier>,
+    ) -> SassResult<Value> {
+        match self {
+            Self::Builtin(f) => f.call(args, scope, super_selector),
+            Self::UserDefined(f, module) => {
+                f.eval_in_module(args, scope, super_selector, &module, name)
+            }
+        }
+    }
+}
+
+/// The modules visible to a stylesheet
+#[derive(Debug, Clone)]
+pub(crate) struct Modules {
+    namespaced: HashMap<String, Rc<Module>>,
+    /// Modules loaded with `as *`
+    global: Vec<Rc<Module>>,
+    /// Modules made available to users of this stylesheet by `@forward`
+    ///
+    /// Their members are not visible to the stylesheet itself
+    forwarded: Vec<ForwardedModule>,
+}
+
+#[derive(Debug, Clone)]
+struct ForwardedModule {
+    module: Rc<Module>,
+    forwarding: Forwarding,
+}
+
+/// Which members of a module are forwarded, and under what names
+///
+/// `@forward "src/buttons" as btn-* show btn-color, $btn-radius;`
+#[derive(Debug, Clone)]
+pub(crate) struct Forwarding {
+    /// Prepended to the name of every forwarded member
+    pub prefix: Option<Identifier>,
+    pub visibility: Visibility,
+}
+
+/// The names listed in a `show` or `hide` clause, after the prefix has been
+/// applied
+#[derive(Debug, Clone)]
+pub(crate) enum Visibility {
+    All,
+    Show(MemberNames),
+    Hide(MemberNames),
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MemberNames {
+    pub variables: HashSet<Identifier>,
+    /// Mixins and functions, which share a single list
+    pub callables: HashSet<Identifier>,
+}
+
+/// The namespace a module is made available under
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum Namespace {
+    Named(String),
+    /// `@use "foo" as *`
+    Global,
+}
+
+/// Variables passed to a module in a `with (...)` clause
+///
+/// The span of each value covers the whole `$name: value` declaration
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Configuration(HashMap<Identifier, ConfiguredValue>);
+
+#[derive(Debug, Clone)]
+struct ConfiguredValue {
+    value: Spanned<Value>,
+    /// Only possible in `@forward ... with`, where it allows the user of the
+    /// forwarding module to override the value
+    is_default: bool,
+}
+
+impl Configuration {
+    pub fn insert(
+        &mut self,
+        name: Spanned<Identifier>,
+        value: Spanned<Value>,
+        is_default: bool,
+    ) -> SassResult<()> {
+        if self.0.contains_key(&name.node) {
+            return Err(SassError::runtime(
+                "The same variable may only be configured once.",
+                name.span,
+            ));
+        }
+        self.0
+            .insert(name.node, ConfiguredValue { value, is_default });
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn remove(&mut self, name: &Identifier) -> Option<Spanned<Value>> {
+        self.0.remove(name).map(|v| v.value)
+    }
+}
+
+impl Visibility {
+    fn allows(&self, name: &Identifier, is_var: bool) -> bool {
+        let contains = |names: &MemberNames| {
+            if is_var {
+                names.variables.contains(name)
+            } else {
+                names.callables.contains(name)
+            }
+        };
+        match self {
+            Self::All => true,
+            Self::Show(names) => contains(names),
+            Self::Hide(names) => !contains(names),
+        }
+    }
+}
+
+impl Forwarding {
+    /// The name of the member of the forwarded module that is visible to
+    /// users of the forwarding module as `name`, if any
+    fn inner_name(&self, name: &Identifier, is_var: bool) -> Option<Identifier> {
+        if !self.visibility.allows(name, is_var) {
+            return None;
+        }
+        match &self.prefix {
+            Some(prefix) if name.as_str().starts_with(prefix.as_str()) => {
+                Some(Identifier::from(&name.as_str()[prefix.as_str().len()..]))
+            }
+            Some(..) => None,
+            None => Some(name.clone()),
+        }
+    }
+}
+
+fn is_private(name: &Identifier) -> bool {
+    name.as_str().starts_with('-')
+}
+
+impl Module {
+    pub fn get_var(self: &Rc<Self>, name: Spanned<Identifier>) -> SassResult<Spanned<Value>> {
+        if is_private(&name.node) {
+            return Err(SassError::runtime(
+                "Private members can't be accessed from outside their modules.",
+                name.span,
+            ));
+        }
+        match self.public_var(&name.node) {
+            Some(v) => Ok(v),
+            None => Err(SassError::runtime("Undefined variable.", name.span)),
+        }
+    }
+
+    /// Assign `value` to the variable `name` of this module, as in
+    /// `colors.$primary: blue`
+    ///
+    /// Only variables the module has declared may be assigned. If `default`
+    /// the variable is only assigned if it is `null`
+    pub fn set_var(
+        self: &Rc<Self>,
+        name: Spanned<Identifier>,
+        value: Spanned<Value>,
+        default: bool,
+    ) -> SassResult<()> {
+        if is_private(&name.node) {
+            return Err(SassError::runtime(
+                "Private members can't be accessed from outside their modules.",
+                name.span,
+            ));
+        }
+        if self.assign_public_var(&name.node, name.span, value, default)? {
+            Ok(())
+        } else {
+            Err(SassError::runtime("Undefined variable.", name.span))
+        }
+    }
+
+    /// Assign a variable declared by this module or forwarded by it,
+    /// returning whether it exists
+    fn assign_public_var(
+        &self,
+        name: &Identifier,
+        span: Span,
+        value: Spanned<Value>,
+        default: bool,
+    ) -> SassResult<bool> {
+        let existing = self.scope.borrow().vars().get(name).cloned();
+        if let Some(existing) = existing {
+            if self.key.is_none() {
+                return Err(SassError::runtime("Cannot modify built-in variable.", span));
+            }
+            if !(default && !existing.node.is_null(existing.span)?) {
+                self.scope.borrow_mut().insert_var(name.clone(), value)?;
+            }
+            return Ok(true);
+        }
+        for f in &self.modules.forwarded {
+            if let Some(name) = f.forwarding.inner_name(name, true) {
+                if is_private(&name) {
+                    continue;
+                }
+                if f.module
+                    .assign_public_var(&name, span, value.clone(), default)?
+                {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    pub fn get_fn(self: &Rc<Self>, name: Spanned<Identifier>) -> SassResult<ModuleFunction> {
+        if is_private(&name.node) {
+            return Err(SassError::runtime(
+                "Private members can't be accessed from outside their modules.",
+                name.span,
+            ));
+        }
+        match self.public_fn(&name.node) {
+            Some(v) => Ok(v),
+            None => Err(SassError::runtime("Undefined function.", name.span)),
+        }
+    }
+
+    /// Look up a mixin, along with the module it was defined in
+    pub fn get_mixin(
+        self: &Rc<Self>,
+        name: Spanned<Identifier>,
+    ) -> SassResult<(Mixin, Rc<Module>)> {
+        if is_private(&name.node) {
+            return Err(SassError::runtime(
+                "Private members can't be accessed from outside their modules.",
+                name.span,
+            ));
+        }
+        match self.public_mixin(&name.node) {
+            Some(v) => Ok(v),
+            None => Err(SassError::runtime("Undefined mixin.", name.span)),
+        }
+    }
+
+    fn public_var(self: &Rc<Self>, name: &Identifier) -> Option<Spanned<Value>> {
+        if is_private(name) {
+            return None;
+        }
+        if let Some(v) = self.scope.borrow().vars().get(name) {
+            return Some(v.clone());
+        }
+        self.modules.forwarded.iter().find_map(|f| {
+            let name = f.forwarding.inner_name(name, true)?;
+            f.module.public_var(&name)
+        })
+    }
+
+    pub fn fn_exists(self: &Rc<Self>, name: &Identifier) -> bool {
+        self.public_fn(name).is_some()
+    }
+
+    fn public_fn(self: &Rc<Self>, name: &Identifier) -> Option<ModuleFunction> {
+        if is_private(name) {
+            return None;
+        }
+        if let Some(f) = self.scope.borrow().functions().get(name) {
+            return Some(ModuleFunction::UserDefined(f.clone(), Rc::clone(self)));
+        }
+        if let Some(f) = self.builtins.get(name.as_str()) {
+            return Some(ModuleFunction::Builtin(f.clone()));
+        }
+        self.modules.forwarded.iter().find_map(|f| {
+            let name = f.forwarding.inner_name(name, false)?;
+            f.module.public_fn(&name)
+        })
+    }
+
+    fn public_mixin(self: &Rc<Self>, name: &Identifier) -> Option<(Mixin, Rc<Module>)> {
+        if is_private(name) {
+            return None;
+        }
+        if let Some(m) = self.scope.borrow().mixins().get(name) {
+            return Some((m.clone(), Rc::clone(self)));
+        }
+        self.modules.forwarded.iter().find_map(|f| {
+            let name = f.forwarding.inner_name(name, false)?;
+            f.module.public_mixin(&name)
+        })
+    }
+
+    /// The canonical path this module was loaded from, or `None` if it is a
+    /// built-in module
+    pub fn key(&self) -> Option<&PathBuf> {
+        self.key.as_ref()
+    }
+
+    /// Run `f` with the global scope and namespaces of this module, so that
+    /// the bodies of its mixins and functions resolve members the same way
+    /// they would have inside the module itself
+    ///
+    /// The module's scope is shared rather than copied, so any global
+    /// variables `f` assigns are kept in the module
+    pub fn enter<T, F: FnOnce() -> SassResult<T>>(&self, f: F) -> SassResult<T> {
+        let state = current();
+        let scope = state.global_scope.replace(Rc::clone(&self.scope));
+        let modules = state.modules.replace(self.modules.clone());
+        let result = f();
+        state.global_scope.replace(scope);
+        state.modules.replace(modules);
+        result
+    }
+}
+
+impl Modules {
+    pub fn new() -> Self {
+        Modules {
+            namespaced: HashMap::new(),
+            global: Vec::new(),
+            forwarded: Vec::new(),
+        }
+    }
+
+    pub fn forward(&mut self, module: Rc<Module>, forwarding: Forwarding) {
+        self.forwarded.push(ForwardedModule { module, forwarding });
+    }
+
+    pub fn insert(&mut self, namespace: Spanned<Namespace>, module: Rc<Module>) -> SassResult<()> {
+        match namespace.node {
+            Namespace::Named(name) => {
+                if self.namespaced.contains_key(&name) {
+                    return Err(SassError::runtime(
+                        format!("There's already a module with namespace \"{}\".", name),
+                        namespace.span,
+                    ));
+                }
+                self.namespaced.insert(name, module);
+            }
+            Namespace::Global => self.global.push(module),
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, namespace: Spanned<&str>) -> SassResult<Rc<Module>> {
+        match self.namespaced.get(namespace.node) {
+            Some(m) => Ok(Rc::clone(m)),
+            None => Err(SassError::runtime(
+                format!(
+                    "There is no module with the namespace \"{}\".",
+                    namespace.node
+                ),
+                namespace.span,
+            )),
+        }
+    }
+
+    pub fn get_global_var(&self, name: &Identifier) -> Option<Spanned<Value>> {
+        self.global.iter().find_map(|m| m.public_var(name))
+    }
+
+    pub fn get_global_fn(&self, name: &Identifier) -> Option<ModuleFunction> {
+        self.global.iter().find_map(|m| m.public_fn(name))
+    }
+
+    pub fn get_global_mixin(&self, name: &Identifier) -> Option<(Mixin, Rc<Module>)> {
+        self.global.iter().find_map(|m| m.public_mixin(name))
+    }
+}
+
+pub(crate) fn get_module(namespace: Spanned<&str>) -> SassResult<Rc<Module>> {
+    current().modules.borrow().get(namespace)
+}
+
+/// Find a function made available without a namespace by `@use ... as *`
+pub(crate) fn get_global_module_fn(name: &Identifier) -> Option<ModuleFunction> {
+    current().modules.borrow().get_global_fn(name)
+}
+
+/// Find a mixin made available without a namespace by `@use ... as *`
+pub(crate) fn get_global_module_mixin(name: &Identifier) -> Option<(Mixin, Rc<Module>)> {
+    current().modules.borrow().get_global_mixin(name)
+}
+
+/// Take the configured value of a variable declared with `!default` at the
+/// top level of the module currently being evaluated
+pub(crate) fn take_configured_var<T: Into<Identifier>>(name: T) -> Option<Spanned<Value>> {
+    let name = name.into();
+    current().module_config.borrow_mut().remove(&name)
+}
+
+/// Combine the `with` clause of an `@forward` rule with the configuration of
+/// the module containing it
+///
+/// Variables configured by the user of the forwarding module are passed on to
+/// the forwarded module, taking precedence over values declared `!default` in
+/// the `with` clause.
+pub(crate) fn forwarded_configuration(
+    mut config: Configuration,
+    forwarding: &Forwarding,
+) -> Configuration {
+    let state = current();
+    let mut outer = state.module_config.borrow_mut();
+    let names: Vec<Identifier> = outer.0.keys().cloned().collect();
+    for name in names {
+        let inner_name = match forwarding.inner_name(&name, true) {
+            Some(inner_name) => inner_name,
+            None => continue,
+        };
+        if let Some(ConfiguredValue {
+            is_default: false, ..
+        }) = config.0.get(&inner_name)
+        {
+            continue;
+        }
+        if let Some(value) = outer.0.remove(&name) {
+            config.0.insert(inner_name, value);
+        }
+    }
+    config
+}
+
+/// Load the module at `path`, evaluating it if it has not yet been loaded
+///
+/// The CSS emitted by the module is returned only the first time it is loaded
+pub(crate) fn load_module(
+    stylesheet: &Resolved,
+    map: &mut CodeMap,
+    options: &Options,
+    loading: &mut Vec<PathBuf>,
+    span: Span,
+    config: Configuration,
+) -> SassResult<(Vec<Spanned<Stmt>>, Rc<Module>)> {
+    let key = stylesheet.canonical();
+    let state = current();
+    let loaded = state.loaded_modules.borrow().get(&key).cloned();
+    if let Some(module) = loaded {
+        if !config.is_empty() {
+            return Err(SassError::runtime(
+                "This module was already loaded, so it can't be configured using \"with\".",
+                span,
+            ));
+        }
+        return Ok((Vec::new(), module));
+    }
+    check_not_loading(
+        stylesheet.path(),
+        loading,
+        "Module loop: this module is already being loaded.",
+        span,
+    )?;
+
+    let scope = state
+        .global_scope
+        .replace(Rc::new(RefCell::new(Scope::new())));
+    let modules = state.modules.replace(Modules::new());
+    let outer_config = state.module_config.replace(config);
+    let result = StyleSheet::export(stylesheet, map, options, loading, span);
+    let unused_config = state.module_config.replace(outer_config);
+    let module_modules = state.modules.replace(modules);
+    state.global_scope.replace(scope);
+
+    let (stmts, module_scope) = result?;
+
+    if let Some(configured) = unused_config.0.values().next() {
+        return Err(SassError::runtime(
+            "This variable was not declared with !default in the @used module.",
+            configured.value.span,
+        ));
+    }
+
+    let module = Rc::new(Module {
+        scope: Rc::new(RefCell::new(module_scope)),
+        modules: module_modules,
+        builtins: GlobalFunctionMap::new(),
+        key: Some(key.clone()),
+    });
+    state
+        .loaded_modules
+        .borrow_mut()
+        .insert(key, Rc::clone(&module));
+    Ok((stmts, module))
+}
+
+/// The module loaded from `key` during the current compilation, if any
+pub(crate) fn loaded_module(key: &Path) -> Option<Rc<Module>> {
+    current().loaded_modules.borrow().get(key).cloned()
+}
+
+/// Load a built-in module, given the part of its url following `sass:`
+pub(crate) fn load_builtin_module(
+    name: &str,
+    span: Span,
+    config: &Configuration,
+) -> SassResult<Rc<Module>> {
+    let builtins = match BUILTIN_MODULES.get(name) {
+        Some(builtins) => builtins.clone(),
+        None => return Err(SassError::runtime("Can't find stylesheet to import.", span)),
+    };
+    if !config.is_empty() {
+        return Err(SassError::runtime(
+            "Built-in modules can't be configured.",
+            span,
+        ));
+    }
+
+    let mut scope = Scope::new();
+    for (name, value) in builtin_module_variables(name) {
+        scope.insert_var(name, Spanned { node: value, span })?;
+    }
+
+    Ok(Rc::new(Module {
+        scope: Rc::new(RefCell::new(scope)),
+        modules: Modules::new(),
+        builtins,
+        key: None,
+    }))
+}
@@ -0,0 +1,282 @@
+//! # Options for compiling a stylesheet
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::global_variable::GlobalVariable;
+use crate::host_function::HostFunction;
+use crate::importer::Importer;
+use crate::logger::{Logger, StderrLogger};
+use crate::output::OutputStyle;
+use crate::source_map::SourceMapUrls;
+use crate::stylesheet::Syntax;
+use crate::value::Value;
+
+/// The number of digits after the decimal point numbers are written with by
+/// default
+pub(crate) const DEFAULT_PRECISION: usize = 10;
+
+/// Configuration for compiling a stylesheet
+///
+/// Options are built up from their defaults, which match those of
+/// `StyleSheet::new` and `StyleSheet::from_path`
+///
+/// ```
+/// use grass::{Options, OutputStyle, SassResult, StyleSheet};
+///
+/// fn main() -> SassResult<()> {
+///     let options = Options::default()
+///         .style(OutputStyle::Compressed)
+///         .quiet(true);
+///     let css = StyleSheet::new_with_options("a { color: red; }".to_string(), &options)?;
+///     assert_eq!(css, "a{color:red}");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub(crate) style: OutputStyle,
+    pub(crate) load_paths: Vec<PathBuf>,
+    pub(crate) importers: Vec<Arc<dyn Importer>>,
+    pub(crate) functions: Vec<HostFunction>,
+    pub(crate) variables: Vec<(String, GlobalVariable)>,
+    pub(crate) logger: Arc<dyn Logger>,
+    pub(crate) quiet: bool,
+    pub(crate) allows_charset: bool,
+    pub(crate) precision: usize,
+    pub(crate) syntax: Option<Syntax>,
+    pub(crate) source_map_urls: SourceMapUrls,
+    pub(crate) embed_sources: bool,
+    pub(crate) embed_source_map: bool,
+    pub(crate) output_path: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            style: OutputStyle::Expanded,
+            load_paths: Vec::new(),
+            importers: Vec::new(),
+            functions: Vec::new(),
+            variables: Vec::new(),
+            logger: Arc::new(StderrLogger),
+            quiet: false,
+            allows_charset: true,
+            precision: DEFAULT_PRECISION,
+            syntax: None,
+            source_map_urls: SourceMapUrls::Relative,
+            embed_sources: false,
+            embed_source_map: false,
+            output_path: None,
+        }
+    }
+}
+
+impl Options {
+    /// The formatting of the generated CSS
+    ///
+    /// Defaults to `OutputStyle::Expanded`
+    #[must_use]
+    pub const fn style(mut self, style: OutputStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Add a directory to search for imports that can't be found relative to
+    /// the importing file
+    ///
+    /// Load paths are searched in the order they are added
+    #[must_use]
+    pub fn load_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.load_paths.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Add several directories to search for imports, as with
+    /// [`Options::load_path`]
+    #[must_use]
+    pub fn load_paths<P: AsRef<Path>>(mut self, paths: &[P]) -> Self {
+        self.load_paths
+            .extend(paths.iter().map(|path| path.as_ref().to_path_buf()));
+        self
+    }
+
+    /// Add an importer to load stylesheets from somewhere other than the
+    /// filesystem
+    ///
+    /// Importers are tried in the order they are added, before imports are
+    /// searched for relative to the importing file or in the load paths
+    #[must_use]
+    pub fn importer<I: Importer + 'static>(mut self, importer: I) -> Self {
+        self.importers.push(Arc::new(importer));
+        self
+    }
+
+    /// Define a global function, implemented in rust, that can be called from
+    /// Sass
+    ///
+    /// `signature` declares the name and arguments of the function in the
+    /// same way as an `@function` rule, e.g. `"asset-url($path, $hash: true)"`.
+    /// `function` is passed the value of each argument in order, with any
+    /// variable arguments as a single list, and an error it returns is
+    /// reported at the call.
+    ///
+    /// Functions defined this way take precedence over the built-in
+    /// functions, but not over functions declared in the stylesheet
+    ///
+    /// ```
+    /// use grass::{Options, SassResult, StyleSheet, Value};
+    ///
+    /// fn main() -> SassResult<()> {
+    ///     let options = Options::default().function("asset-url($path)", |args| {
+    ///         let path = args[0].assert_string("path")?;
+    ///         Ok(Value::unquoted_string(format!("url(\"/assets/{}\")", path)))
+    ///     });
+    ///     let css = StyleSheet::new_with_options(
+    ///         "a {\n  background: asset-url(\"a.png\");\n}\n".to_string(),
+    ///         &options,
+    ///     )?;
+    ///     assert_eq!(css, "a {\n  background: url(\"/assets/a.png\");\n}\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn function<F>(mut self, signature: &str, function: F) -> Self
+    where
+        F: Fn(&[Value]) -> Result<Value, String> + Send + Sync + 'static,
+    {
+        self.functions
+            .push(HostFunction::new(signature.to_owned(), function));
+        self
+    }
+
+    /// Define a global variable before the stylesheet is evaluated
+    ///
+    /// The variable is visible to the stylesheet being compiled and any it
+    /// imports with `@import`. A declaration of the variable with `!default`
+    /// doesn't replace it, so this can be used to configure a stylesheet
+    /// without changing it. The `$` at the start of `name` is optional.
+    ///
+    /// ```
+    /// use grass::{Options, SassResult, StyleSheet, Value};
+    ///
+    /// fn main() -> SassResult<()> {
+    ///     let options = Options::default().variable("brand", Value::quoted_string("acme"));
+    ///     let css = StyleSheet::new_with_options(
+    ///         "$brand: \"default\" !default;\na {\n  content: $brand;\n}\n".to_string(),
+    ///         &options,
+    ///     )?;
+    ///     assert_eq!(css, "a {\n  content: \"acme\";\n}\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn variable(mut self, name: &str, value: Value) -> Self {
+        self.variables.push((
+            name.trim_start_matches('$').to_owned(),
+            GlobalVariable::Value(value),
+        ));
+        self
+    }
+
+    /// Define a global variable whose value is the SassScript `expression`,
+    /// such as `"acme"` or `(primary: red, secondary: blue)`
+    ///
+    /// The expression is evaluated before the stylesheet, and may refer to
+    /// variables defined before it. Otherwise, this is the same as
+    /// [`Options::variable`].
+    #[must_use]
+    pub fn define(mut self, name: &str, expression: &str) -> Self {
+        self.variables.push((
+            name.trim_start_matches('$').to_owned(),
+            GlobalVariable::Expression(expression.to_owned()),
+        ));
+        self
+    }
+
+    /// Where the messages printed by `@warn` and `@debug` are sent
+    ///
+    /// Defaults to `StderrLogger`
+    #[must_use]
+    pub fn logger<L: Logger + 'static>(mut self, logger: L) -> Self {
+        self.logger = Arc::new(logger);
+        self
+    }
+
+    /// Whether to silence the output of `@warn` and `@debug`, which are then
+    /// never passed to the logger
+    ///
+    /// Defaults to `false`
+    #[must_use]
+    pub const fn quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    /// Whether to mark CSS containing non-ASCII characters with `@charset`,
+    /// or a byte order mark in compressed output
+    ///
+    /// Defaults to `true`
+    #[must_use]
+    pub const fn allows_charset(mut self, allows_charset: bool) -> Self {
+        self.allows_charset = allows_charset;
+        self
+    }
+
+    /// The maximum number of digits after the decimal point in numbers
+    ///
+    /// Defaults to 10
+    #[must_use]
+    pub const fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// The syntax of the input
+    ///
+    /// By default, strings are parsed as SCSS and the syntax of files is
+    /// determined by their extension
+    #[must_use]
+    pub const fn syntax(mut self, syntax: Syntax) -> Self {
+        self.syntax = Some(syntax);
+        self
+    }
+
+    /// How source maps link to the stylesheets they were generated from
+    ///
+    /// Defaults to `SourceMapUrls::Relative`
+    #[must_use]
+    pub const fn source_map_urls(mut self, source_map_urls: SourceMapUrls) -> Self {
+        self.source_map_urls = source_map_urls;
+        self
+    }
+
+    /// Whether to include the contents of each stylesheet in source maps
+    ///
+    /// Defaults to `false`
+    #[must_use]
+    pub const fn embed_sources(mut self, embed_sources: bool) -> Self {
+        self.embed_sources = embed_sources;
+        self
+    }
+
+    /// Whether to embed the source map in the generated CSS as a `data:` URL
+    ///
+    /// Defaults to `false`
+    #[must_use]
+    pub const fn embed_source_map(mut self, embed_source_map: bool) -> Self {
+        self.embed_source_map = embed_source_map;
+        self
+    }
+
+    /// The path the generated CSS will be written to
+    ///
+    /// Relative URLs in source maps are relative to this path, and a source
+    /// map that isn't embedded is expected to be written alongside it, with
+    /// `.map` appended to its name
+    #[must_use]
+    pub fn output_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.output_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+}
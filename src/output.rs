@@ -1,113 +1,397 @@
 //! # Convert from SCSS AST to CSS
 use std::io::Write;
+use std::rc::Rc;
 
-use codemap::{CodeMap, Span};
+use codemap::{CodeMap, Span, Spanned};
 
-use crate::atrule::AtRule;
-use crate::error::SassResult;
+use crate::atrule::{AtRule, KeyframesRuleSet};
+use crate::error::{SassError, SassResult};
+use crate::options::Options;
+use crate::selector::ExtensionStore;
+use crate::source_map::SourceMap;
 use crate::{RuleSet, Selector, Stmt, Style, StyleSheet};
 
+/// The formatting of the compiled CSS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStyle {
+    /// Each declaration on its own line, with nested blocks indented
+    Expanded,
+    /// As little whitespace as possible, for the smallest possible output
+    Compressed,
+}
+
+impl Default for OutputStyle {
+    fn default() -> Self {
+        OutputStyle::Expanded
+    }
+}
+
+/// The CSS being written, along with the position the next write will start
+/// at, so that it can be mapped back to the source
+struct Output {
+    buf: Vec<u8>,
+    line: usize,
+    /// The column within `line`, in UTF-16 code units as source maps expect
+    column: usize,
+    source_map: SourceMap,
+}
+
+impl Output {
+    fn new() -> Self {
+        Output {
+            buf: Vec::new(),
+            line: 0,
+            column: 0,
+            source_map: SourceMap::new(),
+        }
+    }
+
+    /// Map the current position to the start of `span`
+    fn mark(&mut self, map: &CodeMap, span: Span) {
+        let loc = map.look_up_span(span);
+        self.source_map.add_mapping(
+            self.line,
+            self.column,
+            &loc.file,
+            loc.begin.line,
+            loc.begin.column,
+        );
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        for &b in bytes {
+            if b == b'\n' {
+                self.line += 1;
+                self.column = 0;
+            } else if b & 0b1100_0000 != 0b1000_0000 {
+                // characters outside of the basic multilingual plane, which
+                // take 4 bytes, are 2 UTF-16 code units
+                self.column += if b >= 0b1111_0000 { 2 } else { 1 };
+            }
+        }
+        self.buf.extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 enum Toplevel {
-    RuleSet(Selector, Vec<BlockEntry>),
+    RuleSet(Selector, Span, Vec<BlockEntry>),
     MultilineComment(String),
-    AtRule(AtRule),
+    AtRule(AtRule, Span),
     Newline,
-    Style(Box<Style>),
+    Style(Box<Style>, Span),
 }
 
 #[derive(Debug, Clone)]
 enum BlockEntry {
-    Style(Box<Style>),
+    Style(Box<Style>, Span),
     MultilineComment(String),
+    Import(String),
 }
 
 impl BlockEntry {
     pub fn to_string(&self) -> SassResult<String> {
         match self {
-            BlockEntry::Style(s) => s.to_string(),
+            BlockEntry::Style(s, _) => s.to_string(),
             BlockEntry::MultilineComment(s) => Ok(format!("/*{}*/", s)),
+            BlockEntry::Import(s) => Ok(format!("@import {};", s)),
+        }
+    }
+
+    /// The compressed form of this entry, or `None` if it is omitted from
+    /// compressed output entirely
+    fn to_compressed_string(&self) -> SassResult<Option<String>> {
+        Ok(match self {
+            BlockEntry::Style(s, _) => Some(s.to_compressed_string()?),
+            BlockEntry::MultilineComment(s) if is_preserved(s) => Some(format!("/*{}*/", s)),
+            BlockEntry::MultilineComment(..) => None,
+            BlockEntry::Import(s) => Some(format!("@import {}", s)),
+        })
+    }
+}
+
+/// The selector and entries of each non-empty block within `@keyframes`
+///
+/// Comments between the blocks aren't kept. Anything else that isn't allowed
+/// directly within `@keyframes` is an error
+fn keyframe_blocks(body: Vec<Spanned<Stmt>>) -> SassResult<Vec<(Vec<String>, Vec<BlockEntry>)>> {
+    let mut blocks = Vec::new();
+    for stmt in body {
+        match stmt.node {
+            Stmt::KeyframesRuleSet(k) => {
+                let KeyframesRuleSet { selector, body } = *k;
+                let entries = keyframes_entries(body)?;
+                if !entries.is_empty() {
+                    blocks.push((selector, entries));
+                }
+            }
+            Stmt::MultilineComment(..) => {}
+            Stmt::Style(..) => {
+                return Err((
+                    "Declarations may only be used within style rules.",
+                    stmt.span,
+                )
+                    .into())
+            }
+            Stmt::RuleSet(..) | Stmt::AtRule(..) => {
+                return Err(("This at-rule is not allowed here.", stmt.span).into())
+            }
+        }
+    }
+    Ok(blocks)
+}
+
+/// The declarations and comments within a single keyframe block
+fn keyframes_entries(body: Vec<Spanned<Stmt>>) -> SassResult<Vec<BlockEntry>> {
+    let mut entries = Vec::new();
+    for stmt in body {
+        match stmt.node {
+            Stmt::Style(s) => {
+                let s = s.eval()?;
+                if !s.value.is_null(s.value.span)? {
+                    entries.push(BlockEntry::Style(Box::new(s), stmt.span));
+                }
+            }
+            Stmt::MultilineComment(s) => entries.push(BlockEntry::MultilineComment(s)),
+            Stmt::RuleSet(..) | Stmt::KeyframesRuleSet(..) => {
+                return Err((
+                    "Style rules may not be used within keyframe blocks.",
+                    stmt.span,
+                )
+                    .into())
+            }
+            Stmt::AtRule(..) => return Err(("This at-rule is not allowed here.", stmt.span).into()),
+        }
+    }
+    Ok(entries)
+}
+
+/// Keyframe blocks are only read directly within `@keyframes`, but may be
+/// moved out of it by `@at-root`
+fn keyframe_block_outside_keyframes(span: Span) -> SassError {
+    ("Keyframe blocks may only be used within @keyframes.", span).into()
+}
+
+/// Loud comments, `/*! ... */`, are kept in compressed output
+fn is_preserved(comment: &str) -> bool {
+    comment.starts_with('!')
+}
+
+/// Write the entries of a block separated by semicolons, omitting the
+/// semicolon after the last entry
+fn write_compressed_entries(
+    buf: &mut Output,
+    map: &CodeMap,
+    entries: &[BlockEntry],
+) -> SassResult<()> {
+    let mut needs_semicolon = false;
+    for entry in entries {
+        let entry_str = match entry.to_compressed_string()? {
+            Some(entry_str) => entry_str,
+            None => continue,
+        };
+        if needs_semicolon {
+            write!(buf, ";")?;
+        }
+        if let BlockEntry::Style(_, span) = entry {
+            buf.mark(map, *span);
+        }
+        write!(buf, "{}", entry_str)?;
+        // comments aren't terminated by a semicolon
+        needs_semicolon = match entry {
+            BlockEntry::MultilineComment(..) => false,
+            _ => true,
+        };
+    }
+    Ok(())
+}
+
+/// Remove the whitespace around each of `separators` in `s`, which is either
+/// a selector or the prelude of an at-rule
+///
+/// Whitespace within quoted strings is untouched, and any other run of
+/// whitespace becomes a single space.
+fn compress_whitespace(s: &str, separators: &[char]) -> String {
+    let mut buf = String::with_capacity(s.len());
+    let mut quote = None;
+    let mut pending_space = false;
+    let mut after_separator = false;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            buf.push(c);
+            if c == '\\' {
+                buf.extend(chars.next());
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        if c.is_whitespace() {
+            pending_space = !after_separator;
+            continue;
+        }
+        let is_separator = separators.contains(&c);
+        if pending_space && !is_separator && !buf.is_empty() {
+            buf.push(' ');
+        }
+        pending_space = false;
+        after_separator = is_separator;
+        buf.push(c);
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '\\' => buf.extend(chars.next()),
+            _ => {}
         }
     }
+    buf
+}
+
+/// Selectors in compressed output have no whitespace around combinators or
+/// commas
+fn compress_selector(selector: &str) -> String {
+    compress_whitespace(selector, &['>', '+', '~', ','])
+}
+
+/// The queries of `@media` and conditions of `@supports` in compressed output
+/// have no whitespace after colons or commas
+fn compress_params(params: &str) -> String {
+    compress_whitespace(params, &[':', ','])
 }
 
 impl Toplevel {
-    const fn new_rule(selector: Selector) -> Self {
-        Toplevel::RuleSet(selector, Vec::new())
+    const fn new_rule(selector: Selector, span: Span) -> Self {
+        Toplevel::RuleSet(selector, span, Vec::new())
     }
 
-    fn push_style(&mut self, mut s: Style) -> SassResult<()> {
+    fn push_style(&mut self, mut s: Style, span: Span) -> SassResult<()> {
         s = s.eval()?;
         if s.value.is_null(s.value.span)? {
             return Ok(());
         }
-        if let Toplevel::RuleSet(_, entries) = self {
-            entries.push(BlockEntry::Style(Box::new(s)));
+        if let Toplevel::RuleSet(_, _, entries) = self {
+            entries.push(BlockEntry::Style(Box::new(s), span));
         }
         Ok(())
     }
 
     fn push_comment(&mut self, s: String) {
-        if let Toplevel::RuleSet(_, entries) = self {
+        if let Toplevel::RuleSet(_, _, entries) = self {
             entries.push(BlockEntry::MultilineComment(s));
         }
     }
+
+    fn push_imports(&mut self, imports: Vec<String>) {
+        if let Toplevel::RuleSet(_, _, entries) = self {
+            entries.extend(imports.into_iter().map(BlockEntry::Import));
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Css {
     blocks: Vec<Toplevel>,
+    extensions: Rc<ExtensionStore>,
+    /// The queries of the `@media` rules these blocks are nested within
+    media: Vec<String>,
 }
 
 impl Css {
-    pub const fn new() -> Self {
-        Css { blocks: Vec::new() }
+    fn new(extensions: Rc<ExtensionStore>, media: Vec<String>) -> Self {
+        Css {
+            blocks: Vec::new(),
+            extensions,
+            media,
+        }
     }
 
     pub fn from_stylesheet(s: StyleSheet) -> SassResult<Self> {
-        Css::new().parse_stylesheet(s)
+        let extensions = ExtensionStore::from_stmts(&s.0)?;
+        // plain CSS imports at the root of the stylesheet are hoisted above
+        // everything else, except for the comments written before the first
+        // statement that isn't an import, which stay in place
+        let mut stmts = s.0.into_iter().peekable();
+        let mut head = Vec::new();
+        while let Some(stmt) = stmts.next_if(|stmt| match stmt.node {
+            Stmt::AtRule(AtRule::Import(..)) | Stmt::MultilineComment(..) => true,
+            _ => false,
+        }) {
+            head.push(stmt);
+        }
+        let (imports, rest): (Vec<Spanned<Stmt>>, Vec<Spanned<Stmt>>) =
+            stmts.partition(|stmt| match stmt.node {
+                Stmt::AtRule(AtRule::Import(..)) => true,
+                _ => false,
+            });
+        let mut stmts = head;
+        stmts.extend(imports);
+        stmts.extend(rest);
+        Css::new(Rc::new(extensions), Vec::new()).parse_stylesheet(StyleSheet::from_stmts(stmts))
     }
 
-    fn parse_stmt(&mut self, stmt: Stmt) -> SassResult<Vec<Toplevel>> {
-        Ok(match stmt {
+    fn parse_stmt(&mut self, stmt: Spanned<Stmt>) -> SassResult<Vec<Toplevel>> {
+        let span = stmt.span;
+        Ok(match stmt.node {
             Stmt::RuleSet(RuleSet {
                 selector,
                 super_selector,
                 rules,
             }) => {
-                let selector = super_selector.zip(&selector).remove_placeholders();
+                let selector = self
+                    .extensions
+                    .extend(super_selector.zip(&selector), &self.media)?
+                    .remove_placeholders();
                 if selector.is_empty() {
                     return Ok(Vec::new());
                 }
-                let mut vals = vec![Toplevel::new_rule(selector)];
+                let mut vals = vec![Toplevel::new_rule(selector, span)];
                 for rule in rules {
                     match rule.node {
-                        Stmt::RuleSet(_) => vals.extend(self.parse_stmt(rule.node)?),
-                        Stmt::Style(s) => vals.get_mut(0).unwrap().push_style(*s)?,
+                        Stmt::RuleSet(_) => vals.extend(self.parse_stmt(rule)?),
+                        Stmt::Style(s) => vals.get_mut(0).unwrap().push_style(*s, rule.span)?,
                         Stmt::MultilineComment(s) => vals.get_mut(0).unwrap().push_comment(s),
                         Stmt::AtRule(AtRule::AtRoot(stmts)) => stmts
                             .into_iter()
-                            .map(|r| Ok(vals.extend(self.parse_stmt(r.node)?)))
+                            .map(|r| Ok(vals.extend(self.parse_stmt(r)?)))
                             .collect::<SassResult<()>>()?,
-                        Stmt::AtRule(r) => vals.push(Toplevel::AtRule(r)),
+                        Stmt::AtRule(AtRule::Extend(..)) => {}
+                        Stmt::AtRule(AtRule::Import(imports)) => {
+                            vals.get_mut(0).unwrap().push_imports(imports)
+                        }
+                        Stmt::AtRule(r) => vals.push(Toplevel::AtRule(r, rule.span)),
+                        Stmt::KeyframesRuleSet(..) => {
+                            return Err(keyframe_block_outside_keyframes(rule.span))
+                        }
                     };
                 }
                 vals
             }
             Stmt::MultilineComment(s) => vec![Toplevel::MultilineComment(s)],
-            Stmt::Style(s) => vec![Toplevel::Style(s)],
-            Stmt::AtRule(r) => vec![Toplevel::AtRule(r)],
+            Stmt::Style(s) => vec![Toplevel::Style(s, span)],
+            Stmt::AtRule(AtRule::Extend(..)) => Vec::new(),
+            Stmt::AtRule(r) => vec![Toplevel::AtRule(r, span)],
+            Stmt::KeyframesRuleSet(..) => return Err(keyframe_block_outside_keyframes(span)),
         })
     }
 
     fn parse_stylesheet(mut self, s: StyleSheet) -> SassResult<Css> {
         let mut is_first = true;
         for stmt in s.0 {
-            let v = self.parse_stmt(stmt.node)?;
+            let v = self.parse_stmt(stmt)?;
             // this is how we print newlines between unrelated styles
             // it could probably be refactored
             if !v.is_empty() {
-                if let Some(Toplevel::MultilineComment(..)) = v.get(0) {
+                // plain CSS imports aren't separated from what follows them
+                if let Some(Toplevel::MultilineComment(..))
+                | Some(Toplevel::AtRule(AtRule::Import(..), _)) = v.get(0)
+                {
                 } else if is_first {
                     is_first = false;
                 } else {
@@ -119,105 +403,311 @@ impl Css {
         Ok(self)
     }
 
-    pub fn pretty_print(self, map: &CodeMap) -> SassResult<String> {
-        let mut string = Vec::new();
-        self._inner_pretty_print(&mut string, map, 0)?;
-        if string.iter().any(|s| !s.is_ascii()) {
-            return Ok(format!("@charset \"UTF-8\";\n{}", unsafe {
-                String::from_utf8_unchecked(string)
-            }));
+    /// Print the CSS along with a source map relating it to `map`
+    pub fn pretty_print(self, map: &CodeMap, options: &Options) -> SassResult<(String, SourceMap)> {
+        let mut output = Output::new();
+        match options.style {
+            OutputStyle::Expanded => self._inner_pretty_print(&mut output, map, options, 0)?,
+            OutputStyle::Compressed => self._inner_compressed_print(&mut output, map, options)?,
         }
-        Ok(unsafe { String::from_utf8_unchecked(string) })
-    }
-
-    fn debug(map: &CodeMap, span: Span, message: &str) {
-        let loc = map.look_up_span(span);
-        eprintln!(
-            "{}:{} Debug: {}",
-            loc.file.name(),
-            loc.begin.line + 1,
-            message
-        );
+        let Output {
+            buf: string,
+            mut source_map,
+            ..
+        } = output;
+        if options.allows_charset && string.iter().any(|s| !s.is_ascii()) {
+            // compressed output marks its encoding with a byte order mark,
+            // which is shorter than `@charset`
+            return Ok(match options.style {
+                OutputStyle::Expanded => {
+                    source_map.shift_lines(1);
+                    (
+                        format!("@charset \"UTF-8\";\n{}", unsafe {
+                            String::from_utf8_unchecked(string)
+                        }),
+                        source_map,
+                    )
+                }
+                // the byte order mark isn't counted when decoding, so the
+                // columns on the first line needn't be adjusted
+                OutputStyle::Compressed => (
+                    format!("\u{FEFF}{}", unsafe { String::from_utf8_unchecked(string) }),
+                    source_map,
+                ),
+            });
+        }
+        Ok((unsafe { String::from_utf8_unchecked(string) }, source_map))
     }
 
-    fn warn(map: &CodeMap, span: Span, message: &str) {
-        let loc = map.look_up_span(span);
-        eprintln!(
-            "Warning: {}\n    {} {}:{}  root stylesheet",
-            message,
-            loc.file.name(),
-            loc.begin.line + 1,
-            loc.begin.column + 1
-        );
+    /// Write the entries of a block, each on its own line
+    fn write_entries(
+        buf: &mut Output,
+        map: &CodeMap,
+        padding: &str,
+        entries: &[BlockEntry],
+    ) -> SassResult<()> {
+        for entry in entries {
+            write!(buf, "{}  ", padding)?;
+            if let BlockEntry::Style(_, span) = entry {
+                buf.mark(map, *span);
+            }
+            writeln!(buf, "{}", entry.to_string()?)?;
+        }
+        Ok(())
     }
 
     fn _inner_pretty_print(
         self,
-        buf: &mut Vec<u8>,
+        buf: &mut Output,
         map: &CodeMap,
+        options: &Options,
         nesting: usize,
     ) -> SassResult<()> {
         let mut has_written = false;
+        // newlines between blocks are only written once we know something
+        // follows them, as rule sets may turn out to be empty
+        let mut should_emit_newline = false;
         let padding = vec![' '; nesting * 2].iter().collect::<String>();
-        for block in self.blocks {
+        let Css {
+            blocks,
+            extensions,
+            media,
+        } = self;
+        let nested = |body, media| {
+            Css::new(Rc::clone(&extensions), media).parse_stylesheet(StyleSheet::from_stmts(body))
+        };
+        for block in blocks {
+            match &block {
+                Toplevel::Newline => {}
+                Toplevel::RuleSet(_, _, styles) if styles.is_empty() => {}
+                Toplevel::AtRule(AtRule::Media(m), _) if m.body.is_empty() => {}
+                Toplevel::AtRule(AtRule::Supports(s), _) if s.body.is_empty() => {}
+                _ => {
+                    if should_emit_newline {
+                        should_emit_newline = false;
+                        writeln!(buf)?;
+                    }
+                }
+            }
             match block {
-                Toplevel::RuleSet(selector, styles) => {
+                Toplevel::RuleSet(selector, span, styles) => {
                     if styles.is_empty() {
                         continue;
                     }
                     has_written = true;
-                    writeln!(buf, "{}{} {{", padding, selector)?;
-                    for style in styles {
-                        writeln!(buf, "{}  {}", padding, style.to_string()?)?;
-                    }
+                    write!(buf, "{}", padding)?;
+                    buf.mark(map, span);
+                    writeln!(buf, "{} {{", selector)?;
+                    Self::write_entries(buf, map, &padding, &styles)?;
                     writeln!(buf, "{}}}", padding)?;
                 }
                 Toplevel::MultilineComment(s) => {
                     has_written = true;
                     writeln!(buf, "{}/*{}*/", padding, s)?;
                 }
-                Toplevel::AtRule(r) => {
-                    match r {
-                        AtRule::Unknown(u) => {
-                            if u.params.is_empty() {
-                                write!(buf, "{}@{}", padding, u.name)?;
-                            } else {
-                                write!(buf, "{}@{} {}", padding, u.name, u.params)?;
-                            }
-
-                            if u.body.is_empty() {
-                                writeln!(buf, ";")?;
-                                continue;
-                            } else {
-                                writeln!(buf, " {{")?;
-                            }
-
-                            Css::from_stylesheet(StyleSheet::from_stmts(u.body))?
-                                ._inner_pretty_print(buf, map, nesting + 1)?;
-                            writeln!(buf, "{}}}", padding)?;
+                Toplevel::AtRule(r, span) => match r {
+                    AtRule::Unknown(u) => {
+                        write!(buf, "{}", padding)?;
+                        buf.mark(map, span);
+                        if u.params.is_empty() {
+                            write!(buf, "@{}", u.name)?;
+                        } else {
+                            write!(buf, "@{} {}", u.name, u.params)?;
+                        }
+
+                        if u.body.is_empty() {
+                            writeln!(buf, ";")?;
+                            continue;
+                        } else {
+                            writeln!(buf, " {{")?;
                         }
-                        AtRule::Media(m) => {
-                            if m.body.is_empty() {
-                                continue;
-                            }
-                            writeln!(buf, "{}@media {} {{", padding, m.params)?;
-                            Css::from_stylesheet(StyleSheet::from_stmts(m.body))?
-                                ._inner_pretty_print(buf, map, nesting + 1)?;
-                            writeln!(buf, "{}}}", padding)?;
+
+                        nested(u.body, media.clone())?._inner_pretty_print(
+                            buf,
+                            map,
+                            options,
+                            nesting + 1,
+                        )?;
+                        writeln!(buf, "{}}}", padding)?;
+                    }
+                    AtRule::Media(m) => {
+                        if m.body.is_empty() {
+                            continue;
                         }
-                        AtRule::Debug(e) => Self::debug(map, e.span, &e.node),
-                        AtRule::Warn(e) => Self::warn(map, e.span, &e.node),
-                        _ => todo!("at-rule other than unknown at toplevel: {:?}", r),
+                        write!(buf, "{}", padding)?;
+                        buf.mark(map, span);
+                        writeln!(buf, "@media {} {{", m.params)?;
+                        let mut media = media.clone();
+                        media.push(m.params);
+                        nested(m.body, media)?._inner_pretty_print(
+                            buf,
+                            map,
+                            options,
+                            nesting + 1,
+                        )?;
+                        writeln!(buf, "{}}}", padding)?;
                     }
-                }
-                Toplevel::Style(s) => {
-                    writeln!(buf, "{}{}", padding, s.to_string()?)?;
+                    AtRule::Supports(s) => {
+                        if s.body.is_empty() {
+                            continue;
+                        }
+                        write!(buf, "{}", padding)?;
+                        buf.mark(map, span);
+                        writeln!(buf, "@supports {} {{", s.params)?;
+                        nested(s.body, media.clone())?._inner_pretty_print(
+                            buf,
+                            map,
+                            options,
+                            nesting + 1,
+                        )?;
+                        writeln!(buf, "{}}}", padding)?;
+                    }
+                    AtRule::Keyframes(k) => {
+                        has_written = true;
+                        write!(buf, "{}", padding)?;
+                        buf.mark(map, span);
+                        let has_blocks = k.body.iter().any(|stmt| match stmt.node {
+                            Stmt::KeyframesRuleSet(..) => true,
+                            _ => false,
+                        });
+                        let blocks = keyframe_blocks(k.body)?;
+                        if !has_blocks {
+                            writeln!(buf, "@{} {} {{}}", k.name, k.params)?;
+                            continue;
+                        }
+                        writeln!(buf, "@{} {} {{", k.name, k.params)?;
+                        for (selector, entries) in blocks {
+                            writeln!(buf, "{}  {} {{", padding, selector.join(", "))?;
+                            Self::write_entries(buf, map, &format!("{}  ", padding), &entries)?;
+                            writeln!(buf, "{}  }}", padding)?;
+                        }
+                        writeln!(buf, "{}}}", padding)?;
+                    }
+                    AtRule::Import(imports) => {
+                        has_written = true;
+                        for import in imports {
+                            write!(buf, "{}", padding)?;
+                            buf.mark(map, span);
+                            writeln!(buf, "@import {};", import)?;
+                        }
+                    }
+                    _ => return Err(("This at-rule is not allowed here.", span).into()),
+                },
+                Toplevel::Style(s, span) => {
+                    write!(buf, "{}", padding)?;
+                    buf.mark(map, span);
+                    writeln!(buf, "{}", s.to_string()?)?;
                 }
                 Toplevel::Newline => {
                     if has_written {
-                        writeln!(buf)?
+                        should_emit_newline = true;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn _inner_compressed_print(
+        self,
+        buf: &mut Output,
+        map: &CodeMap,
+        options: &Options,
+    ) -> SassResult<()> {
+        let Css {
+            blocks,
+            extensions,
+            media,
+        } = self;
+        let nested = |body, media| {
+            Css::new(Rc::clone(&extensions), media).parse_stylesheet(StyleSheet::from_stmts(body))
+        };
+        // declarations directly within an at-rule are separated like the
+        // entries of a block
+        let mut needs_semicolon = false;
+        for block in blocks {
+            if let Toplevel::Style(s, span) = &block {
+                if needs_semicolon {
+                    write!(buf, ";")?;
+                }
+                buf.mark(map, *span);
+                write!(buf, "{}", s.to_compressed_string()?)?;
+                needs_semicolon = true;
+                continue;
+            }
+            match block {
+                Toplevel::RuleSet(selector, span, styles) => {
+                    if styles.is_empty() {
+                        continue;
+                    }
+                    buf.mark(map, span);
+                    write!(buf, "{}{{", compress_selector(&selector.to_string()))?;
+                    write_compressed_entries(buf, map, &styles)?;
+                    write!(buf, "}}")?;
+                }
+                Toplevel::MultilineComment(s) => {
+                    if is_preserved(&s) {
+                        write!(buf, "/*{}*/", s)?;
                     }
                 }
+                Toplevel::AtRule(r, span) => match r {
+                    AtRule::Unknown(u) => {
+                        buf.mark(map, span);
+                        if u.params.is_empty() {
+                            write!(buf, "@{}", u.name)?;
+                        } else {
+                            write!(buf, "@{} {}", u.name, u.params)?;
+                        }
+
+                        if u.body.is_empty() {
+                            write!(buf, ";")?;
+                            continue;
+                        }
+                        write!(buf, "{{")?;
+                        nested(u.body, media.clone())?
+                            ._inner_compressed_print(buf, map, options)?;
+                        write!(buf, "}}")?;
+                    }
+                    AtRule::Media(m) => {
+                        if m.body.is_empty() {
+                            continue;
+                        }
+                        buf.mark(map, span);
+                        write!(buf, "@media {}{{", compress_params(&m.params))?;
+                        let mut media = media.clone();
+                        media.push(m.params);
+                        nested(m.body, media)?._inner_compressed_print(buf, map, options)?;
+                        write!(buf, "}}")?;
+                    }
+                    AtRule::Supports(s) => {
+                        if s.body.is_empty() {
+                            continue;
+                        }
+                        buf.mark(map, span);
+                        write!(buf, "@supports {}{{", compress_params(&s.params))?;
+                        nested(s.body, media.clone())?
+                            ._inner_compressed_print(buf, map, options)?;
+                        write!(buf, "}}")?;
+                    }
+                    AtRule::Keyframes(k) => {
+                        buf.mark(map, span);
+                        write!(buf, "@{} {}{{", k.name, k.params)?;
+                        for (selector, entries) in keyframe_blocks(k.body)? {
+                            write!(buf, "{}{{", selector.join(","))?;
+                            write_compressed_entries(buf, map, &entries)?;
+                            write!(buf, "}}")?;
+                        }
+                        write!(buf, "}}")?;
+                    }
+                    AtRule::Import(imports) => {
+                        for import in imports {
+                            buf.mark(map, span);
+                            write!(buf, "@import {};", import)?;
+                        }
+                    }
+                    _ => return Err(("This at-rule is not allowed here.", span).into()),
+                },
+                Toplevel::Style(..) | Toplevel::Newline => {}
             }
         }
         Ok(())
@@ -0,0 +1,401 @@
+//! Plain CSS, used by `.css` files
+//!
+//! Nothing in a plain CSS stylesheet is evaluated: values, including function
+//! calls such as `rgb()` and `min()`, are emitted exactly as written. Syntax
+//! that only exists in Sass is an error.
+
+use codemap::{Span, Spanned};
+
+use peekmore::{PeekMore, PeekMoreIterator};
+
+use crate::atrule::{
+    is_vendor_keyframes, AtRule, Keyframes, KeyframesRuleSet, Media, Supports, UnknownAtRule,
+};
+use crate::common::QuoteKind;
+use crate::error::SassResult;
+use crate::scope::Scope;
+use crate::selector::Selector;
+use crate::style::Style;
+use crate::utils::{devour_whitespace, eat_ident_no_interpolation, is_name_start};
+use crate::value::Value;
+use crate::{RuleSet, Stmt, Token};
+
+/// The at-rules that only exist in Sass
+const SASS_AT_RULES: [&str; 17] = [
+    "at-root", "content", "debug", "each", "else", "error", "extend", "for", "forward", "function",
+    "if", "include", "mixin", "return", "use", "warn", "while",
+];
+
+/// What may appear within a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Block {
+    /// The top level of the stylesheet
+    Root,
+    /// The body of a style rule, which may only contain declarations
+    StyleRule,
+    /// The body of an at-rule such as `@media` or `@font-face`, which may
+    /// contain both declarations and style rules
+    AtRule,
+}
+
+/// Parse a stylesheet written in plain CSS
+pub(crate) fn parse_plain_css<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+) -> SassResult<Vec<Spanned<Stmt>>> {
+    parse_block(toks, Block::Root, None)
+}
+
+/// Parse statements up to and including the `}` closing `block`, or up to
+/// the end of the file at the root
+fn parse_block<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    block: Block,
+    span_before: Option<Span>,
+) -> SassResult<Vec<Spanned<Stmt>>> {
+    let mut stmts = Vec::new();
+    loop {
+        devour_whitespace(toks);
+        let Token { kind, pos } = match toks.peek() {
+            Some(tok) => *tok,
+            None => match span_before {
+                Some(span) => return Err(("expected \"}\".", span).into()),
+                None => break,
+            },
+        };
+        let next = toks.peek_forward(1).map(|tok| tok.kind);
+        toks.reset_view();
+        match (kind, next) {
+            ('}', _) => {
+                toks.next();
+                if block == Block::Root {
+                    return Err(("unmatched \"}\".", pos).into());
+                }
+                break;
+            }
+            (';', _) => {
+                toks.next();
+            }
+            ('/', Some('*')) => {
+                stmts.push(read_comment(toks)?.map_node(Stmt::MultilineComment));
+            }
+            ('/', Some('/')) => {
+                return Err(("Silent comments aren't allowed in plain CSS.", pos).into());
+            }
+            ('$', _) => return Err(("Sass variables aren't allowed in plain CSS.", pos).into()),
+            ('@', _) => {
+                toks.next();
+                stmts.extend(parse_at_rule(toks, block, pos)?);
+            }
+            // the value of a custom property may contain braces
+            ('-', Some('-')) if block != Block::Root => {
+                let (text, end) = read_raw(toks, &[';', '}'])?;
+                stmts.push(parse_declaration(&text, pos)?);
+                if let Some(Token { kind: '}', .. }) = end {
+                    break;
+                }
+            }
+            _ => {
+                let (text, end) = read_raw(toks, &[';', '{', '}'])?;
+                match end {
+                    Some(Token {
+                        kind: '{',
+                        pos: end,
+                    }) => {
+                        if block == Block::StyleRule {
+                            return Err(("Nesting isn't allowed in plain CSS.", pos).into());
+                        }
+                        let selector = parse_selector(&text, pos.merge(end))?;
+                        let rules = parse_block(toks, Block::StyleRule, Some(end))?;
+                        stmts.push(Spanned {
+                            node: Stmt::RuleSet(RuleSet {
+                                selector,
+                                rules,
+                                super_selector: Selector::new(),
+                            }),
+                            span: pos,
+                        });
+                    }
+                    end => {
+                        if block == Block::Root {
+                            return Err(("expected \"{\".", pos).into());
+                        }
+                        if let Some(Token { kind: '}', .. }) = end {
+                            stmts.push(parse_declaration(&text, pos)?);
+                            break;
+                        }
+                        stmts.push(parse_declaration(&text, pos)?);
+                    }
+                }
+            }
+        }
+    }
+    Ok(stmts)
+}
+
+/// Read a `/* */` comment, returning its contents
+fn read_comment<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+) -> SassResult<Spanned<String>> {
+    let mut span = toks.next().unwrap().pos;
+    toks.next();
+    let mut comment = String::new();
+    while let Some(tok) = toks.next() {
+        span = span.merge(tok.pos);
+        if tok.kind == '*' {
+            if let Some(Token { kind: '/', .. }) = toks.peek() {
+                toks.next();
+                return Ok(Spanned {
+                    node: comment,
+                    span,
+                });
+            }
+        }
+        comment.push(tok.kind);
+    }
+    Err(("expected more input.", span).into())
+}
+
+/// Read up to, and including, one of `stops` outside of brackets and
+/// strings, collapsing whitespace
+///
+/// Returns the text read and the token that ended it, which is `None` at the
+/// end of the file
+fn read_raw<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    stops: &[char],
+) -> SassResult<(String, Option<Token>)> {
+    let mut text = String::new();
+    let mut brackets = Vec::new();
+    while let Some(tok) = toks.next() {
+        match tok.kind {
+            kind if brackets.is_empty() && stops.contains(&kind) => {
+                return Ok((text.trim_end().to_owned(), Some(tok)))
+            }
+            q @ '"' | q @ '\'' => {
+                text.push(q);
+                while let Some(tok) = toks.next() {
+                    text.push(tok.kind);
+                    if tok.kind == '\\' {
+                        if let Some(escaped) = toks.next() {
+                            text.push(escaped.kind);
+                        }
+                    } else if tok.kind == q {
+                        break;
+                    }
+                }
+            }
+            '\\' => {
+                text.push('\\');
+                if let Some(escaped) = toks.next() {
+                    text.push(escaped.kind);
+                }
+            }
+            '#' if matches!(toks.peek(), Some(Token { kind: '{', .. })) => {
+                return Err(("Interpolation isn't allowed in plain CSS.", tok.pos).into())
+            }
+            '/' if brackets.is_empty() && matches!(toks.peek(), Some(Token { kind: '/', .. })) => {
+                return Err(("Silent comments aren't allowed in plain CSS.", tok.pos).into())
+            }
+            ' ' | '\t' | '\n' => {
+                devour_whitespace(toks);
+                if !text.is_empty() {
+                    text.push(' ');
+                }
+            }
+            kind @ '(' | kind @ '[' | kind @ '{' => {
+                brackets.push(match kind {
+                    '(' => ')',
+                    '[' => ']',
+                    _ => '}',
+                });
+                text.push(kind);
+            }
+            kind @ ')' | kind @ ']' | kind @ '}' => {
+                if brackets.pop() != Some(kind) {
+                    return Err((format!("expected \"{}\".", kind), tok.pos).into());
+                }
+                text.push(kind);
+            }
+            kind => text.push(kind),
+        }
+    }
+    Ok((text.trim_end().to_owned(), None))
+}
+
+fn parse_selector(text: &str, span: Span) -> SassResult<Selector> {
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '&' => return Err(("Parent selectors aren't allowed here.", span).into()),
+            '%' if chars
+                .peek()
+                .map_or(false, |c| is_name_start(*c) || *c == '-') =>
+            {
+                return Err(("Placeholder selectors aren't allowed in plain CSS.", span).into())
+            }
+            _ => {}
+        }
+    }
+    Selector::from_tokens(
+        &mut text.chars().map(|c| Token::new(span, c)).peekmore(),
+        &Scope::new(),
+        &Selector::new(),
+    )
+}
+
+/// Parse a declaration such as `color: rgb(0, 0, 0)`, whose value is left
+/// as written
+fn parse_declaration(text: &str, span: Span) -> SassResult<Spanned<Stmt>> {
+    let colon = match text.find(':') {
+        Some(colon) => colon,
+        None => return Err(("expected \":\".", span).into()),
+    };
+    let property = text[..colon].trim();
+    let value = text[colon + 1..].trim();
+    if property.is_empty() {
+        return Err(("Expected identifier.", span).into());
+    }
+    if value.is_empty() && !property.starts_with("--") {
+        return Err(("Expected expression.", span).into());
+    }
+    if contains_variable(value) {
+        return Err(("Sass variables aren't allowed in plain CSS.", span).into());
+    }
+    Ok(Spanned {
+        node: Stmt::Style(Box::new(Style {
+            property: property.to_owned(),
+            value: Spanned {
+                node: Value::String(value.to_owned(), QuoteKind::None),
+                span,
+            },
+        })),
+        span,
+    })
+}
+
+/// Whether `value` refers to a Sass variable outside of a string
+fn contains_variable(value: &str) -> bool {
+    let mut quote = None;
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(..), '\\') | (None, '\\') => {
+                chars.next();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some(..), _) => {}
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '$') => {
+                if chars
+                    .peek()
+                    .map_or(false, |c| is_name_start(*c) || *c == '-')
+                {
+                    return true;
+                }
+            }
+            (None, _) => {}
+        }
+    }
+    false
+}
+
+fn parse_at_rule<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    block: Block,
+    span_before: Span,
+) -> SassResult<Option<Spanned<Stmt>>> {
+    let Spanned { node: name, span } = eat_ident_no_interpolation(toks, false, span_before)?;
+    let span = span_before.merge(span);
+    let lower = name.to_ascii_lowercase();
+    if SASS_AT_RULES.contains(&lower.as_str()) {
+        return Err(("This at-rule isn't allowed in plain CSS.", span).into());
+    }
+
+    let (params, end) = read_raw(toks, &[';', '{', '}'])?;
+    let has_body = match end {
+        Some(Token { kind: '{', .. }) => true,
+        Some(Token { kind: '}', pos }) => {
+            return Err(("expected \";\".", pos).into());
+        }
+        _ => false,
+    };
+    if has_body && block == Block::StyleRule {
+        return Err(("Nesting isn't allowed in plain CSS.", span).into());
+    }
+
+    let rule = match lower.as_str() {
+        "import" if !has_body => AtRule::Import(vec![params]),
+        // the charset of the output is determined separately
+        "charset" if !has_body => return Ok(None),
+        "media" if has_body => AtRule::Media(Media {
+            super_selector: Selector::new(),
+            params,
+            body: parse_block(toks, Block::AtRule, Some(span))?,
+        }),
+        "supports" if has_body => AtRule::Supports(Supports {
+            params,
+            body: parse_block(toks, Block::AtRule, Some(span))?,
+        }),
+        "keyframes" if has_body => AtRule::Keyframes(parse_keyframes(toks, name, params, span)?),
+        _ if has_body && is_vendor_keyframes(&lower) => {
+            AtRule::Keyframes(parse_keyframes(toks, name, params, span)?)
+        }
+        _ => AtRule::Unknown(UnknownAtRule {
+            name,
+            super_selector: Selector::new(),
+            params,
+            body: if has_body {
+                parse_block(toks, Block::AtRule, Some(span))?
+            } else {
+                Vec::new()
+            },
+        }),
+    };
+    Ok(Some(Spanned {
+        node: Stmt::AtRule(rule),
+        span,
+    }))
+}
+
+fn parse_keyframes<I: Iterator<Item = Token>>(
+    toks: &mut PeekMoreIterator<I>,
+    name: String,
+    params: String,
+    span: Span,
+) -> SassResult<Keyframes> {
+    let mut body = Vec::new();
+    loop {
+        devour_whitespace(toks);
+        let next = toks.peek_forward(1).map(|tok| tok.kind);
+        toks.reset_view();
+        match (toks.peek().map(|tok| tok.kind), next) {
+            (Some('}'), _) => {
+                toks.next();
+                break;
+            }
+            (Some('/'), Some('*')) => {
+                read_comment(toks)?;
+                continue;
+            }
+            (Some(..), _) => {}
+            (None, _) => return Err(("expected \"}\".", span).into()),
+        }
+        let (selector, end) = read_raw(toks, &['{', ';', '}'])?;
+        let block_span = match end {
+            Some(Token { kind: '{', pos }) => pos,
+            Some(Token { pos, .. }) => return Err(("expected \"{\".", pos).into()),
+            None => return Err(("expected \"{\".", span).into()),
+        };
+        body.push(Spanned {
+            node: Stmt::KeyframesRuleSet(Box::new(KeyframesRuleSet {
+                selector: selector
+                    .split(',')
+                    .map(|selector| selector.trim().to_owned())
+                    .collect(),
+                body: parse_block(toks, Block::StyleRule, Some(span))?,
+            })),
+            span: block_span,
+        });
+    }
+    Ok(Keyframes { name, params, body })
+}
@@ -1,63 +1,92 @@
-use std::cell::RefCell;
 use std::collections::HashMap;
 
 use codemap::Spanned;
 
 use crate::atrule::{Function, Mixin};
 use crate::common::Identifier;
-use crate::error::SassResult;
+use crate::context::current;
+use crate::error::{SassError, SassResult};
 use crate::value::Value;
 
-thread_local!(pub(crate) static GLOBAL_SCOPE: RefCell<Scope> = RefCell::new(Scope::new()));
-
 pub(crate) fn get_global_var<T: Into<Identifier>>(s: Spanned<T>) -> SassResult<Spanned<Value>> {
-    GLOBAL_SCOPE.with(|scope| match scope.borrow().vars().get(&s.node.into()) {
-        Some(v) => Ok(v.clone()),
-        None => Err(("Undefined variable.", s.span).into()),
-    })
+    let name = s.node.into();
+    let state = current();
+    let var = state.global_scope().borrow().vars().get(&name).cloned();
+    match var {
+        Some(v) => Ok(v),
+        None => match state.modules.borrow().get_global_var(&name) {
+            Some(v) => Ok(v),
+            None => Err(SassError::runtime("Undefined variable.", s.span)),
+        },
+    }
 }
 
 pub(crate) fn global_var_exists<T: Into<Identifier>>(v: T) -> bool {
-    GLOBAL_SCOPE.with(|scope| scope.borrow().vars().contains_key(&v.into()))
+    let name = v.into();
+    let state = current();
+    let exists = state.global_scope().borrow().vars().contains_key(&name);
+    exists || state.modules.borrow().get_global_var(&name).is_some()
 }
 
 pub(crate) fn insert_global_var<T: Into<Identifier>>(
     s: T,
     v: Spanned<Value>,
 ) -> SassResult<Option<Spanned<Value>>> {
-    GLOBAL_SCOPE.with(|scope| scope.borrow_mut().insert_var(s.into(), v))
+    current()
+        .global_scope()
+        .borrow_mut()
+        .insert_var(s.into(), v)
 }
 
 pub(crate) fn get_global_fn<T: Into<Identifier>>(s: Spanned<T>) -> SassResult<Function> {
-    GLOBAL_SCOPE.with(
-        |scope| match scope.borrow().functions().get(&s.node.into()) {
-            Some(v) => Ok(v.clone()),
-            None => Err(("Undefined function.", s.span).into()),
-        },
-    )
+    match current()
+        .global_scope()
+        .borrow()
+        .functions()
+        .get(&s.node.into())
+    {
+        Some(v) => Ok(v.clone()),
+        None => Err(SassError::runtime("Undefined function.", s.span)),
+    }
 }
 
 pub(crate) fn global_fn_exists<T: Into<Identifier>>(v: T) -> bool {
-    GLOBAL_SCOPE.with(|scope| scope.borrow().functions().contains_key(&v.into()))
+    current()
+        .global_scope()
+        .borrow()
+        .functions()
+        .contains_key(&v.into())
 }
 
 pub(crate) fn insert_global_fn<T: Into<Identifier>>(s: T, v: Function) -> Option<Function> {
-    GLOBAL_SCOPE.with(|scope| scope.borrow_mut().insert_fn(s.into(), v))
+    current().global_scope().borrow_mut().insert_fn(s.into(), v)
 }
 
 pub(crate) fn get_global_mixin<T: Into<Identifier>>(s: Spanned<T>) -> SassResult<Mixin> {
-    GLOBAL_SCOPE.with(|scope| match scope.borrow().mixins().get(&s.node.into()) {
+    match current()
+        .global_scope()
+        .borrow()
+        .mixins()
+        .get(&s.node.into())
+    {
         Some(v) => Ok(v.clone()),
-        None => Err(("Undefined mixin.", s.span).into()),
-    })
+        None => Err(SassError::runtime("Undefined mixin.", s.span)),
+    }
 }
 
 pub(crate) fn global_mixin_exists<T: Into<Identifier>>(v: T) -> bool {
-    GLOBAL_SCOPE.with(|scope| scope.borrow().mixins().contains_key(&v.into()))
+    current()
+        .global_scope()
+        .borrow()
+        .mixins()
+        .contains_key(&v.into())
 }
 
 pub(crate) fn insert_global_mixin<T: Into<Identifier>>(s: T, v: Mixin) -> Option<Mixin> {
-    GLOBAL_SCOPE.with(|scope| scope.borrow_mut().insert_mixin(s.into(), v))
+    current()
+        .global_scope()
+        .borrow_mut()
+        .insert_mixin(s.into(), v)
 }
 
 #[derive(Debug, Clone)]
@@ -1,4 +1,5 @@
 use std::fmt::{self, Display, Write};
+use std::hash::{Hash, Hasher};
 
 use peekmore::PeekMoreIterator;
 
@@ -12,7 +13,7 @@ use crate::utils::{devour_whitespace, eat_ident, is_ident, parse_quoted_string};
 use crate::value::Value;
 use crate::Token;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug)]
 pub(crate) struct Attribute {
     attr: QualifiedName,
     value: String,
@@ -21,6 +22,26 @@ pub(crate) struct Attribute {
     span: Span,
 }
 
+impl PartialEq for Attribute {
+    fn eq(&self, other: &Attribute) -> bool {
+        self.attr == other.attr
+            && self.value == other.value
+            && self.modifier == other.modifier
+            && self.op == other.op
+    }
+}
+
+impl Eq for Attribute {}
+
+impl Hash for Attribute {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.attr.hash(state);
+        self.value.hash(state);
+        self.modifier.hash(state);
+        self.op.hash(state);
+    }
+}
+
 fn attribute_name<I: Iterator<Item = Token>>(
     toks: &mut PeekMoreIterator<I>,
     scope: &Scope,
@@ -201,7 +222,7 @@ impl Display for Attribute {
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 enum AttributeOp {
     /// \[attr\]
     ///
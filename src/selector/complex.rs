@@ -0,0 +1,348 @@
+//! A structured view of selectors, used when extending and comparing them
+//!
+//! `Selector` stores each complex selector as a flat list of simple selectors,
+//! whitespace and combinators, which is convenient to parse and print. The
+//! types here group those into compound selectors separated by combinators.
+
+use std::fmt::{self, Display, Write};
+
+use super::{Selector, SelectorKind, SelectorPart};
+
+/// A comma separated list of complex selectors
+///
+/// `a .b, c > d`
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct SelectorList {
+    pub components: Vec<ComplexSelector>,
+}
+
+/// A sequence of compound selectors separated by combinators
+///
+/// `a .b > c`
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct ComplexSelector {
+    pub components: Vec<ComplexComponent>,
+    /// Whether a newline should be printed after this selector when it is
+    /// part of a list
+    pub line_break: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) enum ComplexComponent {
+    Compound(CompoundSelector),
+    Combinator(Combinator),
+}
+
+/// A sequence of simple selectors that all match the same element
+///
+/// `a.b:hover`
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct CompoundSelector {
+    pub components: Vec<SelectorKind>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) enum Combinator {
+    /// `>`
+    Child,
+    /// `+`
+    NextSibling,
+    /// `~`
+    FollowingSibling,
+}
+
+/// Pseudo selectors whose argument is itself a selector
+const SELECTOR_PSEUDOS: [&str; 10] = [
+    "not",
+    "is",
+    "matches",
+    "any",
+    "where",
+    "has",
+    "host",
+    "host-context",
+    "slotted",
+    "current",
+];
+
+/// Legacy pseudo elements that may be written with a single colon
+const LEGACY_PSEUDO_ELEMENTS: [&str; 4] = ["after", "before", "first-line", "first-letter"];
+
+/// The name of a pseudo selector without any vendor prefix
+pub(crate) fn normalized_pseudo_name(name: &str) -> String {
+    let name = name.to_ascii_lowercase();
+    if name.starts_with('-') {
+        if let Some(idx) = name[1..].find('-') {
+            return name[idx + 2..].to_owned();
+        }
+    }
+    name
+}
+
+impl SelectorKind {
+    /// The selector argument of a pseudo selector such as `:not(.a)`
+    pub fn pseudo_selector(&self) -> Option<SelectorList> {
+        match self {
+            SelectorKind::PseudoParen(name, selector)
+                if SELECTOR_PSEUDOS.contains(&normalized_pseudo_name(name).as_str()) =>
+            {
+                Some(SelectorList::from_selector(selector))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_pseudo(&self) -> bool {
+        match self {
+            SelectorKind::Pseudo(..)
+            | SelectorKind::PseudoElement(..)
+            | SelectorKind::PseudoParen(..) => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_pseudo_element(&self) -> bool {
+        match self {
+            SelectorKind::PseudoElement(..) => true,
+            SelectorKind::Pseudo(name) => {
+                LEGACY_PSEUDO_ELEMENTS.contains(&name.to_ascii_lowercase().as_str())
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_type(&self) -> bool {
+        match self {
+            SelectorKind::Element(..) | SelectorKind::Universal => true,
+            _ => false,
+        }
+    }
+
+    /// Whether at most one of this selector may match any given element,
+    /// such as an id or a pseudo element
+    pub fn is_unique(&self) -> bool {
+        match self {
+            SelectorKind::Id(..) => true,
+            _ => self.is_pseudo_element(),
+        }
+    }
+
+    pub fn max_specificity(&self) -> u32 {
+        match self {
+            SelectorKind::Universal => 0,
+            SelectorKind::Element(..) => 1,
+            SelectorKind::Id(..) => 1_000_000,
+            _ if self.is_pseudo_element() => 1,
+            SelectorKind::PseudoParen(name, ..) => match self.pseudo_selector() {
+                Some(..) if normalized_pseudo_name(name) == "where" => 0,
+                Some(list) => list
+                    .components
+                    .iter()
+                    .map(ComplexSelector::max_specificity)
+                    .max()
+                    .unwrap_or(0),
+                None => 1000,
+            },
+            _ => 1000,
+        }
+    }
+
+    pub fn min_specificity(&self) -> u32 {
+        match self {
+            SelectorKind::PseudoParen(name, ..) => match self.pseudo_selector() {
+                Some(..) if normalized_pseudo_name(name) == "where" => 0,
+                Some(list) => list
+                    .components
+                    .iter()
+                    .map(ComplexSelector::min_specificity)
+                    .min()
+                    .unwrap_or(0),
+                None => 1000,
+            },
+            _ => self.max_specificity(),
+        }
+    }
+
+    /// Replace the selector argument of a pseudo selector
+    pub fn with_selector(&self, list: SelectorList) -> SelectorKind {
+        match self {
+            SelectorKind::PseudoParen(name, ..) => {
+                SelectorKind::PseudoParen(name.clone(), list.into_selector())
+            }
+            _ => self.clone(),
+        }
+    }
+}
+
+impl SelectorList {
+    pub fn from_selector(selector: &Selector) -> SelectorList {
+        SelectorList {
+            components: selector.0.iter().map(ComplexSelector::from_part).collect(),
+        }
+    }
+
+    pub fn into_selector(self) -> Selector {
+        Selector(
+            self.components
+                .into_iter()
+                .map(ComplexSelector::into_part)
+                .collect(),
+        )
+    }
+}
+
+impl ComplexSelector {
+    pub fn new(components: Vec<ComplexComponent>, line_break: bool) -> ComplexSelector {
+        ComplexSelector {
+            components,
+            line_break,
+        }
+    }
+
+    fn from_part(part: &SelectorPart) -> ComplexSelector {
+        let mut components = Vec::new();
+        let mut compound = Vec::new();
+        for kind in &part.inner {
+            let combinator = match kind {
+                SelectorKind::Whitespace | SelectorKind::Super => None,
+                SelectorKind::ImmediateChild => Some(Combinator::Child),
+                SelectorKind::Following => Some(Combinator::NextSibling),
+                SelectorKind::Preceding => Some(Combinator::FollowingSibling),
+                _ => {
+                    compound.push(kind.clone());
+                    continue;
+                }
+            };
+            if !compound.is_empty() {
+                components.push(ComplexComponent::Compound(CompoundSelector {
+                    components: std::mem::take(&mut compound),
+                }));
+            }
+            if let Some(combinator) = combinator {
+                components.push(ComplexComponent::Combinator(combinator));
+            }
+        }
+        if !compound.is_empty() {
+            components.push(ComplexComponent::Compound(CompoundSelector {
+                components: compound,
+            }));
+        }
+        ComplexSelector::new(components, part.has_newline)
+    }
+
+    fn into_part(self) -> SelectorPart {
+        let mut inner = Vec::new();
+        let mut is_invisible = false;
+        for (idx, component) in self.components.into_iter().enumerate() {
+            if idx != 0 {
+                inner.push(SelectorKind::Whitespace);
+            }
+            match component {
+                ComplexComponent::Compound(compound) => {
+                    is_invisible |= compound.components.iter().any(|simple| match simple {
+                        SelectorKind::Placeholder(..) => true,
+                        _ => false,
+                    });
+                    inner.extend(compound.components);
+                }
+                ComplexComponent::Combinator(combinator) => inner.push(combinator.into()),
+            }
+        }
+        SelectorPart {
+            inner,
+            is_invisible,
+            has_newline: self.line_break,
+            contains_super_selector: false,
+        }
+    }
+
+    pub fn max_specificity(&self) -> u32 {
+        self.compounds()
+            .map(CompoundSelector::max_specificity)
+            .sum()
+    }
+
+    pub fn min_specificity(&self) -> u32 {
+        self.compounds()
+            .map(CompoundSelector::min_specificity)
+            .sum()
+    }
+
+    pub fn compounds(&self) -> impl Iterator<Item = &CompoundSelector> {
+        self.components
+            .iter()
+            .filter_map(ComplexComponent::as_compound)
+    }
+}
+
+impl ComplexComponent {
+    pub fn as_compound(&self) -> Option<&CompoundSelector> {
+        match self {
+            ComplexComponent::Compound(compound) => Some(compound),
+            ComplexComponent::Combinator(..) => None,
+        }
+    }
+
+    pub fn is_combinator(&self) -> bool {
+        match self {
+            ComplexComponent::Combinator(..) => true,
+            ComplexComponent::Compound(..) => false,
+        }
+    }
+}
+
+impl CompoundSelector {
+    pub fn new(components: Vec<SelectorKind>) -> CompoundSelector {
+        CompoundSelector { components }
+    }
+
+    pub fn max_specificity(&self) -> u32 {
+        self.components
+            .iter()
+            .map(SelectorKind::max_specificity)
+            .sum()
+    }
+
+    pub fn min_specificity(&self) -> u32 {
+        self.components
+            .iter()
+            .map(SelectorKind::min_specificity)
+            .sum()
+    }
+}
+
+impl Into<SelectorKind> for Combinator {
+    fn into(self) -> SelectorKind {
+        match self {
+            Combinator::Child => SelectorKind::ImmediateChild,
+            Combinator::NextSibling => SelectorKind::Following,
+            Combinator::FollowingSibling => SelectorKind::Preceding,
+        }
+    }
+}
+
+impl Display for CompoundSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for simple in &self.components {
+            write!(f, "{}", simple)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ComplexSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, component) in self.components.iter().enumerate() {
+            if idx != 0 {
+                f.write_char(' ')?;
+            }
+            match component {
+                ComplexComponent::Compound(compound) => write!(f, "{}", compound)?,
+                ComplexComponent::Combinator(combinator) => {
+                    write!(f, "{}", Into::<SelectorKind>::into(*combinator))?
+                }
+            }
+        }
+        Ok(())
+    }
+}
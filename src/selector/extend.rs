@@ -0,0 +1,900 @@
+//! The `@extend` engine
+//!
+//! Extensions are collected from the whole stylesheet before any CSS is
+//! emitted, so that a style rule is extended no matter where the `@extend`
+//! targeting it appears.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use codemap::{Span, Spanned};
+
+use super::complex::{
+    normalized_pseudo_name, ComplexComponent, ComplexSelector, CompoundSelector, SelectorList,
+};
+use super::functions::{complex_is_superselector, paths, unify_complex, weave};
+use super::{Selector, SelectorKind};
+use crate::atrule::{AtRule, Extend};
+use crate::error::{SassError, SassResult};
+use crate::{RuleSet, Stmt};
+
+/// The media queries enclosing a style rule or `@extend`, outermost first
+type MediaContext = Vec<String>;
+
+#[derive(Clone, Debug)]
+struct Extension {
+    /// The selector of the style rule containing the `@extend`
+    extender: ComplexSelector,
+    target: SelectorKind,
+    media: MediaContext,
+    is_optional: bool,
+    span: Span,
+}
+
+impl Extension {
+    fn with_extender(&self, extender: ComplexSelector) -> Extension {
+        Extension {
+            extender,
+            ..self.clone()
+        }
+    }
+
+    /// Whether this extension may be applied to selectors within `media`
+    fn is_compatible_with(&self, media: &[String]) -> bool {
+        self.media.is_empty() || self.media.as_slice() == media
+    }
+}
+
+/// One of the ways a simple selector may be expanded when extending it
+struct Extender<'a> {
+    selector: ComplexSelector,
+    /// Whether this is the selector being extended, rather than a selector
+    /// that extends it
+    is_original: bool,
+    extension: Option<&'a Extension>,
+}
+
+impl Extender<'_> {
+    fn assert_compatible_media(&self, media: &[String]) -> SassResult<()> {
+        match self.extension {
+            Some(extension) if !extension.is_compatible_with(media) => Err(SassError::runtime(
+                "You may not @extend selectors across media queries.",
+                extension.span,
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn last_compound(&self) -> &[SelectorKind] {
+        match self.selector.components.last() {
+            Some(ComplexComponent::Compound(compound)) => &compound.components,
+            _ => &[],
+        }
+    }
+}
+
+type Extensions = HashMap<SelectorKind, Vec<Extension>>;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum ExtendMode {
+    /// Extended selectors are added alongside the originals, as with
+    /// `@extend` and `selector-extend()`
+    Normal,
+    /// Extended selectors replace the originals, as with `selector-replace()`
+    ///
+    /// Every simple selector in a compound target must match for it to be
+    /// replaced
+    Replace,
+}
+
+impl Default for ExtendMode {
+    fn default() -> Self {
+        ExtendMode::Normal
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ExtensionStore {
+    /// Every extension, keyed by the simple selector it targets
+    extensions: Extensions,
+    /// Every simple selector that appears in a style rule
+    selectors: HashSet<SelectorKind>,
+    /// The complex selectors written in the stylesheet, rather than
+    /// generated by extension
+    ///
+    /// These are never removed as redundant when trimming extended selectors
+    originals: RefCell<HashSet<ComplexSelector>>,
+    /// The specificity of the extender each simple selector first appeared
+    /// in
+    source_specificity: HashMap<SelectorKind, u32>,
+    mode: ExtendMode,
+}
+
+impl ExtensionStore {
+    /// Collect every style rule and `@extend` in `stmts`
+    pub fn from_stmts(stmts: &[Spanned<Stmt>]) -> SassResult<ExtensionStore> {
+        let mut selectors = Vec::new();
+        let mut extends = Vec::new();
+        collect(stmts, &mut Vec::new(), &mut selectors, &mut extends);
+
+        let mut store = ExtensionStore::default();
+        if extends.is_empty() {
+            return Ok(store);
+        }
+
+        for selector in selectors {
+            store.add_selector(&SelectorList::from_selector(&selector));
+        }
+        for (extend, media) in extends {
+            store.add_extend(extend, media)?;
+        }
+        store.check_unsatisfied_extensions()?;
+        Ok(store)
+    }
+
+    /// Apply every extension to the selector of a style rule within `media`
+    pub fn extend(&self, selector: Selector, media: &[String]) -> SassResult<Selector> {
+        if self.extensions.is_empty() {
+            return Ok(selector);
+        }
+        let list = SelectorList::from_selector(&selector);
+        Ok(match self.extend_list(&list, &self.extensions, media)? {
+            Some(extended) => extended.into_selector(),
+            None => selector,
+        })
+    }
+
+    /// Extend `selector` as though every simple selector in `targets` were
+    /// extended by `source`, as `selector-extend()` and `selector-replace()`
+    /// do
+    pub fn extend_or_replace(
+        selector: &Selector,
+        source: &Selector,
+        targets: &Selector,
+        mode: ExtendMode,
+        span: Span,
+    ) -> SassResult<Selector> {
+        let mut store = ExtensionStore {
+            mode,
+            ..ExtensionStore::default()
+        };
+        let mut list = SelectorList::from_selector(selector);
+        store.add_selector(&list);
+
+        let source = SelectorList::from_selector(source);
+        for complex in SelectorList::from_selector(targets).components {
+            let compound = match complex.components.as_slice() {
+                [ComplexComponent::Compound(compound)] => compound,
+                _ => {
+                    return Err(
+                        (format!("Can't extend complex selector {}.", complex), span).into(),
+                    )
+                }
+            };
+            let extensions: Extensions = compound
+                .components
+                .iter()
+                .map(|target| {
+                    let sources = source
+                        .components
+                        .iter()
+                        .map(|extender| Extension {
+                            extender: extender.clone(),
+                            target: target.clone(),
+                            media: Vec::new(),
+                            is_optional: true,
+                            span,
+                        })
+                        .collect();
+                    (target.clone(), sources)
+                })
+                .collect();
+            if let Some(extended) = store.extend_list(&list, &extensions, &[])? {
+                list = extended;
+            }
+        }
+        Ok(list.into_selector())
+    }
+
+    fn add_selector(&mut self, list: &SelectorList) {
+        for complex in &list.components {
+            for compound in complex.compounds() {
+                for simple in &compound.components {
+                    self.register_simple(simple);
+                }
+            }
+        }
+        let is_invisible = list.components.iter().all(|complex| {
+            complex.compounds().any(|compound| {
+                compound.components.iter().any(|simple| match simple {
+                    SelectorKind::Placeholder(..) => true,
+                    _ => false,
+                })
+            })
+        });
+        if !is_invisible {
+            self.originals
+                .get_mut()
+                .extend(list.components.iter().cloned());
+        }
+    }
+
+    fn register_simple(&mut self, simple: &SelectorKind) {
+        self.selectors.insert(simple.clone());
+        if let Some(list) = simple.pseudo_selector() {
+            self.add_selector(&list);
+        }
+    }
+
+    fn add_extend(&mut self, extend: Extend, media: MediaContext) -> SassResult<()> {
+        let extender = SelectorList::from_selector(&extend.selector);
+        for target in extend.targets {
+            for complex in &extender.components {
+                self.add_extension(Extension {
+                    extender: complex.clone(),
+                    target: target.clone(),
+                    media: media.clone(),
+                    is_optional: extend.is_optional,
+                    span: extend.span,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    fn add_extension(&mut self, extension: Extension) -> SassResult<()> {
+        // the extender is itself extended by any extensions that came before
+        let compatible = self.compatible_extensions(&self.extensions, &extension.media);
+        let extenders = self
+            .extend_complex(&extension.extender, &compatible, &extension.media)?
+            .unwrap_or_else(|| vec![extension.extender.clone()]);
+
+        let mut new_extensions = Vec::new();
+        for extender in extenders {
+            let new_extension = extension.with_extender(extender);
+            if self.insert_extension(new_extension.clone()) {
+                new_extensions.push(new_extension);
+            }
+        }
+        if new_extensions.is_empty() {
+            return Ok(());
+        }
+
+        // any existing extenders that contain the new target are extended
+        // in turn
+        let target = extension.target.clone();
+        let existing: Vec<Extension> =
+            self.extensions
+                .values()
+                .flatten()
+                .filter(|existing| {
+                    !new_extensions.iter().any(|new| {
+                        new.target == existing.target && new.extender == existing.extender
+                    }) && complex_contains(&existing.extender, &target)
+                })
+                .cloned()
+                .collect();
+
+        for existing_extension in existing {
+            let mut new_map = Extensions::new();
+            new_map.insert(target.clone(), new_extensions.clone());
+            let selectors = match self.extend_complex(
+                &existing_extension.extender,
+                &self.compatible_extensions(&new_map, &existing_extension.media),
+                &existing_extension.media,
+            )? {
+                Some(selectors) => selectors,
+                None => continue,
+            };
+
+            let contains_extension = selectors.first() == Some(&existing_extension.extender);
+            for complex in selectors {
+                if complex != existing_extension.extender {
+                    self.insert_extension(existing_extension.with_extender(complex));
+                }
+            }
+            if !contains_extension {
+                if let Some(sources) = self.extensions.get_mut(&existing_extension.target) {
+                    sources.retain(|e| e.extender != existing_extension.extender);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns `false` if an extension with the same target and extender
+    /// already exists
+    fn insert_extension(&mut self, extension: Extension) -> bool {
+        let specificity = extension.extender.max_specificity();
+        for compound in extension.extender.compounds() {
+            for simple in &compound.components {
+                self.source_specificity
+                    .entry(simple.clone())
+                    .or_insert(specificity);
+                self.selectors.insert(simple.clone());
+            }
+        }
+
+        let sources = self
+            .extensions
+            .entry(extension.target.clone())
+            .or_insert_with(Vec::new);
+        if let Some(existing) = sources
+            .iter_mut()
+            .find(|existing| existing.extender == extension.extender)
+        {
+            existing.is_optional &= extension.is_optional;
+            return false;
+        }
+        sources.push(extension);
+        true
+    }
+
+    fn compatible_extensions(&self, extensions: &Extensions, media: &[String]) -> Extensions {
+        extensions
+            .iter()
+            .map(|(target, sources)| {
+                (
+                    target.clone(),
+                    sources
+                        .iter()
+                        .filter(|e| e.is_compatible_with(media))
+                        .cloned()
+                        .collect::<Vec<Extension>>(),
+                )
+            })
+            .filter(|(_, sources)| !sources.is_empty())
+            .collect()
+    }
+
+    fn check_unsatisfied_extensions(&self) -> SassResult<()> {
+        for extension in self.extensions.values().flatten() {
+            if !extension.is_optional && !self.selectors.contains(&extension.target) {
+                return Err(SassError::runtime(format!(
+                        "The target selector was not found.\nUse \"@extend {} !optional\" to avoid this error.",
+                        extension.target
+                    ), extension.span));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `None` if no extensions apply to `list`
+    fn extend_list(
+        &self,
+        list: &SelectorList,
+        extensions: &Extensions,
+        media: &[String],
+    ) -> SassResult<Option<SelectorList>> {
+        let mut extended: Option<Vec<Vec<ComplexSelector>>> = None;
+        for (idx, complex) in list.components.iter().enumerate() {
+            match self.extend_complex(complex, extensions, media)? {
+                Some(mut result) => {
+                    // the newline following the original selector follows
+                    // everything it was extended to instead
+                    if complex.line_break {
+                        for extended_complex in &mut result {
+                            extended_complex.line_break = false;
+                        }
+                        if let Some(last) = result.last_mut() {
+                            last.line_break = true;
+                        }
+                    }
+                    extended
+                        .get_or_insert_with(|| {
+                            if idx == 0 {
+                                Vec::new()
+                            } else {
+                                vec![list.components[..idx].to_vec()]
+                            }
+                        })
+                        .push(result);
+                }
+                None => {
+                    if let Some(extended) = &mut extended {
+                        extended.push(vec![complex.clone()]);
+                    }
+                }
+            }
+        }
+
+        Ok(extended.map(|extended| {
+            let originals = self.originals.borrow();
+            SelectorList {
+                components: self.trim(extended, |complex| originals.contains(complex)),
+            }
+        }))
+    }
+
+    /// Returns `None` if no extensions apply to `complex`
+    fn extend_complex(
+        &self,
+        complex: &ComplexSelector,
+        extensions: &Extensions,
+        media: &[String],
+    ) -> SassResult<Option<Vec<ComplexSelector>>> {
+        // the complex selectors that each compound selector in `complex`
+        // can expand to
+        //
+        // given `.a .b {...}` and `.x .y {@extend .b}`, this will contain
+        // `[[.a], [.b, .x .y]]`
+        let mut extended_not_expanded: Option<Vec<Vec<ComplexSelector>>> = None;
+        let is_original = self.originals.borrow().contains(complex);
+        for (idx, component) in complex.components.iter().enumerate() {
+            let extended = match component {
+                ComplexComponent::Compound(compound) => {
+                    self.extend_compound(compound, extensions, media, is_original)?
+                }
+                ComplexComponent::Combinator(..) => None,
+            };
+            match extended {
+                Some(extended) => extended_not_expanded
+                    .get_or_insert_with(|| {
+                        complex.components[..idx]
+                            .iter()
+                            .map(|component| {
+                                vec![ComplexSelector::new(vec![component.clone()], false)]
+                            })
+                            .collect()
+                    })
+                    .push(extended),
+                None => {
+                    if let Some(extended_not_expanded) = &mut extended_not_expanded {
+                        extended_not_expanded
+                            .push(vec![ComplexSelector::new(vec![component.clone()], false)]);
+                    }
+                }
+            }
+        }
+
+        let extended_not_expanded = match extended_not_expanded {
+            Some(extended_not_expanded) => extended_not_expanded,
+            None => return Ok(None),
+        };
+
+        let mut first = true;
+        let mut result = Vec::new();
+        for path in paths(extended_not_expanded) {
+            for components in weave(path.into_iter().map(|c| c.components).collect()) {
+                let output = ComplexSelector::new(components, false);
+                if first && is_original {
+                    self.originals.borrow_mut().insert(output.clone());
+                }
+                first = false;
+                result.push(output);
+            }
+        }
+        Ok(Some(result))
+    }
+
+    /// Returns `None` if no extensions apply to `compound`
+    fn extend_compound(
+        &self,
+        compound: &CompoundSelector,
+        extensions: &Extensions,
+        media: &[String],
+        in_original: bool,
+    ) -> SassResult<Option<Vec<ComplexSelector>>> {
+        // when replacing, a compound target only matches if every one of its
+        // simple selectors is used
+        let mut targets_used = if self.mode == ExtendMode::Normal || extensions.len() < 2 {
+            None
+        } else {
+            Some(HashSet::new())
+        };
+
+        // the ways each simple selector in `compound` may be expanded
+        let mut options: Option<Vec<Vec<Extender<'_>>>> = None;
+        for (idx, simple) in compound.components.iter().enumerate() {
+            match self.extend_simple(simple, extensions, media, &mut targets_used)? {
+                Some(extended) => options
+                    .get_or_insert_with(|| {
+                        if idx == 0 {
+                            Vec::new()
+                        } else {
+                            vec![vec![extender_for_compound(&compound.components[..idx])]]
+                        }
+                    })
+                    .extend(extended),
+                None => {
+                    if let Some(options) = &mut options {
+                        options.push(vec![extender_for_compound(&[simple.clone()])]);
+                    }
+                }
+            }
+        }
+
+        let mut options = match options {
+            Some(options) => options,
+            None => return Ok(None),
+        };
+        if let Some(targets_used) = targets_used {
+            if targets_used.len() != extensions.len() {
+                return Ok(None);
+            }
+        }
+
+        // the simple case of a single simple selector that doesn't need
+        // any unification
+        if options.len() == 1 {
+            let mut result = Vec::new();
+            for state in options.pop().unwrap() {
+                state.assert_compatible_media(media)?;
+                result.push(state.selector);
+            }
+            return Ok(Some(result));
+        }
+
+        // each path through `options` is a different unification of the
+        // base selector
+        //
+        // given `.a.b {...}`, `.w .x {@extend .a}` and `.y .z {@extend .b}`,
+        // the paths are `[.a, .b]`, `[.a, .y .z]`, `[.w .x, .b]` and
+        // `[.w .x, .y .z]`, which unify to `.a.b`, `.y .a.z`, `.w .x.b`
+        // and `.w .y .x.z, .y .w .x.z`
+        let mut unified_paths: Vec<Vec<ComplexSelector>> = Vec::new();
+        let choices = options
+            .iter()
+            .map(|option| option.iter().collect())
+            .collect();
+        for (idx, path) in paths::<&Extender<'_>>(choices).into_iter().enumerate() {
+            let complexes = if idx == 0 && self.mode != ExtendMode::Replace {
+                // the first path is always the original selector, so it
+                // doesn't need unifying
+                vec![vec![ComplexComponent::Compound(CompoundSelector::new(
+                    path.iter()
+                        .flat_map(|state| state.last_compound().iter().cloned())
+                        .collect(),
+                ))]]
+            } else {
+                let mut to_unify = VecDeque::new();
+                let mut originals: Option<Vec<SelectorKind>> = None;
+                for state in &path {
+                    if state.is_original {
+                        originals
+                            .get_or_insert_with(Vec::new)
+                            .extend(state.last_compound().iter().cloned());
+                    } else {
+                        to_unify.push_back(state.selector.components.clone());
+                    }
+                }
+                if let Some(originals) = originals {
+                    to_unify.push_front(vec![ComplexComponent::Compound(CompoundSelector::new(
+                        originals,
+                    ))]);
+                }
+                match unify_complex(to_unify.into_iter().collect()) {
+                    Some(complexes) => complexes,
+                    None => continue,
+                }
+            };
+
+            for state in &path {
+                state.assert_compatible_media(media)?;
+            }
+
+            unified_paths.push(
+                complexes
+                    .into_iter()
+                    .map(|components| ComplexSelector::new(components, false))
+                    .collect(),
+            );
+        }
+
+        // if we're preserving the original selector, mark the first
+        // unification as such so `trim` doesn't get rid of it
+        let original = if in_original && self.mode != ExtendMode::Replace {
+            unified_paths.first().and_then(|path| path.first()).cloned()
+        } else {
+            None
+        };
+
+        Ok(Some(self.trim(unified_paths, |complex| {
+            Some(complex) == original.as_ref()
+        })))
+    }
+
+    /// Returns `None` if no extensions apply to `simple`
+    fn extend_simple<'a>(
+        &self,
+        simple: &SelectorKind,
+        extensions: &'a Extensions,
+        media: &[String],
+        targets_used: &mut Option<HashSet<SelectorKind>>,
+    ) -> SassResult<Option<Vec<Vec<Extender<'a>>>>> {
+        let mut without_pseudo = |simple: &SelectorKind| -> Option<Vec<Extender<'a>>> {
+            let sources = extensions.get(simple)?;
+            if let Some(targets_used) = targets_used {
+                targets_used.insert(simple.clone());
+            }
+            let mut result = if self.mode == ExtendMode::Replace {
+                Vec::new()
+            } else {
+                vec![extender_for_compound(&[simple.clone()])]
+            };
+            result.extend(sources.iter().map(|extension| Extender {
+                selector: extension.extender.clone(),
+                is_original: false,
+                extension: Some(extension),
+            }));
+            Some(result)
+        };
+
+        if simple.pseudo_selector().is_some() {
+            if let Some(extended) = self.extend_pseudo(simple, extensions, media)? {
+                return Ok(Some(
+                    extended
+                        .iter()
+                        .map(|pseudo| {
+                            without_pseudo(pseudo)
+                                .unwrap_or_else(|| vec![extender_for_compound(&[pseudo.clone()])])
+                        })
+                        .collect(),
+                ));
+            }
+        }
+
+        Ok(without_pseudo(simple).map(|result| vec![result]))
+    }
+
+    /// Extend the selector argument of a pseudo selector such as `:not(.a)`
+    ///
+    /// Returns `None` if no extensions apply to it
+    fn extend_pseudo(
+        &self,
+        pseudo: &SelectorKind,
+        extensions: &Extensions,
+        media: &[String],
+    ) -> SassResult<Option<Vec<SelectorKind>>> {
+        let (name, selector) = match (pseudo, pseudo.pseudo_selector()) {
+            (SelectorKind::PseudoParen(name, ..), Some(selector)) => (name, selector),
+            _ => return Ok(None),
+        };
+        let normalized_name = normalized_pseudo_name(name);
+
+        let extended = match self.extend_list(&selector, extensions, media)? {
+            Some(extended) => extended,
+            None => return Ok(None),
+        };
+
+        // for `:not()`, we usually want to get rid of any complex selectors,
+        // because that will cause the selector to fail to parse in many
+        // browsers
+        let mut complexes = extended.components;
+        if normalized_name == "not"
+            && !selector.components.iter().any(|c| c.components.len() > 1)
+            && complexes.iter().any(|c| c.components.len() == 1)
+        {
+            complexes.retain(|c| c.components.len() <= 1);
+        }
+
+        let complexes: Vec<ComplexSelector> = complexes
+            .into_iter()
+            .flat_map(|complex| {
+                let inner_pseudo = match complex.components.as_slice() {
+                    [ComplexComponent::Compound(compound)] if compound.components.len() == 1 => {
+                        compound.components[0].clone()
+                    }
+                    _ => return vec![complex],
+                };
+                let (inner_name, inner_selector) =
+                    match (&inner_pseudo, inner_pseudo.pseudo_selector()) {
+                        (SelectorKind::PseudoParen(inner_name, ..), Some(inner_selector)) => {
+                            (inner_name, inner_selector)
+                        }
+                        _ => return vec![complex],
+                    };
+
+                match normalized_name.as_str() {
+                    "not" => {
+                        // we could theoretically support a `:not` nested
+                        // within another `:not`, but it's a narrow edge case
+                        match normalized_pseudo_name(inner_name).as_str() {
+                            "matches" | "is" | "where" => inner_selector.components,
+                            _ => Vec::new(),
+                        }
+                    }
+                    "matches" | "is" | "where" | "any" | "current" => {
+                        if inner_name == name {
+                            inner_selector.components
+                        } else {
+                            Vec::new()
+                        }
+                    }
+                    // we can't expand nested selectors here, because each
+                    // layer adds an additional layer of semantics
+                    "has" | "host" | "host-context" | "slotted" => vec![complex],
+                    _ => Vec::new(),
+                }
+            })
+            .collect();
+
+        // older browsers support `:not`, but only with a single complex
+        // selector, so we break up its contents unless it originally
+        // contained a selector list
+        if normalized_name == "not" && selector.components.len() == 1 {
+            if complexes.is_empty() {
+                return Ok(None);
+            }
+            Ok(Some(
+                complexes
+                    .into_iter()
+                    .map(|complex| {
+                        pseudo.with_selector(SelectorList {
+                            components: vec![complex],
+                        })
+                    })
+                    .collect(),
+            ))
+        } else {
+            Ok(Some(vec![pseudo.with_selector(SelectorList {
+                components: complexes,
+            })]))
+        }
+    }
+
+    /// Removes redundant selectors from `selectors`
+    ///
+    /// A selector is redundant if it's matched by another selector in the
+    /// list with at least as much specificity as the selectors it was
+    /// generated from. Selectors for which `is_original` returns true are
+    /// never removed.
+    fn trim<F: Fn(&ComplexSelector) -> bool>(
+        &self,
+        selectors: Vec<Vec<ComplexSelector>>,
+        is_original: F,
+    ) -> Vec<ComplexSelector> {
+        // avoid truly horrific quadratic behavior
+        if selectors.len() > 100 {
+            return selectors.into_iter().flatten().collect();
+        }
+
+        let mut result: VecDeque<ComplexSelector> = VecDeque::new();
+        let mut num_originals = 0;
+        for i in (0..selectors.len()).rev() {
+            'outer: for complex1 in selectors[i].iter().rev() {
+                if is_original(complex1) {
+                    // make sure we don't include duplicate originals, which
+                    // could happen if a style rule extends a component of its
+                    // own selector
+                    for j in 0..num_originals {
+                        if &result[j] == complex1 {
+                            let original = result.remove(j).unwrap();
+                            result.push_front(original);
+                            continue 'outer;
+                        }
+                    }
+                    num_originals += 1;
+                    result.push_front(complex1.clone());
+                    continue 'outer;
+                }
+
+                // the maximum specificity of the sources that caused `complex1`
+                // to be generated
+                let max_specificity = complex1
+                    .compounds()
+                    .map(|compound| self.source_specificity_for(compound))
+                    .max()
+                    .unwrap_or(0);
+
+                let is_redundant = |complex2: &ComplexSelector| {
+                    complex2.min_specificity() >= max_specificity
+                        && complex_is_superselector(&complex2.components, &complex1.components)
+                };
+
+                // look in `result` rather than `selectors` for selectors after
+                // `i`, so that if there are two identical selectors only one
+                // is trimmed
+                if result.iter().any(is_redundant) {
+                    continue;
+                }
+                if selectors[..i].iter().flatten().any(is_redundant) {
+                    continue;
+                }
+
+                result.push_front(complex1.clone());
+            }
+        }
+
+        result.into_iter().collect()
+    }
+
+    fn source_specificity_for(&self, compound: &CompoundSelector) -> u32 {
+        compound
+            .components
+            .iter()
+            .map(|simple| *self.source_specificity.get(simple).unwrap_or(&0))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+fn extender_for_compound<'a>(simples: &[SelectorKind]) -> Extender<'a> {
+    Extender {
+        selector: ComplexSelector::new(
+            vec![ComplexComponent::Compound(CompoundSelector::new(
+                simples.to_vec(),
+            ))],
+            false,
+        ),
+        is_original: true,
+        extension: None,
+    }
+}
+
+/// Whether `simple` appears anywhere in `complex`, including within the
+/// arguments of pseudo selectors
+fn complex_contains(complex: &ComplexSelector, simple: &SelectorKind) -> bool {
+    complex.compounds().any(|compound| {
+        compound.components.iter().any(|other| {
+            other == simple
+                || other.pseudo_selector().map_or(false, |list| {
+                    list.components.iter().any(|c| complex_contains(c, simple))
+                })
+        })
+    })
+}
+
+/// The simple selectors targeted by `@extend {selector}`
+pub(crate) fn extension_targets(selector: &Selector, span: Span) -> SassResult<Vec<SelectorKind>> {
+    let mut targets = Vec::new();
+    for complex in SelectorList::from_selector(selector).components {
+        let compound = match complex.components.as_slice() {
+            [ComplexComponent::Compound(compound)] => compound,
+            _ => {
+                return Err(SassError::runtime(
+                    "complex selectors may not be extended.",
+                    span,
+                ))
+            }
+        };
+        if compound.components.len() != 1 {
+            return Err(SassError::runtime(format!(
+                    "compound selectors may no longer be extended.\nConsider `@extend {}` instead.\nSee http://bit.ly/ExtendCompound for details.\n",
+                    compound
+                        .components
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ), span));
+        }
+        targets.push(compound.components[0].clone());
+    }
+    Ok(targets)
+}
+
+/// Walk `stmts`, collecting the resolved selector of every style rule and
+/// every `@extend` along with the media queries enclosing it
+fn collect(
+    stmts: &[Spanned<Stmt>],
+    media: &mut MediaContext,
+    selectors: &mut Vec<Selector>,
+    extends: &mut Vec<(Extend, MediaContext)>,
+) {
+    for stmt in stmts {
+        match &stmt.node {
+            Stmt::RuleSet(RuleSet {
+                selector,
+                super_selector,
+                rules,
+            }) => {
+                selectors.push(super_selector.zip(selector));
+                collect(rules, media, selectors, extends);
+            }
+            Stmt::AtRule(AtRule::Extend(extend)) => extends.push((extend.clone(), media.clone())),
+            Stmt::AtRule(AtRule::Media(m)) => {
+                media.push(m.params.clone());
+                collect(&m.body, media, selectors, extends);
+                media.pop();
+            }
+            Stmt::AtRule(AtRule::Unknown(u)) => collect(&u.body, media, selectors, extends),
+            Stmt::AtRule(AtRule::Supports(s)) => collect(&s.body, media, selectors, extends),
+            Stmt::AtRule(AtRule::AtRoot(body)) | Stmt::AtRule(AtRule::Include(body)) => {
+                collect(body, media, selectors, extends)
+            }
+            _ => {}
+        }
+    }
+}
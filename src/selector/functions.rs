@@ -0,0 +1,966 @@
+//! Algorithms for unifying, weaving and comparing selectors
+//!
+//! These follow the reference implementation closely, so that extended
+//! selectors are emitted in the same order and with the same redundancies
+//! removed.
+
+use std::collections::VecDeque;
+
+use super::complex::{
+    normalized_pseudo_name, Combinator, ComplexComponent, ComplexSelector, CompoundSelector,
+    SelectorList,
+};
+use super::{Selector, SelectorKind};
+
+type Components = Vec<ComplexComponent>;
+
+/// Every combination of one element from each of `choices`
+///
+/// `paths([[1, 2], [3], [4, 5]])` is `[[1, 3, 4], [1, 3, 5], [2, 3, 4], [2, 3, 5]]`
+pub(crate) fn paths<T: Clone>(choices: Vec<Vec<T>>) -> Vec<Vec<T>> {
+    choices.into_iter().fold(vec![Vec::new()], |paths, choice| {
+        choice
+            .into_iter()
+            .flat_map(|option| {
+                paths.iter().map(move |path| {
+                    let mut path = path.clone();
+                    path.push(option.clone());
+                    path
+                })
+            })
+            .collect()
+    })
+}
+
+/// The longest common subsequence of `list1` and `list2`, where two elements
+/// are considered equal if `select` returns a value for them
+fn longest_common_subsequence<T: Clone>(
+    list1: &[T],
+    list2: &[T],
+    select: &dyn Fn(&T, &T) -> Option<T>,
+) -> Vec<T> {
+    let mut lengths = vec![vec![0; list2.len() + 1]; list1.len() + 1];
+    let mut selections: Vec<Vec<Option<T>>> = vec![vec![None; list2.len()]; list1.len()];
+
+    for i in 0..list1.len() {
+        for j in 0..list2.len() {
+            let selection = select(&list1[i], &list2[j]);
+            lengths[i + 1][j + 1] = if selection.is_none() {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            } else {
+                lengths[i][j] + 1
+            };
+            selections[i][j] = selection;
+        }
+    }
+
+    let mut result = Vec::new();
+    let (mut i, mut j) = (list1.len(), list2.len());
+    while i != 0 && j != 0 {
+        if let Some(selection) = &selections[i - 1][j - 1] {
+            result.push(selection.clone());
+            i -= 1;
+            j -= 1;
+        } else if lengths[i][j - 1] > lengths[i - 1][j] {
+            j -= 1;
+        } else {
+            i -= 1;
+        }
+    }
+    result.reverse();
+    result
+}
+
+fn eq_select<T: Clone + PartialEq>(a: &T, b: &T) -> Option<T> {
+    if a == b {
+        Some(a.clone())
+    } else {
+        None
+    }
+}
+
+/// Add `simple` to `compound`, returning `None` if no element could match both
+pub(crate) fn unify_simple(
+    simple: &SelectorKind,
+    compound: &[SelectorKind],
+) -> Option<Vec<SelectorKind>> {
+    match simple {
+        SelectorKind::Universal | SelectorKind::Element(..) => {
+            return match compound.first() {
+                Some(first) if first.is_type() => {
+                    let mut unified = vec![unify_type(simple, first)?];
+                    unified.extend_from_slice(&compound[1..]);
+                    Some(unified)
+                }
+                _ if simple == &SelectorKind::Universal && !compound.is_empty() => {
+                    Some(compound.to_vec())
+                }
+                _ => {
+                    let mut unified = vec![simple.clone()];
+                    unified.extend_from_slice(compound);
+                    Some(unified)
+                }
+            };
+        }
+        _ => {}
+    }
+
+    if compound.len() == 1 && compound[0] == SelectorKind::Universal {
+        return unify_simple(&compound[0], &[simple.clone()]);
+    }
+    if compound.contains(simple) {
+        return Some(compound.to_vec());
+    }
+
+    if let SelectorKind::Id(..) = simple {
+        if compound.iter().any(|other| match other {
+            SelectorKind::Id(..) => true,
+            _ => false,
+        }) {
+            return None;
+        }
+    }
+
+    let mut unified = Vec::with_capacity(compound.len() + 1);
+    let mut added_this = false;
+    for other in compound {
+        if !added_this {
+            if simple.is_pseudo() {
+                if other.is_pseudo_element() {
+                    // a compound selector may contain only one pseudo element,
+                    // which must come after any pseudo classes
+                    if simple.is_pseudo_element() {
+                        return None;
+                    }
+                    unified.push(simple.clone());
+                    added_this = true;
+                }
+            } else if other.is_pseudo() {
+                unified.push(simple.clone());
+                added_this = true;
+            }
+        }
+        unified.push(other.clone());
+    }
+    if !added_this {
+        unified.push(simple.clone());
+    }
+    Some(unified)
+}
+
+fn unify_type(a: &SelectorKind, b: &SelectorKind) -> Option<SelectorKind> {
+    match (a, b) {
+        (SelectorKind::Universal, other) | (other, SelectorKind::Universal) => Some(other.clone()),
+        (SelectorKind::Element(a), SelectorKind::Element(b)) if a == b => {
+            Some(SelectorKind::Element(a.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// Returns a compound selector that matches only elements matched by both
+/// `compound1` and `compound2`
+pub(crate) fn unify_compound(
+    compound1: &[SelectorKind],
+    compound2: &[SelectorKind],
+) -> Option<CompoundSelector> {
+    let mut result = compound2.to_vec();
+    for simple in compound1 {
+        result = unify_simple(simple, &result)?;
+    }
+    Some(CompoundSelector::new(result))
+}
+
+/// Returns a list of complex selectors that match only elements matched by
+/// every one of `complexes`
+pub(crate) fn unify_complex(complexes: Vec<Components>) -> Option<Vec<Components>> {
+    if complexes.len() == 1 {
+        return Some(complexes);
+    }
+
+    let mut unified_base: Option<Vec<SelectorKind>> = None;
+    for complex in &complexes {
+        let base = complex.last()?.as_compound()?;
+        unified_base = Some(match unified_base {
+            None => base.components.clone(),
+            Some(mut unified) => {
+                for simple in &base.components {
+                    unified = unify_simple(simple, &unified)?;
+                }
+                unified
+            }
+        });
+    }
+
+    let mut without_bases: Vec<Components> = complexes
+        .into_iter()
+        .map(|mut complex| {
+            complex.pop();
+            complex
+        })
+        .collect();
+    without_bases
+        .last_mut()
+        .unwrap()
+        .push(ComplexComponent::Compound(CompoundSelector::new(
+            unified_base?,
+        )));
+
+    Some(weave(without_bases))
+}
+
+/// Expands "parenthesized selectors" in `complexes`
+///
+/// `weave([[.a, .b], [.c, .d]])` produces `.a .c .b .d` and `.c .a .b .d`,
+/// every way the parents of `.d` can be interleaved with `.a .b` while still
+/// matching both
+pub(crate) fn weave(complexes: Vec<Components>) -> Vec<Components> {
+    let mut iter = complexes.into_iter();
+    let mut prefixes = match iter.next() {
+        Some(first) => vec![first],
+        None => return Vec::new(),
+    };
+
+    for mut complex in iter {
+        let target = match complex.pop() {
+            Some(target) => target,
+            None => continue,
+        };
+
+        if complex.is_empty() {
+            for prefix in &mut prefixes {
+                prefix.push(target.clone());
+            }
+            continue;
+        }
+
+        let mut new_prefixes = Vec::new();
+        for prefix in prefixes {
+            if let Some(parent_prefixes) = weave_parents(prefix, complex.clone()) {
+                for mut parent_prefix in parent_prefixes {
+                    parent_prefix.push(target.clone());
+                    new_prefixes.push(parent_prefix);
+                }
+            }
+        }
+        prefixes = new_prefixes;
+    }
+
+    prefixes
+}
+
+/// Interweaves `parents1` and `parents2` as parents of the same target
+/// selector, returning every possible ordering that matches both
+fn weave_parents(parents1: Components, parents2: Components) -> Option<Vec<Components>> {
+    let mut queue1: VecDeque<ComplexComponent> = parents1.into_iter().collect();
+    let mut queue2: VecDeque<ComplexComponent> = parents2.into_iter().collect();
+
+    let initial_combinators = merge_initial_combinators(&mut queue1, &mut queue2)?;
+    let final_combinators = merge_final_combinators(&mut queue1, &mut queue2, VecDeque::new())?;
+
+    // make sure there's at most one `:root` in the output
+    let root1 = first_if_root(&mut queue1);
+    let root2 = first_if_root(&mut queue2);
+    match (root1, root2) {
+        (Some(root1), Some(root2)) => {
+            let root = unify_compound(&root1.components, &root2.components)?;
+            queue1.push_front(ComplexComponent::Compound(root.clone()));
+            queue2.push_front(ComplexComponent::Compound(root));
+        }
+        (Some(root1), None) => queue2.push_front(ComplexComponent::Compound(root1)),
+        (None, Some(root2)) => queue1.push_front(ComplexComponent::Compound(root2)),
+        (None, None) => {}
+    }
+
+    let mut groups1 = group_selectors(queue1);
+    let mut groups2 = group_selectors(queue2);
+    let lcs = longest_common_subsequence(
+        groups2.make_contiguous(),
+        groups1.make_contiguous(),
+        &|group1: &Components, group2: &Components| {
+            if group1 == group2 {
+                return Some(group1.clone());
+            }
+            if group1.first()?.is_combinator() || group2.first()?.is_combinator() {
+                return None;
+            }
+            if complex_is_parent_superselector(group1, group2) {
+                return Some(group2.clone());
+            }
+            if complex_is_parent_superselector(group2, group1) {
+                return Some(group1.clone());
+            }
+            if !must_unify(group1, group2) {
+                return None;
+            }
+            let mut unified = unify_complex(vec![group1.clone(), group2.clone()])?;
+            if unified.len() == 1 {
+                unified.pop()
+            } else {
+                None
+            }
+        },
+    );
+
+    let mut choices: Vec<Vec<Components>> = vec![vec![initial_combinators
+        .into_iter()
+        .map(ComplexComponent::Combinator)
+        .collect()]];
+
+    for group in lcs {
+        choices.push(
+            chunks(&mut groups1, &mut groups2, |sequence| {
+                match sequence.front() {
+                    Some(first) => complex_is_parent_superselector(first, &group),
+                    None => true,
+                }
+            })
+            .into_iter()
+            .map(|chunk| chunk.into_iter().flatten().collect())
+            .collect(),
+        );
+        choices.push(vec![group]);
+        groups1.pop_front();
+        groups2.pop_front();
+    }
+
+    choices.push(
+        chunks(&mut groups1, &mut groups2, VecDeque::is_empty)
+            .into_iter()
+            .map(|chunk| chunk.into_iter().flatten().collect())
+            .collect(),
+    );
+
+    choices.extend(final_combinators);
+
+    Some(
+        paths(
+            choices
+                .into_iter()
+                .filter(|choice| !choice.is_empty())
+                .collect(),
+        )
+        .into_iter()
+        .map(|path| path.into_iter().flatten().collect())
+        .collect(),
+    )
+}
+
+/// Removes the leading compound selector of `queue` if it contains `:root`
+fn first_if_root(queue: &mut VecDeque<ComplexComponent>) -> Option<CompoundSelector> {
+    let is_root = match queue.front() {
+        Some(ComplexComponent::Compound(compound)) => {
+            compound.components.iter().any(|simple| match simple {
+                SelectorKind::Pseudo(name) => name.eq_ignore_ascii_case("root"),
+                _ => false,
+            })
+        }
+        _ => false,
+    };
+    if !is_root {
+        return None;
+    }
+    match queue.pop_front() {
+        Some(ComplexComponent::Compound(compound)) => Some(compound),
+        _ => None,
+    }
+}
+
+/// Extracts leading combinators from both queues, returning them if one is a
+/// subsequence of the other
+fn merge_initial_combinators(
+    components1: &mut VecDeque<ComplexComponent>,
+    components2: &mut VecDeque<ComplexComponent>,
+) -> Option<Vec<Combinator>> {
+    let mut combinators1 = Vec::new();
+    while let Some(ComplexComponent::Combinator(c)) = components1.front() {
+        combinators1.push(*c);
+        components1.pop_front();
+    }
+
+    let mut combinators2 = Vec::new();
+    while let Some(ComplexComponent::Combinator(c)) = components2.front() {
+        combinators2.push(*c);
+        components2.pop_front();
+    }
+
+    let lcs = longest_common_subsequence(&combinators1, &combinators2, &eq_select);
+    if lcs == combinators1 {
+        Some(combinators2)
+    } else if lcs == combinators2 {
+        Some(combinators1)
+    } else {
+        None
+    }
+}
+
+fn pop_compound(components: &mut VecDeque<ComplexComponent>) -> CompoundSelector {
+    match components.pop_back() {
+        Some(ComplexComponent::Compound(compound)) => compound,
+        _ => CompoundSelector::new(Vec::new()),
+    }
+}
+
+fn compound_with(compound: &CompoundSelector, combinator: Combinator) -> Components {
+    vec![
+        ComplexComponent::Compound(compound.clone()),
+        ComplexComponent::Combinator(combinator),
+    ]
+}
+
+/// Extracts trailing combinators and the compound selectors they apply to
+/// from both queues, returning the possible ways of combining them
+fn merge_final_combinators(
+    components1: &mut VecDeque<ComplexComponent>,
+    components2: &mut VecDeque<ComplexComponent>,
+    mut result: VecDeque<Vec<Components>>,
+) -> Option<VecDeque<Vec<Components>>> {
+    let ends_in_combinator = |components: &VecDeque<ComplexComponent>| {
+        components
+            .back()
+            .map_or(false, ComplexComponent::is_combinator)
+    };
+    if !ends_in_combinator(components1) && !ends_in_combinator(components2) {
+        return Some(result);
+    }
+
+    let mut combinators1 = Vec::new();
+    while let Some(ComplexComponent::Combinator(c)) = components1.back() {
+        combinators1.push(*c);
+        components1.pop_back();
+    }
+
+    let mut combinators2 = Vec::new();
+    while let Some(ComplexComponent::Combinator(c)) = components2.back() {
+        combinators2.push(*c);
+        components2.pop_back();
+    }
+
+    if combinators1.len() > 1 || combinators2.len() > 1 {
+        // if there are multiple combinators, something hacky's going on. if
+        // one is a supersequence of the other, use that, otherwise give up
+        let lcs = longest_common_subsequence(&combinators1, &combinators2, &eq_select);
+        let combinators = if lcs == combinators1 {
+            combinators2
+        } else if lcs == combinators2 {
+            combinators1
+        } else {
+            return None;
+        };
+        result.push_front(vec![combinators
+            .into_iter()
+            .rev()
+            .map(ComplexComponent::Combinator)
+            .collect()]);
+        return Some(result);
+    }
+
+    match (combinators1.first().copied(), combinators2.first().copied()) {
+        (Some(combinator1), Some(combinator2)) => {
+            let compound1 = pop_compound(components1);
+            let compound2 = pop_compound(components2);
+
+            match (combinator1, combinator2) {
+                (Combinator::FollowingSibling, Combinator::FollowingSibling) => {
+                    if compound_is_superselector(&compound1, &compound2, &[]) {
+                        result.push_front(vec![compound_with(
+                            &compound2,
+                            Combinator::FollowingSibling,
+                        )]);
+                    } else if compound_is_superselector(&compound2, &compound1, &[]) {
+                        result.push_front(vec![compound_with(
+                            &compound1,
+                            Combinator::FollowingSibling,
+                        )]);
+                    } else {
+                        let mut choices = vec![
+                            [
+                                compound_with(&compound1, Combinator::FollowingSibling),
+                                compound_with(&compound2, Combinator::FollowingSibling),
+                            ]
+                            .concat(),
+                            [
+                                compound_with(&compound2, Combinator::FollowingSibling),
+                                compound_with(&compound1, Combinator::FollowingSibling),
+                            ]
+                            .concat(),
+                        ];
+                        if let Some(unified) =
+                            unify_compound(&compound1.components, &compound2.components)
+                        {
+                            choices.push(compound_with(&unified, Combinator::FollowingSibling));
+                        }
+                        result.push_front(choices);
+                    }
+                }
+                (Combinator::FollowingSibling, Combinator::NextSibling)
+                | (Combinator::NextSibling, Combinator::FollowingSibling) => {
+                    let (following_sibling, next_sibling) =
+                        if combinator1 == Combinator::FollowingSibling {
+                            (&compound1, &compound2)
+                        } else {
+                            (&compound2, &compound1)
+                        };
+
+                    if compound_is_superselector(following_sibling, next_sibling, &[]) {
+                        result
+                            .push_front(vec![compound_with(next_sibling, Combinator::NextSibling)]);
+                    } else {
+                        let mut choices = vec![[
+                            compound_with(following_sibling, Combinator::FollowingSibling),
+                            compound_with(next_sibling, Combinator::NextSibling),
+                        ]
+                        .concat()];
+                        if let Some(unified) =
+                            unify_compound(&compound1.components, &compound2.components)
+                        {
+                            choices.push(compound_with(&unified, Combinator::NextSibling));
+                        }
+                        result.push_front(choices);
+                    }
+                }
+                (Combinator::Child, Combinator::NextSibling)
+                | (Combinator::Child, Combinator::FollowingSibling) => {
+                    result.push_front(vec![compound_with(&compound2, combinator2)]);
+                    components1.push_back(ComplexComponent::Compound(compound1));
+                    components1.push_back(ComplexComponent::Combinator(Combinator::Child));
+                }
+                (Combinator::NextSibling, Combinator::Child)
+                | (Combinator::FollowingSibling, Combinator::Child) => {
+                    result.push_front(vec![compound_with(&compound1, combinator1)]);
+                    components2.push_back(ComplexComponent::Compound(compound2));
+                    components2.push_back(ComplexComponent::Combinator(Combinator::Child));
+                }
+                _ if combinator1 == combinator2 => {
+                    let unified = unify_compound(&compound1.components, &compound2.components)?;
+                    result.push_front(vec![compound_with(&unified, combinator1)]);
+                }
+                _ => return None,
+            }
+
+            merge_final_combinators(components1, components2, result)
+        }
+        (Some(combinator1), None) => {
+            merge_final_combinator(components1, components2, combinator1, &mut result);
+            merge_final_combinators(components1, components2, result)
+        }
+        (None, Some(combinator2)) => {
+            merge_final_combinator(components2, components1, combinator2, &mut result);
+            merge_final_combinators(components1, components2, result)
+        }
+        (None, None) => Some(result),
+    }
+}
+
+/// Handles the case in `merge_final_combinators` where only `with_combinator`
+/// ends in a combinator
+fn merge_final_combinator(
+    with_combinator: &mut VecDeque<ComplexComponent>,
+    without_combinator: &mut VecDeque<ComplexComponent>,
+    combinator: Combinator,
+    result: &mut VecDeque<Vec<Components>>,
+) {
+    if combinator == Combinator::Child {
+        if let (Some(ComplexComponent::Compound(other)), Some(ComplexComponent::Compound(this))) =
+            (without_combinator.back(), with_combinator.back())
+        {
+            if compound_is_superselector(other, this, &[]) {
+                without_combinator.pop_back();
+            }
+        }
+    }
+    let compound = pop_compound(with_combinator);
+    result.push_front(vec![compound_with(&compound, combinator)]);
+}
+
+/// Splits `complex` into groups of compound selectors joined by combinators
+///
+/// `a + b .c` is grouped as `[a + b, .c]`
+fn group_selectors(complex: VecDeque<ComplexComponent>) -> VecDeque<Components> {
+    let mut groups: VecDeque<Components> = VecDeque::new();
+    for component in complex {
+        match groups.back_mut() {
+            Some(group)
+                if group.last().map_or(false, ComplexComponent::is_combinator)
+                    || component.is_combinator() =>
+            {
+                group.push(component)
+            }
+            _ => groups.push_back(vec![component]),
+        }
+    }
+    groups
+}
+
+/// Removes leading elements from both queues until `done` returns true for
+/// each, returning the possible orderings of the removed elements
+fn chunks<T: Clone>(
+    queue1: &mut VecDeque<T>,
+    queue2: &mut VecDeque<T>,
+    done: impl Fn(&VecDeque<T>) -> bool,
+) -> Vec<Vec<T>> {
+    let mut chunk1 = Vec::new();
+    while !done(queue1) {
+        chunk1.push(queue1.pop_front().unwrap());
+    }
+
+    let mut chunk2 = Vec::new();
+    while !done(queue2) {
+        chunk2.push(queue2.pop_front().unwrap());
+    }
+
+    match (chunk1.is_empty(), chunk2.is_empty()) {
+        (true, true) => Vec::new(),
+        (true, false) => vec![chunk2],
+        (false, true) => vec![chunk1],
+        (false, false) => vec![
+            [chunk1.clone(), chunk2.clone()].concat(),
+            [chunk2, chunk1].concat(),
+        ],
+    }
+}
+
+/// Whether `complex1` and `complex2` share an id or pseudo element, and so
+/// must refer to the same element
+fn must_unify(complex1: &[ComplexComponent], complex2: &[ComplexComponent]) -> bool {
+    let unique: Vec<&SelectorKind> = complex1
+        .iter()
+        .filter_map(ComplexComponent::as_compound)
+        .flat_map(|compound| compound.components.iter())
+        .filter(|simple| simple.is_unique())
+        .collect();
+    if unique.is_empty() {
+        return false;
+    }
+    complex2
+        .iter()
+        .filter_map(ComplexComponent::as_compound)
+        .flat_map(|compound| compound.components.iter())
+        .any(|simple| simple.is_unique() && unique.contains(&simple))
+}
+
+/// Like `complex_is_superselector`, but compares `complex1` and `complex2` as
+/// though they shared an implicit base compound selector
+fn complex_is_parent_superselector(
+    complex1: &[ComplexComponent],
+    complex2: &[ComplexComponent],
+) -> bool {
+    match (complex1.first(), complex2.first()) {
+        (Some(first1), Some(first2)) if !first1.is_combinator() && !first2.is_combinator() => {}
+        _ => return false,
+    }
+    if complex1.len() > complex2.len() {
+        return false;
+    }
+    let base = ComplexComponent::Compound(CompoundSelector::new(vec![SelectorKind::Placeholder(
+        "<temp>".to_owned(),
+    )]));
+    let mut complex1 = complex1.to_vec();
+    complex1.push(base.clone());
+    let mut complex2 = complex2.to_vec();
+    complex2.push(base);
+    complex_is_superselector(&complex1, &complex2)
+}
+
+/// Whether every element matched by `list2` is also matched by `list1`
+pub(crate) fn list_is_superselector(list1: &SelectorList, list2: &SelectorList) -> bool {
+    list2.components.iter().all(|complex2| {
+        list1
+            .components
+            .iter()
+            .any(|complex1| complex_is_superselector(&complex1.components, &complex2.components))
+    })
+}
+
+/// Whether every element matched by `complex2` is also matched by `complex1`
+pub(crate) fn complex_is_superselector(
+    complex1: &[ComplexComponent],
+    complex2: &[ComplexComponent],
+) -> bool {
+    // selectors with trailing operators are neither superselectors nor subselectors
+    if complex1
+        .last()
+        .map_or(true, ComplexComponent::is_combinator)
+        || complex2
+            .last()
+            .map_or(true, ComplexComponent::is_combinator)
+    {
+        return false;
+    }
+
+    let mut i1 = 0;
+    let mut i2 = 0;
+    loop {
+        let remaining1 = complex1.len() - i1;
+        let remaining2 = complex2.len() - i2;
+        if remaining1 == 0 || remaining2 == 0 {
+            return false;
+        }
+
+        // more complex selectors are never superselectors of less complex ones
+        if remaining1 > remaining2 {
+            return false;
+        }
+
+        // selectors with leading operators are neither superselectors nor subselectors
+        let compound1 = match (&complex1[i1], &complex2[i2]) {
+            (ComplexComponent::Compound(compound1), ComplexComponent::Compound(..)) => compound1,
+            _ => return false,
+        };
+
+        if remaining1 == 1 {
+            let compound2 = match complex2.last() {
+                Some(ComplexComponent::Compound(compound2)) => compound2,
+                _ => return false,
+            };
+            return compound_is_superselector(
+                compound1,
+                compound2,
+                &complex2[i2..complex2.len() - 1],
+            );
+        }
+
+        // find the first index where `complex2[i2..after_superselector]` is
+        // a subselector of `compound1`
+        let mut after_superselector = i2 + 1;
+        while after_superselector < complex2.len() {
+            if let ComplexComponent::Compound(compound2) = &complex2[after_superselector - 1] {
+                if compound_is_superselector(
+                    compound1,
+                    compound2,
+                    &complex2[i2..after_superselector - 1],
+                ) {
+                    break;
+                }
+            }
+            after_superselector += 1;
+        }
+        if after_superselector == complex2.len() {
+            return false;
+        }
+
+        match (&complex1[i1 + 1], &complex2[after_superselector]) {
+            (
+                ComplexComponent::Combinator(combinator1),
+                ComplexComponent::Combinator(combinator2),
+            ) => {
+                // `.foo ~ .bar` is a superselector of `.foo + .bar`, but
+                // otherwise the combinators must match
+                if *combinator1 == Combinator::FollowingSibling {
+                    if *combinator2 == Combinator::Child {
+                        return false;
+                    }
+                } else if combinator1 != combinator2 {
+                    return false;
+                }
+
+                // `.foo > .baz` is not a superselector of `.foo > .bar > .baz`
+                // or `.foo > .bar .baz`, despite the fact that `.baz` is a
+                // superselector of `.bar > .baz` and `.bar .baz`
+                if remaining1 == 3 && remaining2 > 3 {
+                    return false;
+                }
+
+                i1 += 2;
+                i2 = after_superselector + 1;
+            }
+            (ComplexComponent::Combinator(..), ComplexComponent::Compound(..)) => return false,
+            (ComplexComponent::Compound(..), ComplexComponent::Combinator(combinator2)) => {
+                if *combinator2 != Combinator::Child {
+                    return false;
+                }
+                i1 += 1;
+                i2 = after_superselector + 1;
+            }
+            (ComplexComponent::Compound(..), ComplexComponent::Compound(..)) => {
+                i1 += 1;
+                i2 = after_superselector;
+            }
+        }
+    }
+}
+
+/// Whether every element matched by `compound2` is also matched by `compound1`
+///
+/// `parents` are the components that precede `compound2` in its complex
+/// selector, if any
+pub(crate) fn compound_is_superselector(
+    compound1: &CompoundSelector,
+    compound2: &CompoundSelector,
+    parents: &[ComplexComponent],
+) -> bool {
+    // every selector in `compound1` must have a matching selector in `compound2`
+    for simple1 in &compound1.components {
+        let matches = match simple1.pseudo_selector() {
+            Some(selector1) => {
+                selector_pseudo_is_superselector(simple1, &selector1, compound2, parents)
+            }
+            None => simple_is_superselector_of_compound(simple1, compound2),
+        };
+        if !matches {
+            return false;
+        }
+    }
+
+    // `compound1` can't be a superselector of a selector with non-selector
+    // pseudo elements that it doesn't share
+    compound2.components.iter().all(|simple2| {
+        !simple2.is_pseudo_element() || simple_is_superselector_of_compound(simple2, compound1)
+    })
+}
+
+fn simple_is_superselector_of_compound(simple: &SelectorKind, compound: &CompoundSelector) -> bool {
+    compound.components.iter().any(|their_simple| {
+        if simple == their_simple {
+            return true;
+        }
+
+        // some selector pseudo classes can match normal selectors
+        match their_simple {
+            SelectorKind::PseudoParen(name, ..) => {
+                match normalized_pseudo_name(name).as_str() {
+                    "matches" | "is" | "any" | "where" => {}
+                    _ => return false,
+                }
+                match their_simple.pseudo_selector() {
+                    Some(list) => list.components.iter().all(|complex| {
+                        complex.components.len() == 1
+                            && complex.components[0]
+                                .as_compound()
+                                .map_or(false, |compound| compound.components.contains(simple))
+                    }),
+                    None => false,
+                }
+            }
+            _ => false,
+        }
+    })
+}
+
+/// The selector arguments of every pseudo selector in `compound` named `name`
+fn selector_pseudo_args(compound: &CompoundSelector, name: &str) -> Vec<SelectorList> {
+    compound
+        .components
+        .iter()
+        .filter_map(|simple| match simple {
+            SelectorKind::PseudoParen(their_name, ..) if their_name == name => {
+                simple.pseudo_selector()
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn selector_pseudo_is_superselector(
+    pseudo1: &SelectorKind,
+    selector1: &SelectorList,
+    compound2: &CompoundSelector,
+    parents: &[ComplexComponent],
+) -> bool {
+    let name = match pseudo1 {
+        SelectorKind::PseudoParen(name, ..) => name,
+        _ => return false,
+    };
+    match normalized_pseudo_name(name).as_str() {
+        "matches" | "is" | "any" | "where" => {
+            selector_pseudo_args(compound2, name)
+                .iter()
+                .any(|selector2| list_is_superselector(selector1, selector2))
+                || selector1.components.iter().any(|complex1| {
+                    let mut complex2 = parents.to_vec();
+                    complex2.push(ComplexComponent::Compound(compound2.clone()));
+                    complex_is_superselector(&complex1.components, &complex2)
+                })
+        }
+        "has" | "host" | "host-context" | "slotted" => selector_pseudo_args(compound2, name)
+            .iter()
+            .any(|selector2| list_is_superselector(selector1, selector2)),
+        "not" => selector1.components.iter().all(|complex| {
+            let compound1 = complex
+                .components
+                .last()
+                .and_then(ComplexComponent::as_compound);
+            compound2.components.iter().any(|simple2| match simple2 {
+                SelectorKind::Element(..) => compound1.map_or(false, |compound1| {
+                    compound1.components.iter().any(|simple1| match simple1 {
+                        SelectorKind::Element(..) => simple1 != simple2,
+                        _ => false,
+                    })
+                }),
+                SelectorKind::Id(..) => compound1.map_or(false, |compound1| {
+                    compound1.components.iter().any(|simple1| match simple1 {
+                        SelectorKind::Id(..) => simple1 != simple2,
+                        _ => false,
+                    })
+                }),
+                SelectorKind::PseudoParen(their_name, ..) if their_name == name => {
+                    match simple2.pseudo_selector() {
+                        Some(selector2) => list_is_superselector(
+                            &selector2,
+                            &SelectorList {
+                                components: vec![complex.clone()],
+                            },
+                        ),
+                        None => false,
+                    }
+                }
+                _ => false,
+            })
+        }),
+        "current" => selector_pseudo_args(compound2, name)
+            .iter()
+            .any(|selector2| selector1 == selector2),
+        _ => false,
+    }
+}
+
+impl Selector {
+    /// Whether every element matched by `other` is also matched by `self`
+    pub fn is_superselector(&self, other: &Selector) -> bool {
+        list_is_superselector(
+            &SelectorList::from_selector(self),
+            &SelectorList::from_selector(other),
+        )
+    }
+
+    /// A selector that matches only elements matched by both `self` and
+    /// `other`, or `None` if no such selector exists
+    pub fn unify(&self, other: &Selector) -> Option<Selector> {
+        let list2 = SelectorList::from_selector(other);
+        let mut components = Vec::new();
+        for complex1 in SelectorList::from_selector(self).components {
+            for complex2 in &list2.components {
+                if let Some(unified) = unify_complex(vec![
+                    complex1.components.clone(),
+                    complex2.components.clone(),
+                ]) {
+                    components.extend(
+                        unified
+                            .into_iter()
+                            .map(|complex| ComplexSelector::new(complex, false)),
+                    );
+                }
+            }
+        }
+        if components.is_empty() {
+            None
+        } else {
+            Some(SelectorList { components }.into_selector())
+        }
+    }
+
+    /// The simple selectors making up this selector, or `None` if it isn't
+    /// a single compound selector
+    pub fn simple_selectors(&self) -> Option<Vec<SelectorKind>> {
+        let mut list = SelectorList::from_selector(self);
+        if list.components.len() != 1 {
+            return None;
+        }
+        match list.components.pop()?.components.as_mut_slice() {
+            [ComplexComponent::Compound(compound)] => {
+                Some(std::mem::take(&mut compound.components))
+            }
+            _ => None,
+        }
+    }
+}
@@ -15,13 +15,17 @@ use crate::value::Value;
 use crate::Token;
 
 use attribute::Attribute;
+pub(crate) use extend::{extension_targets, ExtendMode, ExtensionStore};
 
 mod attribute;
+mod complex;
+mod extend;
+mod functions;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub(crate) struct Selector(Vec<SelectorPart>);
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 struct SelectorPart {
     pub inner: Vec<SelectorKind>,
     pub is_invisible: bool,
@@ -114,7 +118,7 @@ impl Display for Selector {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub(crate) enum SelectorKind {
     /// Any string
     ///
@@ -546,6 +550,62 @@ impl Selector {
         Selector(rules)
     }
 
+    /// Append each complex selector in `other` directly onto the end of each
+    /// complex selector in `self`, as `selector-append()` does
+    ///
+    /// `.a` appended with `__b` is `.a__b`, and with `:hover` is `.a:hover`.
+    /// A child starting with a type selector that begins with a letter, such
+    /// as `b`, or with `*`, can't be appended
+    pub fn append(&self, other: &Selector, span: Span) -> SassResult<Selector> {
+        let mut parts = Vec::with_capacity(self.0.len() * other.0.len());
+        for parent in &self.0 {
+            for child in &other.0 {
+                let mut inner = parent.inner.clone();
+                let mut suffix = child.inner.iter();
+                match suffix.next() {
+                    Some(SelectorKind::Element(name)) if name.starts_with(char::is_alphabetic) => {
+                        return Err((format!("Can't append {} to {}.", child, parent), span).into())
+                    }
+                    Some(SelectorKind::Element(name)) => match inner.last_mut() {
+                        Some(SelectorKind::Element(s))
+                        | Some(SelectorKind::Id(s))
+                        | Some(SelectorKind::Class(s))
+                        | Some(SelectorKind::Placeholder(s))
+                        | Some(SelectorKind::Pseudo(s))
+                        | Some(SelectorKind::PseudoElement(s)) => s.push_str(name),
+                        _ => {
+                            return Err((
+                                format!(
+                                    "Parent \"{}\" is incompatible with this selector.",
+                                    parent
+                                ),
+                                span,
+                            )
+                                .into())
+                        }
+                    },
+                    Some(SelectorKind::Universal)
+                    | Some(SelectorKind::ImmediateChild)
+                    | Some(SelectorKind::Following)
+                    | Some(SelectorKind::Preceding)
+                    | Some(SelectorKind::Whitespace)
+                    | None => {
+                        return Err((format!("Can't append {} to {}.", child, parent), span).into())
+                    }
+                    Some(kind) => inner.push(kind.clone()),
+                }
+                inner.extend(suffix.cloned());
+                parts.push(SelectorPart {
+                    inner,
+                    is_invisible: parent.is_invisible || child.is_invisible,
+                    has_newline: false,
+                    contains_super_selector: false,
+                });
+            }
+        }
+        Ok(Selector(parts))
+    }
+
     pub fn remove_placeholders(self) -> Selector {
         Selector(
             self.0
@@ -0,0 +1,358 @@
+//! # Source maps
+//!
+//! Version 3 source maps, which relate positions in the generated CSS back to
+//! the stylesheets they were compiled from. See
+//! <https://sourcemaps.info/spec.html>.
+
+use std::path::{Path, PathBuf};
+
+use codemap::File;
+
+use crate::options::Options;
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// How source maps link to the files they were generated from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceMapUrls {
+    /// URLs relative to the location of the source map
+    Relative,
+    /// Absolute `file:` URLs
+    Absolute,
+}
+
+impl Default for SourceMapUrls {
+    fn default() -> Self {
+        SourceMapUrls::Relative
+    }
+}
+
+/// A single position in the generated CSS and the position in a source file
+/// that it was generated from
+///
+/// All lines and columns are 0-indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Mapping {
+    generated_line: usize,
+    generated_column: usize,
+    source: usize,
+    source_line: usize,
+    source_column: usize,
+}
+
+/// A version 3 source map
+///
+/// The sources are initially named as they were when compiling, which for a
+/// stylesheet read from a path is that path. Use [`SourceMap::map_sources`]
+/// to turn them into URLs relative to wherever the source map is written.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    /// The CSS file this source map describes
+    file: Option<String>,
+    sources: Vec<String>,
+    sources_content: Vec<String>,
+    mappings: Vec<Mapping>,
+}
+
+impl SourceMap {
+    pub(crate) fn new() -> Self {
+        SourceMap::default()
+    }
+
+    /// Record that the CSS at `generated_line` and `generated_column` was
+    /// generated from `line` and `column` of `file`
+    pub(crate) fn add_mapping(
+        &mut self,
+        generated_line: usize,
+        generated_column: usize,
+        file: &File,
+        line: usize,
+        column: usize,
+    ) {
+        let source = match self.sources.iter().position(|s| s == file.name()) {
+            Some(idx) => idx,
+            None => {
+                self.sources.push(file.name().to_owned());
+                self.sources_content.push(file.source().to_owned());
+                self.sources.len() - 1
+            }
+        };
+        let mapping = Mapping {
+            generated_line,
+            generated_column,
+            source,
+            source_line: line,
+            source_column: column,
+        };
+        // several parts of the output may start at the same position
+        match self.mappings.last() {
+            Some(last)
+                if last.generated_line == generated_line
+                    && last.generated_column == generated_column => {}
+            _ => self.mappings.push(mapping),
+        }
+    }
+
+    /// Move every mapping down by `lines`, for when text is inserted before
+    /// the CSS after it has been generated
+    pub(crate) fn shift_lines(&mut self, lines: usize) {
+        for mapping in &mut self.mappings {
+            mapping.generated_line += lines;
+        }
+    }
+
+    /// Turn the paths of the sources into URLs, and name the CSS file this
+    /// source map describes, as configured by `options`
+    ///
+    /// Relative URLs are relative to the directory of the output path, or to
+    /// the current directory if there is none
+    pub(crate) fn resolve_sources(&mut self, options: &Options) {
+        let dir = match options.output_path.as_ref().and_then(|p| p.parent()) {
+            Some(dir) if dir != Path::new("") => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let urls = options.source_map_urls;
+        self.map_sources(|source| source_url(source, &dir, urls));
+        if let Some(name) = options.output_path.as_ref().and_then(|p| p.file_name()) {
+            self.set_file(name.to_string_lossy().into_owned());
+        }
+    }
+
+    /// The comment linking the CSS to this source map
+    ///
+    /// This is `None` unless the source map is either embedded in the CSS or
+    /// written next to the output path
+    pub(crate) fn comment(&self, options: &Options) -> Option<String> {
+        let url = if options.embed_source_map {
+            self.to_data_url(options.embed_sources)
+        } else {
+            let mut path = options.output_path.clone()?.into_os_string();
+            path.push(".map");
+            let path = PathBuf::from(path);
+            match options.source_map_urls {
+                SourceMapUrls::Relative => path.file_name()?.to_string_lossy().into_owned(),
+                SourceMapUrls::Absolute => file_url(&absolute(&path)),
+            }
+        };
+        Some(format!("/*# sourceMappingURL={} */", url))
+    }
+
+    /// The names of the files the CSS was generated from, in the order they
+    /// appear in the source map
+    pub fn sources(&self) -> &[String] {
+        &self.sources
+    }
+
+    /// Replace the name of each source with the result of `f`, for example
+    /// to make paths relative to the location of the source map
+    pub fn map_sources<F: FnMut(&str) -> String>(&mut self, mut f: F) {
+        for source in &mut self.sources {
+            *source = f(source);
+        }
+    }
+
+    /// Set the name of the CSS file this source map describes
+    pub fn set_file(&mut self, file: String) {
+        self.file = Some(file);
+    }
+
+    /// The source map as JSON
+    ///
+    /// If `embed_sources` is true, the contents of every source are included
+    /// so that the source files needn't be available to whatever reads the
+    /// source map
+    pub fn to_json(&self, embed_sources: bool) -> String {
+        let mut json = String::from("{\"version\":3");
+        if let Some(file) = &self.file {
+            json.push_str(",\"file\":");
+            json.push_str(&json_string(file));
+        }
+        json.push_str(",\"sources\":");
+        json.push_str(&json_array(&self.sources));
+        if embed_sources {
+            json.push_str(",\"sourcesContent\":");
+            json.push_str(&json_array(&self.sources_content));
+        }
+        json.push_str(",\"names\":[],\"mappings\":");
+        json.push_str(&json_string(&self.encode_mappings()));
+        json.push('}');
+        json
+    }
+
+    /// The source map as a `data:` URL, for embedding it in the CSS itself
+    pub fn to_data_url(&self, embed_sources: bool) -> String {
+        format!(
+            "data:application/json;charset=utf-8;base64,{}",
+            base64(self.to_json(embed_sources).as_bytes())
+        )
+    }
+
+    /// Encode the mappings as base64 VLQs
+    ///
+    /// Lines of the generated CSS are separated by `;` and segments within a
+    /// line by `,`. Each segment is the generated column relative to the
+    /// previous segment on the same line, followed by the source, line and
+    /// column relative to the previous segment.
+    fn encode_mappings(&self) -> String {
+        let mut encoded = String::new();
+        let mut line = 0;
+        let mut previous_generated_column = 0;
+        let mut previous_source = 0;
+        let mut previous_source_line = 0;
+        let mut previous_source_column = 0;
+        for (idx, mapping) in self.mappings.iter().enumerate() {
+            if mapping.generated_line != line {
+                while line < mapping.generated_line {
+                    encoded.push(';');
+                    line += 1;
+                }
+                previous_generated_column = 0;
+            } else if idx != 0 {
+                encoded.push(',');
+            }
+            vlq(
+                &mut encoded,
+                mapping.generated_column as i64 - previous_generated_column as i64,
+            );
+            vlq(&mut encoded, mapping.source as i64 - previous_source as i64);
+            vlq(
+                &mut encoded,
+                mapping.source_line as i64 - previous_source_line as i64,
+            );
+            vlq(
+                &mut encoded,
+                mapping.source_column as i64 - previous_source_column as i64,
+            );
+            previous_generated_column = mapping.generated_column;
+            previous_source = mapping.source;
+            previous_source_line = mapping.source_line;
+            previous_source_column = mapping.source_column;
+        }
+        encoded
+    }
+}
+
+/// Append `value` to `buf` as a base64 VLQ
+///
+/// The sign is stored in the least significant bit, and each digit holds 5
+/// bits of the value along with a continuation bit
+fn vlq(buf: &mut String, value: i64) {
+    let mut value = if value < 0 {
+        ((-value) << 1) | 1
+    } else {
+        value << 1
+    };
+    loop {
+        let mut digit = value & 0b1_1111;
+        value >>= 5;
+        if value > 0 {
+            digit |= 0b10_0000;
+        }
+        buf.push(BASE64_CHARS[digit as usize] as char);
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+/// The URL of `source` within a source map in `dir`
+///
+/// Sources that aren't files, such as `stdin`, are left as they are
+fn source_url(source: &str, dir: &Path, urls: SourceMapUrls) -> String {
+    let path = match Path::new(source).canonicalize() {
+        Ok(path) => path,
+        Err(..) => return source.to_owned(),
+    };
+    match urls {
+        SourceMapUrls::Absolute => file_url(&path),
+        SourceMapUrls::Relative => {
+            let dir = absolute(dir);
+            let common = path
+                .components()
+                .zip(dir.components())
+                .take_while(|(a, b)| a == b)
+                .count();
+            let mut relative = PathBuf::new();
+            for _ in dir.components().skip(common) {
+                relative.push("..");
+            }
+            relative.extend(path.components().skip(common));
+            relative.to_string_lossy().replace('\\', "/")
+        }
+    }
+}
+
+/// `path` made absolute, even if it doesn't exist yet
+fn absolute(path: &Path) -> PathBuf {
+    if let Ok(path) = path.canonicalize() {
+        return path;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(dir), Some(name)) => absolute(if dir == Path::new("") {
+            Path::new(".")
+        } else {
+            dir
+        })
+        .join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// A `file:` URL for the absolute path `path`
+fn file_url(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    if path.starts_with('/') {
+        format!("file://{}", path)
+    } else {
+        format!("file:///{}", path)
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                encoded.push(BASE64_CHARS[(n >> (18 - 6 * idx)) as usize & 0b11_1111] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn json_array(strings: &[String]) -> String {
+    format!(
+        "[{}]",
+        strings
+            .iter()
+            .map(|s| json_string(s))
+            .collect::<Vec<String>>()
+            .join(",")
+    )
+}
@@ -35,6 +35,15 @@ impl Style {
         ))
     }
 
+    /// `color:red`, without a trailing semicolon
+    pub fn to_compressed_string(&self) -> SassResult<String> {
+        Ok(format!(
+            "{}:{}",
+            self.property,
+            self.value.node.to_compressed_css_string(self.value.span)?
+        ))
+    }
+
     pub(crate) fn eval(self) -> SassResult<Self> {
         Ok(Style {
             property: self.property,
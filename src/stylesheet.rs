@@ -1,29 +1,44 @@
 use std::convert::TryFrom;
-use std::fs;
 use std::iter::Iterator;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::vec::IntoIter;
 
-use codemap::{CodeMap, Span, Spanned};
+use codemap::{CodeMap, File, Span, Spanned};
 
 use peekmore::{PeekMore, PeekMoreIterator};
 
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-use crate::atrule::{eat_include, AtRule, AtRuleKind};
+use crate::atrule::{
+    eat_include, eat_keyframe_block, parse_imports, AtRule, AtRuleKind, Forward, Import, Use,
+};
+use crate::context::{current, Context};
 use crate::error::{SassError, SassResult};
-use crate::imports::import;
+use crate::global_variable::define_global_variables;
+use crate::host_function::declare_host_functions;
+use crate::imports::{import, read_file, resolve, Resolved};
+use crate::indented::indented_to_scss;
 use crate::lexer::Lexer;
+use crate::logger::flush;
+use crate::modules::{
+    forwarded_configuration, load_builtin_module, load_module, take_configured_var, Configuration,
+    Module,
+};
+use crate::options::Options;
 use crate::output::Css;
+use crate::plain_css::parse_plain_css;
 use crate::scope::{
     global_var_exists, insert_global_fn, insert_global_mixin, insert_global_var, Scope,
-    GLOBAL_SCOPE,
 };
 use crate::selector::Selector;
+use crate::source_map::SourceMap;
 use crate::token::Token;
 use crate::utils::{
-    devour_whitespace, eat_comment, eat_ident, eat_variable_value, parse_quoted_string,
-    peek_ident_no_interpolation, peek_whitespace, read_until_newline, VariableDecl,
+    devour_whitespace, eat_comment, eat_ident, eat_variable_value, peek_ident_no_interpolation,
+    peek_whitespace, read_until_newline, VariableDecl,
 };
 use crate::{eat_expr, Expr, RuleSet, Stmt};
 
@@ -36,28 +51,23 @@ pub struct StyleSheet(pub(crate) Vec<Spanned<Stmt>>);
 #[wasm_bindgen]
 impl StyleSheet {
     pub fn new(input: String) -> Result<String, JsValue> {
-        let mut map = CodeMap::new();
-        let file = map.add_file("stdin".into(), input);
-        Ok(Css::from_stylesheet(StyleSheet(
-            StyleSheetParser {
-                lexer: Lexer::new(&file).peekmore(),
-                nesting: 0,
-                map: &mut map,
-                path: Path::new(""),
-            }
-            .parse_toplevel()
-            .map_err(|e| raw_to_parse_error(&map, e).to_string())?
-            .0,
-        ))
-        .map_err(|e| raw_to_parse_error(&map, e).to_string())?
-        .pretty_print(&map)
-        .map_err(|e| raw_to_parse_error(&map, e).to_string())?)
+        StyleSheet::compile_string(input, &Options::default())
+            .map(|(css, _)| css)
+            .map_err(|e| JsValue::from(e.to_string()))
     }
 }
 
-fn raw_to_parse_error(map: &CodeMap, err: SassError) -> SassError {
-    let (message, span) = err.raw();
-    SassError::from_loc(message, map.look_up_span(span))
+/// Append the comment linking `css` to its source map, if the source map is
+/// either embedded or written alongside the CSS
+fn link_source_map(css: &mut String, source_map: &SourceMap, options: &Options) {
+    if let Some(comment) = source_map.comment(options) {
+        if !css.is_empty() && !css.ends_with('\n') {
+            css.push('\n');
+        }
+        css.push('\n');
+        css.push_str(&comment);
+        css.push('\n');
+    }
 }
 
 impl StyleSheet {
@@ -76,22 +86,89 @@ impl StyleSheet {
     #[cfg_attr(not(feature = "profiling"), inline)]
     #[cfg(not(feature = "wasm"))]
     pub fn new(input: String) -> SassResult<String> {
-        let mut map = CodeMap::new();
-        let file = map.add_file("stdin".into(), input);
-        Css::from_stylesheet(StyleSheet(
-            StyleSheetParser {
-                lexer: &mut Lexer::new(&file).peekmore(),
-                nesting: 0,
-                map: &mut map,
-                path: Path::new(""),
-            }
-            .parse_toplevel()
-            .map_err(|e| raw_to_parse_error(&map, e))?
-            .0,
-        ))
-        .map_err(|e| raw_to_parse_error(&map, e))?
-        .pretty_print(&map)
-        .map_err(|e| raw_to_parse_error(&map, e))
+        StyleSheet::new_with_options(input, &Options::default())
+    }
+
+    /// Write CSS to `buf`, constructed from a string written in the
+    /// indented syntax
+    ///
+    /// ```
+    /// use grass::{SassResult, StyleSheet};
+    ///
+    /// fn main() -> SassResult<()> {
+    ///     let sass = StyleSheet::new_indented("a\n  b\n    color: red".to_string())?;
+    ///     assert_eq!(sass, "a b {\n  color: red;\n}\n");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg_attr(feature = "profiling", inline(never))]
+    #[cfg_attr(not(feature = "profiling"), inline)]
+    #[cfg(not(feature = "wasm"))]
+    pub fn new_indented(input: String) -> SassResult<String> {
+        StyleSheet::new_with_options(input, &Options::default().syntax(Syntax::Sass))
+    }
+
+    /// Write CSS to `buf`, constructed from a string and configured by
+    /// `options`
+    ///
+    /// The string is SCSS unless `options` specifies another syntax. Imports
+    /// are resolved relative to the current directory, then to each load
+    /// path.
+    ///
+    /// ```
+    /// use grass::{Options, OutputStyle, SassResult, StyleSheet};
+    ///
+    /// fn main() -> SassResult<()> {
+    ///     let sass = StyleSheet::new_with_options(
+    ///         "a { b { color: #ff0000; } }".to_string(),
+    ///         &Options::default().style(OutputStyle::Compressed),
+    ///     )?;
+    ///     assert_eq!(sass, "a b{color:red}");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg_attr(feature = "profiling", inline(never))]
+    #[cfg_attr(not(feature = "profiling"), inline)]
+    #[cfg(not(feature = "wasm"))]
+    pub fn new_with_options(input: String, options: &Options) -> SassResult<String> {
+        let (mut css, source_map) = StyleSheet::compile_string(input, options)?;
+        if options.embed_source_map {
+            link_source_map(&mut css, &source_map, options);
+        }
+        Ok(css)
+    }
+
+    /// Write CSS to `buf`, constructed from a string and configured by
+    /// `options`, along with a source map relating the CSS back to `input`
+    ///
+    /// The string is named `stdin` in the source map
+    ///
+    /// ```
+    /// use grass::{Options, SassResult, StyleSheet};
+    ///
+    /// fn main() -> SassResult<()> {
+    ///     let (css, source_map) = StyleSheet::new_with_source_map(
+    ///         "a {\n  color: red;\n}\n".to_string(),
+    ///         &Options::default(),
+    ///     )?;
+    ///     assert_eq!(css, "a {\n  color: red;\n}\n");
+    ///     assert_eq!(
+    ///         source_map.to_json(false),
+    ///         r#"{"version":3,"sources":["stdin"],"names":[],"mappings":"AAAA;EACE"}"#
+    ///     );
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg_attr(feature = "profiling", inline(never))]
+    #[cfg_attr(not(feature = "profiling"), inline)]
+    #[cfg(not(feature = "wasm"))]
+    pub fn new_with_source_map(
+        input: String,
+        options: &Options,
+    ) -> SassResult<(String, SourceMap)> {
+        let (mut css, source_map) = StyleSheet::compile_string(input, options)?;
+        link_source_map(&mut css, &source_map, options);
+        Ok((css, source_map))
     }
 
     /// Write CSS to `buf`, constructed from a path
@@ -108,36 +185,169 @@ impl StyleSheet {
     #[cfg_attr(not(feature = "profiling"), inline)]
     #[cfg(not(feature = "wasm"))]
     pub fn from_path(p: &str) -> SassResult<String> {
-        let mut map = CodeMap::new();
-        let file = map.add_file(p.into(), String::from_utf8(fs::read(p)?)?);
-        Css::from_stylesheet(StyleSheet(
+        StyleSheet::from_path_with_options(p, &Options::default())
+    }
+
+    /// Write CSS to `buf`, constructed from a path and configured by `options`
+    ///
+    /// The syntax of the file is determined by its extension unless `options`
+    /// specifies one. Imports that can't be found relative to the importing
+    /// file are searched for in each load path, in order.
+    ///
+    /// ```no_run
+    /// use grass::{Options, SassResult, StyleSheet};
+    ///
+    /// fn main() -> SassResult<()> {
+    ///     let options = Options::default().load_path("node_modules");
+    ///     let sass = StyleSheet::from_path_with_options("input.scss", &options)?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg_attr(feature = "profiling", inline(never))]
+    #[cfg_attr(not(feature = "profiling"), inline)]
+    #[cfg(not(feature = "wasm"))]
+    pub fn from_path_with_options(p: &str, options: &Options) -> SassResult<String> {
+        let (mut css, source_map) = StyleSheet::compile_path(p, options)?;
+        if options.embed_source_map {
+            link_source_map(&mut css, &source_map, options);
+        }
+        Ok(css)
+    }
+
+    /// Write CSS to `buf`, constructed from a path and configured by
+    /// `options`, along with a source map relating the CSS back to each
+    /// stylesheet it was compiled from
+    ///
+    /// Unless the source map is embedded, the CSS links to it as though it
+    /// will be written next to the output path with `.map` appended
+    ///
+    /// ```no_run
+    /// use grass::{Options, SassResult, StyleSheet};
+    ///
+    /// fn main() -> SassResult<()> {
+    ///     let options = Options::default().output_path("input.css");
+    ///     let (css, source_map) = StyleSheet::from_path_with_source_map("input.scss", &options)?;
+    ///     std::fs::write("input.css", css)?;
+    ///     std::fs::write("input.css.map", source_map.to_json(false))?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg_attr(feature = "profiling", inline(never))]
+    #[cfg_attr(not(feature = "profiling"), inline)]
+    #[cfg(not(feature = "wasm"))]
+    pub fn from_path_with_source_map(
+        p: &str,
+        options: &Options,
+    ) -> SassResult<(String, SourceMap)> {
+        let (mut css, source_map) = StyleSheet::compile_path(p, options)?;
+        link_source_map(&mut css, &source_map, options);
+        Ok((css, source_map))
+    }
+
+    fn compile_string(input: String, options: &Options) -> SassResult<(String, SourceMap)> {
+        let syntax = options.syntax.unwrap_or(Syntax::Scss);
+        StyleSheet::compile("stdin", input, Path::new(""), syntax, options)
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    fn compile_path(p: &str, options: &Options) -> SassResult<(String, SourceMap)> {
+        let input = read_file(p)?;
+        let syntax = options
+            .syntax
+            .unwrap_or_else(|| Syntax::for_path(p.as_ref()));
+        StyleSheet::compile(p, input, p.as_ref(), syntax, options)
+    }
+
+    /// Compile the stylesheet `input`, which was read from `path` and is
+    /// named `name` in errors and source maps
+    fn compile(
+        name: &str,
+        input: String,
+        path: &Path,
+        syntax: Syntax,
+        options: &Options,
+    ) -> SassResult<(String, SourceMap)> {
+        let mut context = Context::new(options);
+        let (css, mut source_map) = context.enter(|map| {
+            let css = StyleSheet::compile_in(map, name, input, path, syntax, options);
+            flush(map, options);
+            css
+        })?;
+        source_map.resolve_sources(options);
+        Ok((css, source_map))
+    }
+
+    /// Compile as the current compilation, leaving the messages printed by
+    /// `@warn` and `@debug` to be logged afterwards
+    fn compile_in(
+        map: &mut CodeMap,
+        name: &str,
+        input: String,
+        path: &Path,
+        syntax: Syntax,
+        options: &Options,
+    ) -> SassResult<(String, SourceMap)> {
+        declare_host_functions(map, options).map_err(|e| e.locate(map))?;
+        define_global_variables(map, options).map_err(|e| e.locate(map))?;
+        let file = map.add_file(name.into(), input);
+        let toks = &mut syntax
+            .lex(&file)
+            .map_err(|e| e.locate(map))?
+            .into_iter()
+            .peekmore();
+        let mut loading = Vec::new();
+        if !path.as_os_str().is_empty() {
+            loading.push(path.to_path_buf());
+        }
+        let stmts = if syntax == Syntax::Css {
+            parse_plain_css(toks)
+        } else {
             StyleSheetParser {
-                lexer: &mut Lexer::new(&file).peekmore(),
+                lexer: toks,
                 nesting: 0,
-                map: &mut map,
-                path: p.as_ref(),
+                map,
+                path,
+                options,
+                loading: &mut loading,
             }
             .parse_toplevel()
-            .map_err(|e| raw_to_parse_error(&map, e))?
-            .0,
-        ))
-        .map_err(|e| raw_to_parse_error(&map, e))?
-        .pretty_print(&map)
-        .map_err(|e| raw_to_parse_error(&map, e))
+            .map(|(stmts, _)| stmts)
+        }
+        .map_err(|e| e.locate(map))?;
+        Css::from_stylesheet(StyleSheet(stmts))
+            .map_err(|e| e.locate(map))?
+            .pretty_print(map, options)
+            .map_err(|e| e.locate(map))
     }
 
-    pub(crate) fn export_from_path<P: AsRef<Path> + Into<String> + Clone>(
-        p: &P,
+    pub(crate) fn export(
+        stylesheet: &Resolved,
         map: &mut CodeMap,
+        options: &Options,
+        loading: &mut Vec<PathBuf>,
+        span: Span,
     ) -> SassResult<(Vec<Spanned<Stmt>>, Scope)> {
-        let file = map.add_file(p.clone().into(), String::from_utf8(fs::read(p)?)?);
-        Ok(StyleSheetParser {
-            lexer: &mut Lexer::new(&file).peekmore(),
+        let (name, input, syntax) = stylesheet.load(span)?;
+        let file = map.add_file(name, input);
+        let toks = syntax.lex(&file)?;
+        if syntax == Syntax::Css {
+            return Ok((
+                parse_plain_css(&mut toks.into_iter().peekmore())?,
+                Scope::new(),
+            ));
+        }
+        loading.push(stylesheet.path().to_path_buf());
+        let result = StyleSheetParser {
+            lexer: &mut toks.into_iter().peekmore(),
             nesting: 0,
             map,
-            path: p.as_ref(),
+            path: stylesheet.path(),
+            options,
+            loading,
         }
-        .parse_toplevel()?)
+        .parse_toplevel();
+        loading.pop();
+        result
     }
 
     pub(crate) fn from_stmts(s: Vec<Spanned<Stmt>>) -> StyleSheet {
@@ -145,16 +355,53 @@ impl StyleSheet {
     }
 }
 
+/// The syntax a stylesheet is written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    /// SCSS, a superset of CSS
+    Scss,
+    /// The indented syntax
+    Sass,
+    /// Plain CSS, in which nothing is evaluated
+    Css,
+}
+
+impl Syntax {
+    /// The syntax of the file at `path`, determined by its extension
+    pub(crate) fn for_path(path: &Path) -> Syntax {
+        match path.extension() {
+            Some(ext) if ext == "sass" => Syntax::Sass,
+            Some(ext) if ext == "css" => Syntax::Css,
+            _ => Syntax::Scss,
+        }
+    }
+
+    /// Split `file` into the tokens of SCSS
+    fn lex(self, file: &Arc<File>) -> SassResult<Vec<Token>> {
+        let toks = Lexer::new(file);
+        match self {
+            Syntax::Scss | Syntax::Css => Ok(toks.collect()),
+            Syntax::Sass => indented_to_scss(toks),
+        }
+    }
+}
+
 struct StyleSheetParser<'a> {
-    lexer: &'a mut PeekMoreIterator<Lexer<'a>>,
+    lexer: &'a mut PeekMoreIterator<IntoIter<Token>>,
     nesting: u32,
     map: &'a mut CodeMap,
     path: &'a Path,
+    options: &'a Options,
+    /// The files currently being loaded, from the root stylesheet to this one
+    loading: &'a mut Vec<PathBuf>,
 }
 
 impl<'a> StyleSheetParser<'a> {
     fn parse_toplevel(mut self) -> SassResult<(Vec<Spanned<Stmt>>, Scope)> {
         let mut rules: Vec<Spanned<Stmt>> = Vec::new();
+        // `@use` may only be preceded by `@forward`, `@charset`, variable
+        // declarations and comments
+        let mut is_use_allowed = true;
         devour_whitespace(self.lexer);
         while let Some(Token { kind, .. }) = self.lexer.peek() {
             match kind {
@@ -186,6 +433,7 @@ impl<'a> StyleSheetParser<'a> {
                 | '\''
                 | '"'
                 | '\u{7f}'..=std::char::MAX => {
+                    is_use_allowed = false;
                     rules.extend(self.eat_rules(&Selector::new(), &mut Scope::new())?)
                 }
                 '\t' | '\n' | ' ' | ';' => {
@@ -212,7 +460,13 @@ impl<'a> StyleSheetParser<'a> {
                                 pos,
                             )?;
 
-                            if !(default && global_var_exists(&name.node)) {
+                            if default {
+                                if let Some(config) = take_configured_var(name.node.as_str()) {
+                                    insert_global_var(&name.node, config)?;
+                                } else if !global_var_exists(&name.node) {
+                                    insert_global_var(&name.node, val)?;
+                                }
+                            } else {
                                 insert_global_var(&name.node, val)?;
                             }
                         }
@@ -236,7 +490,49 @@ impl<'a> StyleSheetParser<'a> {
                 '@' => {
                     let span_before = self.lexer.next().unwrap().pos();
                     let rule = eat_ident(self.lexer, &Scope::new(), &Selector::new(), span_before)?;
-                    match AtRuleKind::try_from(&rule)? {
+                    let kind = AtRuleKind::try_from(&rule)?;
+                    match kind {
+                        AtRuleKind::Use | AtRuleKind::Forward | AtRuleKind::Charset => {}
+                        _ => is_use_allowed = false,
+                    }
+                    match kind {
+                        AtRuleKind::Use => {
+                            if !is_use_allowed {
+                                return Err((
+                                    "@use rules must be written before any other rules.",
+                                    rule.span,
+                                )
+                                    .into());
+                            }
+                            let Use {
+                                url,
+                                namespace,
+                                config,
+                            } = Use::from_tokens(self.lexer, rule.span)?;
+                            devour_whitespace(self.lexer);
+                            let (new_rules, module) = self.load_module(&url, config)?;
+                            rules.extend(new_rules);
+                            current().modules.borrow_mut().insert(namespace, module)?;
+                        }
+                        AtRuleKind::Forward => {
+                            if !is_use_allowed {
+                                return Err((
+                                    "@forward rules must be written before any other rules.",
+                                    rule.span,
+                                )
+                                    .into());
+                            }
+                            let Forward {
+                                url,
+                                forwarding,
+                                config,
+                            } = Forward::from_tokens(self.lexer, rule.span)?;
+                            devour_whitespace(self.lexer);
+                            let config = forwarded_configuration(config, &forwarding);
+                            let (new_rules, module) = self.load_module(&url, config)?;
+                            rules.extend(new_rules);
+                            current().modules.borrow_mut().forward(module, forwarding);
+                        }
                         AtRuleKind::Include => rules.extend(eat_include(
                             self.lexer,
                             &Scope::new(),
@@ -245,48 +541,40 @@ impl<'a> StyleSheetParser<'a> {
                             rule.span,
                         )?),
                         AtRuleKind::Import => {
-                            devour_whitespace(self.lexer);
-                            let mut file_name = String::new();
-                            let next = match self.lexer.next() {
-                                Some(v) => v,
-                                None => todo!("expected input after @import"),
-                            };
-                            match next.kind {
-                                q @ '"' | q @ '\'' => {
-                                    file_name.push_str(
-                                        &parse_quoted_string(
-                                            self.lexer,
-                                            &Scope::new(),
-                                            q,
-                                            &Selector::new(),
-                                            next.pos,
-                                        )?
-                                        .node
-                                        .unquote()
-                                        .to_css_string(rule.span)?,
-                                    );
+                            let mut css_imports = Vec::new();
+                            for url in parse_imports(
+                                self.lexer,
+                                &Scope::new(),
+                                &Selector::new(),
+                                rule.span,
+                            )? {
+                                match url {
+                                    Import::Css(css) => css_imports.push(css),
+                                    Import::Sass(url) => {
+                                        let (new_rules, new_scope) = import(
+                                            self.path,
+                                            &url.node,
+                                            url.span,
+                                            &mut self.map,
+                                            self.options,
+                                            self.loading,
+                                        )?;
+                                        rules.extend(new_rules);
+                                        current().global_scope().borrow_mut().extend(new_scope);
+                                    }
                                 }
-                                _ => return Err(("Expected string.", next.pos()).into()),
                             }
-                            if let Some(t) = self.lexer.peek() {
-                                if t.kind == ';' {
-                                    self.lexer.next();
-                                }
+                            if !css_imports.is_empty() {
+                                rules.push(Spanned {
+                                    node: Stmt::AtRule(AtRule::Import(css_imports)),
+                                    span: rule.span,
+                                });
                             }
-
-                            devour_whitespace(self.lexer);
-
-                            let (new_rules, new_scope) =
-                                import(self.path, file_name.as_ref(), &mut self.map)?;
-                            rules.extend(new_rules);
-                            GLOBAL_SCOPE.with(|s| {
-                                s.borrow_mut().extend(new_scope);
-                            });
                         }
                         v => {
                             let rule = AtRule::from_tokens(
                                 v,
-                                rule.span,
+                                span_before.merge(rule.span),
                                 self.lexer,
                                 &mut Scope::new(),
                                 &Selector::new(),
@@ -300,8 +588,7 @@ impl<'a> StyleSheetParser<'a> {
                                     insert_global_fn(&name, *func);
                                 }
                                 AtRule::Charset => continue,
-                                AtRule::Warn(message) => self.warn(rule.span, &message),
-                                AtRule::Debug(message) => self.debug(rule.span, &message),
+                                AtRule::Warn | AtRule::Debug => {}
                                 AtRule::Return(_) => {
                                     return Err(
                                         ("This at-rule is not allowed here.", rule.span).into()
@@ -339,9 +626,15 @@ impl<'a> StyleSheetParser<'a> {
                                     )?);
                                 }
                                 AtRule::AtRoot(root_rules) => rules.extend(root_rules),
-                                AtRule::Unknown(..) | AtRule::Media(..) => {
-                                    rules.push(rule.map_node(Stmt::AtRule))
-                                }
+                                AtRule::Unknown(..)
+                                | AtRule::Media(..)
+                                | AtRule::Supports(..)
+                                | AtRule::Keyframes(..)
+                                | AtRule::Import(..)
+                                | AtRule::Extend(..) => rules.push(Spanned {
+                                    node: Stmt::AtRule(rule.node),
+                                    span: span_before.merge(rule.span),
+                                }),
                             }
                         }
                     }
@@ -362,7 +655,33 @@ impl<'a> StyleSheetParser<'a> {
                 '}' => return Err(("unmatched \"}\".", self.lexer.next().unwrap().pos).into()),
             };
         }
-        Ok((rules, GLOBAL_SCOPE.with(|s| s.borrow().clone())))
+        Ok((rules, current().global_scope().borrow().clone()))
+    }
+
+    /// Load the module `url` refers to, which is either a file relative to
+    /// this stylesheet or a built-in module such as `sass:math`
+    fn load_module(
+        &mut self,
+        url: &Spanned<String>,
+        config: Configuration,
+    ) -> SassResult<(Vec<Spanned<Stmt>>, Rc<Module>)> {
+        if let Some(name) = url.node.strip_prefix("sass:") {
+            return Ok((Vec::new(), load_builtin_module(name, url.span, &config)?));
+        }
+        match resolve(self.path, &url.node, self.options, url.span)? {
+            Some(stylesheet) => load_module(
+                &stylesheet,
+                self.map,
+                self.options,
+                self.loading,
+                url.span,
+                config,
+            ),
+            None => Err(SassError::runtime(
+                "Can't find stylesheet to import.",
+                url.span,
+            )),
+        }
     }
 
     fn eat_rules(
@@ -397,11 +716,15 @@ impl<'a> StyleSheetParser<'a> {
                         return Err(("This at-rule is not allowed here.", expr.span).into())
                     }
                     AtRule::AtRoot(root_stmts) => stmts.extend(root_stmts),
-                    AtRule::Debug(ref message) => self.debug(expr.span, message),
-                    AtRule::Warn(ref message) => self.warn(expr.span, message),
+                    AtRule::Warn | AtRule::Debug => {}
                     AtRule::Mixin(..) | AtRule::Function(..) => todo!(),
                     AtRule::Charset => todo!(),
-                    r @ AtRule::Unknown(..) | r @ AtRule::Media(..) => stmts.push(Spanned {
+                    r @ AtRule::Unknown(..)
+                    | r @ AtRule::Media(..)
+                    | r @ AtRule::Supports(..)
+                    | r @ AtRule::Keyframes(..)
+                    | r @ AtRule::Import(..)
+                    | r @ AtRule::Extend(..) => stmts.push(Spanned {
                         node: Stmt::AtRule(r),
                         span,
                     }),
@@ -418,6 +741,10 @@ impl<'a> StyleSheetParser<'a> {
                 Expr::FunctionDecl(name, func) => {
                     scope.insert_fn(&name, *func);
                 }
+                Expr::KeyframesRuleSet(selector) => stmts.push(Spanned {
+                    node: eat_keyframe_block(self.lexer, scope, selector, None)?,
+                    span,
+                }),
                 Expr::Selector(s) => {
                     self.nesting += 1;
                     let rules = self.eat_rules(&super_selector.zip(&s), scope)?;
@@ -451,27 +778,3 @@ impl<'a> StyleSheetParser<'a> {
         Ok(stmts)
     }
 }
-
-/// Functions that print to stdout or stderr
-impl<'a> StyleSheetParser<'a> {
-    fn debug(&self, span: Span, message: &str) {
-        let loc = self.map.look_up_span(span);
-        eprintln!(
-            "{}:{} Debug: {}",
-            loc.file.name(),
-            loc.begin.line + 1,
-            message
-        );
-    }
-
-    fn warn(&self, span: Span, message: &str) {
-        let loc = self.map.look_up_span(span);
-        eprintln!(
-            "Warning: {}\n    {} {}:{}  root stylesheet",
-            message,
-            loc.file.name(),
-            loc.begin.line + 1,
-            loc.begin.column + 1
-        );
-    }
-}
@@ -5,7 +5,7 @@ pub(crate) use conversion::UNIT_CONVERSION_TABLE;
 mod conversion;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub(crate) enum Unit {
+pub enum Unit {
     // Absolute units
     /// Pixels
     Px,
@@ -117,7 +117,7 @@ pub(crate) enum UnitKind {
 }
 
 impl Unit {
-    pub fn comparable(&self, other: &Unit) -> bool {
+    pub(crate) fn comparable(&self, other: &Unit) -> bool {
         if other == &Unit::None {
             return true;
         }
@@ -155,6 +155,12 @@ impl Unit {
     }
 }
 
+impl From<&str> for Unit {
+    fn from(unit: &str) -> Self {
+        Unit::from(unit.to_owned())
+    }
+}
+
 impl From<String> for Unit {
     fn from(unit: String) -> Self {
         match unit.to_ascii_lowercase().as_str() {
@@ -1,21 +1,35 @@
 use std::slice::Iter;
 use std::vec::IntoIter;
 
-use codemap::Span;
+use codemap::{Span, Spanned};
 
-use super::Value;
+use super::{Value, NO_SPAN};
 use crate::common::{Brackets, ListSeparator};
 use crate::error::SassResult;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) struct SassMap(Vec<(Value, Value)>);
+pub struct SassMap(Vec<(Value, Value)>);
 
 impl SassMap {
     pub const fn new() -> SassMap {
         SassMap(Vec::new())
     }
 
-    pub fn get(self, key: &Value, span: Span) -> SassResult<Option<Value>> {
+    /// The value associated with `key`, if any
+    ///
+    /// Keys are compared in the same way as by `map-get()`, so `1in` finds
+    /// the value at `96px`
+    pub fn get(&self, key: &Value) -> Option<&Value> {
+        self.0
+            .iter()
+            .find(|(k, ..)| match k.clone().equals(key.clone(), *NO_SPAN) {
+                Ok(Spanned { node, .. }) => node == Value::True,
+                Err(..) => false,
+            })
+            .map(|(.., v)| v)
+    }
+
+    pub(crate) fn lookup(self, key: &Value, span: Span) -> SassResult<Option<Value>> {
         for (k, v) in self.0 {
             if k.equals(key.clone(), span)?.node.is_true(span)? {
                 return Ok(Some(v));
@@ -28,17 +42,22 @@ impl SassMap {
         self.0.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     #[allow(dead_code)]
-    pub fn remove(&mut self, key: &Value) {
+    pub(crate) fn remove(&mut self, key: &Value) {
         self.0.retain(|(ref k, ..)| k != key);
     }
 
-    pub fn merge(&mut self, other: SassMap) {
+    pub(crate) fn merge(&mut self, other: SassMap) {
         for (key, value) in other {
             self.insert(key, value);
         }
     }
 
+    /// The keys and values of this map, in the order they were inserted
     pub fn iter(&self) -> Iter<(Value, Value)> {
         self.0.iter()
     }
@@ -51,7 +70,7 @@ impl SassMap {
         self.0.into_iter().map(|(.., v)| v).collect()
     }
 
-    pub fn entries(self) -> Vec<Value> {
+    pub(crate) fn entries(self) -> Vec<Value> {
         self.0
             .into_iter()
             .map(|(k, v)| Value::List(vec![k, v], ListSeparator::Space, Brackets::None))
@@ -71,6 +90,15 @@ impl SassMap {
     }
 }
 
+impl<'a> IntoIterator for &'a SassMap {
+    type Item = &'a (Value, Value);
+    type IntoIter = Iter<'a, (Value, Value)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 impl IntoIterator for SassMap {
     type Item = (Value, Value);
     type IntoIter = IntoIter<Self::Item>;
@@ -1,18 +1,21 @@
 use std::borrow::Cow;
+use std::fmt;
 use std::iter::Iterator;
 
-use codemap::{Span, Spanned};
+use codemap::{CodeMap, Span, Spanned};
+
+use once_cell::sync::Lazy;
 
 use crate::color::Color;
 use crate::common::{Brackets, ListSeparator, Op, QuoteKind};
-use crate::error::SassResult;
-use crate::unit::Unit;
+use crate::error::{SassError, SassResult};
+use crate::unit::{Unit, UNIT_CONVERSION_TABLE};
 use crate::utils::hex_char_for;
 
 use css_function::is_special_function;
-pub(crate) use map::SassMap;
-pub(crate) use number::Number;
-pub(crate) use sass_function::SassFunction;
+pub use map::SassMap;
+pub use number::Number;
+pub use sass_function::SassFunction;
 
 mod css_function;
 mod map;
@@ -21,8 +24,19 @@ mod ops;
 mod parse;
 mod sass_function;
 
+/// A span for values used outside of a compilation, such as by host
+/// functions, where there is no stylesheet for an error to point to
+static NO_SPAN: Lazy<Span> =
+    Lazy::new(|| CodeMap::new().add_file(String::new(), String::new()).span);
+
+/// A SassScript value
+///
+/// Values passed to and returned from host functions have always been
+/// evaluated, so never contain any operations. New kinds of values may be
+/// added, so matches on a `Value` need a wildcard arm
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) enum Value {
+#[non_exhaustive]
+pub enum Value {
     Important,
     True,
     False,
@@ -30,14 +44,28 @@ pub(crate) enum Value {
     Dimension(Number, Unit),
     List(Vec<Value>, ListSeparator, Brackets),
     Color(Box<Color>),
-    UnaryOp(Op, Box<Value>),
-    BinaryOp(Box<Value>, Op, Box<Value>),
-    Paren(Box<Value>),
     String(String, QuoteKind),
     Map(SassMap),
-    ArgList(Vec<Spanned<Value>>),
     /// Returned by `get-function()`
     Function(SassFunction),
+    #[doc(hidden)]
+    Internal(Internal),
+}
+
+/// A value that only exists while a stylesheet is being evaluated
+///
+/// This can't be constructed or inspected outside of the crate, and is never
+/// passed to or accepted from host functions
+#[doc(hidden)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Internal(pub(crate) InternalKind);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum InternalKind {
+    UnaryOp(Op, Box<Value>),
+    BinaryOp(Box<Value>, Op, Box<Value>),
+    Paren(Box<Value>),
+    ArgList(Vec<Spanned<Value>>),
 }
 
 fn visit_quoted_string(buf: &mut String, force_double_quote: bool, string: &str) -> SassResult<()> {
@@ -108,13 +136,29 @@ fn visit_quoted_string(buf: &mut String, force_double_quote: bool, string: &str)
 }
 
 impl Value {
-    pub fn is_null(&self, span: Span) -> SassResult<bool> {
+    pub(crate) fn unary_op(op: Op, value: Box<Value>) -> Self {
+        Value::Internal(Internal(InternalKind::UnaryOp(op, value)))
+    }
+
+    pub(crate) fn binary_op(lhs: Box<Value>, op: Op, rhs: Box<Value>) -> Self {
+        Value::Internal(Internal(InternalKind::BinaryOp(lhs, op, rhs)))
+    }
+
+    pub(crate) fn paren(value: Box<Value>) -> Self {
+        Value::Internal(Internal(InternalKind::Paren(value)))
+    }
+
+    pub(crate) fn arg_list(args: Vec<Spanned<Value>>) -> Self {
+        Value::Internal(Internal(InternalKind::ArgList(args)))
+    }
+
+    pub(crate) fn is_null(&self, span: Span) -> SassResult<bool> {
         Ok(match self {
             Value::Null => true,
             Value::String(i, QuoteKind::None) if i.is_empty() => true,
-            Self::BinaryOp(..) | Self::Paren(..) | Self::UnaryOp(..) => {
-                self.clone().eval(span)?.is_null(span)?
-            }
+            Self::Internal(Internal(
+                InternalKind::BinaryOp(..) | InternalKind::Paren(..) | InternalKind::UnaryOp(..),
+            )) => self.clone().eval(span)?.is_null(span)?,
             Self::List(v, _, Brackets::Bracketed) if v.is_empty() => false,
             Self::List(v, ..) => v
                 .iter()
@@ -126,21 +170,23 @@ impl Value {
         })
     }
 
-    pub fn to_css_string(&self, span: Span) -> SassResult<Cow<'static, str>> {
+    pub(crate) fn to_css_string(&self, span: Span) -> SassResult<Cow<'static, str>> {
         Ok(match self {
             Self::Important => Cow::Borrowed("!important"),
             Self::Dimension(num, unit) => match unit {
                 Unit::Mul(..) => {
-                    return Err((format!("{}{} isn't a valid CSS value.", num, unit), span).into());
+                    return Err(SassError::runtime(
+                        format!("{}{} isn't a valid CSS value.", num, unit),
+                        span,
+                    ));
                 }
                 _ => Cow::Owned(format!("{}{}", num, unit)),
             },
             Self::Map(..) | Self::Function(..) => {
-                return Err((
+                return Err(SassError::runtime(
                     format!("{} isn't a valid CSS value.", self.inspect(span)?),
                     span,
-                )
-                    .into())
+                ))
             }
             Self::List(vals, sep, brackets) => match brackets {
                 Brackets::None => Cow::Owned(
@@ -160,10 +206,10 @@ impl Value {
                 )),
             },
             Self::Color(c) => Cow::Owned(c.to_string()),
-            Self::UnaryOp(..) | Self::BinaryOp(..) => {
+            Self::Internal(Internal(InternalKind::UnaryOp(..) | InternalKind::BinaryOp(..))) => {
                 self.clone().eval(span)?.to_css_string(span)?
             }
-            Self::Paren(val) => val.to_css_string(span)?,
+            Self::Internal(Internal(InternalKind::Paren(val))) => val.to_css_string(span)?,
             Self::String(string, QuoteKind::None) => {
                 let mut after_newline = false;
                 let mut buf = String::with_capacity(string.len());
@@ -194,7 +240,7 @@ impl Value {
             Self::True => Cow::Borrowed("true"),
             Self::False => Cow::Borrowed("false"),
             Self::Null => Cow::Borrowed(""),
-            Self::ArgList(args) => Cow::Owned(
+            Self::Internal(Internal(InternalKind::ArgList(args))) => Cow::Owned(
                 args.iter()
                     .filter(|x| !x.is_null(span).unwrap())
                     .map(|a| Ok(a.node.to_css_string(span)?.into()))
@@ -204,17 +250,62 @@ impl Value {
         })
     }
 
-    pub fn is_true(&self, span: Span) -> SassResult<bool> {
+    /// The CSS representation of this value in compressed output
+    ///
+    /// Numbers drop their leading zero, colors take their shortest form, and
+    /// list separators aren't padded with spaces
+    pub(crate) fn to_compressed_css_string(&self, span: Span) -> SassResult<Cow<'static, str>> {
+        Ok(match self {
+            Self::Dimension(num, unit) => match unit {
+                Unit::Mul(..) => self.to_css_string(span)?,
+                _ => Cow::Owned(format!("{}{}", num.to_compressed_string(), unit)),
+            },
+            Self::List(vals, sep, brackets) => {
+                let sep = match sep {
+                    ListSeparator::Space => " ",
+                    ListSeparator::Comma => ",",
+                    ListSeparator::Slash => "/",
+                };
+                let list = vals
+                    .iter()
+                    .filter(|x| !x.is_null(span).unwrap())
+                    .map(|x| x.to_compressed_css_string(span))
+                    .collect::<SassResult<Vec<Cow<'static, str>>>>()?
+                    .join(sep);
+                match brackets {
+                    Brackets::None => Cow::Owned(list),
+                    Brackets::Bracketed => Cow::Owned(format!("[{}]", list)),
+                }
+            }
+            Self::Color(c) => Cow::Owned(c.to_compressed_string()),
+            Self::Internal(Internal(InternalKind::UnaryOp(..) | InternalKind::BinaryOp(..))) => {
+                self.clone().eval(span)?.to_compressed_css_string(span)?
+            }
+            Self::Internal(Internal(InternalKind::Paren(val))) => {
+                val.to_compressed_css_string(span)?
+            }
+            Self::Internal(Internal(InternalKind::ArgList(args))) => Cow::Owned(
+                args.iter()
+                    .filter(|x| !x.is_null(span).unwrap())
+                    .map(|a| Ok(a.node.to_compressed_css_string(span)?.into()))
+                    .collect::<SassResult<Vec<String>>>()?
+                    .join(","),
+            ),
+            _ => self.to_css_string(span)?,
+        })
+    }
+
+    pub(crate) fn is_true(&self, span: Span) -> SassResult<bool> {
         match self {
             Value::Null | Value::False => Ok(false),
-            Self::BinaryOp(..) | Self::Paren(..) | Self::UnaryOp(..) => {
-                self.clone().eval(span)?.is_true(span)
-            }
+            Self::Internal(Internal(
+                InternalKind::BinaryOp(..) | InternalKind::Paren(..) | InternalKind::UnaryOp(..),
+            )) => self.clone().eval(span)?.is_true(span),
             _ => Ok(true),
         }
     }
 
-    pub fn unquote(self) -> Self {
+    pub(crate) fn unquote(self) -> Self {
         match self {
             Self::String(s1, _) => Self::String(s1, QuoteKind::None),
             Self::List(v, sep, bracket) => {
@@ -224,28 +315,28 @@ impl Value {
         }
     }
 
-    pub const fn span(self, span: Span) -> Spanned<Self> {
+    pub(crate) const fn span(self, span: Span) -> Spanned<Self> {
         Spanned { node: self, span }
     }
 
-    pub fn kind(&self, span: Span) -> SassResult<&'static str> {
+    pub(crate) fn kind(&self, span: Span) -> SassResult<&'static str> {
         match self {
             Self::Color(..) => Ok("color"),
             Self::String(..) | Self::Important => Ok("string"),
             Self::Dimension(..) => Ok("number"),
             Self::List(..) => Ok("list"),
             Self::Function(..) => Ok("function"),
-            Self::ArgList(..) => Ok("arglist"),
+            Self::Internal(Internal(InternalKind::ArgList(..))) => Ok("arglist"),
             Self::True | Self::False => Ok("bool"),
             Self::Null => Ok("null"),
             Self::Map(..) => Ok("map"),
-            Self::BinaryOp(..) | Self::Paren(..) | Self::UnaryOp(..) => {
-                self.clone().eval(span)?.kind(span)
-            }
+            Self::Internal(Internal(
+                InternalKind::BinaryOp(..) | InternalKind::Paren(..) | InternalKind::UnaryOp(..),
+            )) => self.clone().eval(span)?.kind(span),
         }
     }
 
-    pub fn is_special_function(&self) -> bool {
+    pub(crate) fn is_special_function(&self) -> bool {
         match self {
             Self::String(s, QuoteKind::None) => is_special_function(s),
             _ => false,
@@ -262,7 +353,7 @@ impl Value {
 
     // TODO:
     // https://github.com/sass/dart-sass/blob/d4adea7569832f10e3a26d0e420ae51640740cfb/lib/src/ast/sass/expression/list.dart#L39
-    pub fn inspect(&self, span: Span) -> SassResult<Cow<'static, str>> {
+    pub(crate) fn inspect(&self, span: Span) -> SassResult<Cow<'static, str>> {
         Ok(match self {
             Value::List(v, _, brackets) if v.is_empty() => match brackets {
                 Brackets::None => Cow::Borrowed("()"),
@@ -270,11 +361,13 @@ impl Value {
             },
             Value::List(v, sep, brackets) if v.len() == 1 => match brackets {
                 Brackets::None => match sep {
-                    ListSeparator::Space => v[0].inspect(span)?,
+                    ListSeparator::Space | ListSeparator::Slash => v[0].inspect(span)?,
                     ListSeparator::Comma => Cow::Owned(format!("({},)", v[0].inspect(span)?)),
                 },
                 Brackets::Bracketed => match sep {
-                    ListSeparator::Space => Cow::Owned(format!("[{}]", v[0].inspect(span)?)),
+                    ListSeparator::Space | ListSeparator::Slash => {
+                        Cow::Owned(format!("[{}]", v[0].inspect(span)?))
+                    }
                     ListSeparator::Comma => Cow::Owned(format!("[{},]", v[0].inspect(span)?)),
                 },
             },
@@ -305,8 +398,141 @@ impl Value {
                     .collect::<SassResult<Vec<String>>>()?
                     .join(", ")
             )),
-            Value::Paren(v) => v.inspect(span)?,
+            Value::Internal(Internal(InternalKind::Paren(v))) => v.inspect(span)?,
             v => v.to_css_string(span)?,
         })
     }
 }
+
+/// The API for using values from outside of a stylesheet, such as in host
+/// functions
+impl Value {
+    /// A number with `unit`, which is `Unit::None` for a unitless number
+    pub fn number<N: Into<Number>>(number: N, unit: Unit) -> Self {
+        Value::Dimension(number.into(), unit)
+    }
+
+    pub fn quoted_string<S: Into<String>>(string: S) -> Self {
+        Value::String(string.into(), QuoteKind::Quoted)
+    }
+
+    pub fn unquoted_string<S: Into<String>>(string: S) -> Self {
+        Value::String(string.into(), QuoteKind::None)
+    }
+
+    /// Whether this value is considered true by `@if` and boolean
+    /// operators, which is all values except `false` and `null`
+    pub fn is_truthy(&self) -> bool {
+        !matches!(self, Value::False | Value::Null)
+    }
+
+    pub fn as_number(&self) -> Option<(&Number, &Unit)> {
+        match self {
+            Value::Dimension(number, unit) => Some((number, unit)),
+            _ => None,
+        }
+    }
+
+    /// This number converted to `unit`, if it's a number with a compatible
+    /// unit
+    ///
+    /// As in arithmetic, a unitless number is compatible with every unit,
+    /// and every unit is compatible with a unitless number
+    pub fn number_in(&self, unit: &Unit) -> Option<Number> {
+        let (number, from) = self.as_number()?;
+        if from == unit || from == &Unit::None || unit == &Unit::None {
+            return Some(number.clone());
+        }
+        if !from.comparable(unit) {
+            return None;
+        }
+        let ratio = UNIT_CONVERSION_TABLE
+            .get(unit.to_string().as_str())?
+            .get(from.to_string().as_str())?;
+        Some(number.clone() * ratio.clone())
+    }
+
+    /// The contents of this string, without quotes
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(string, ..) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_color(&self) -> Option<&Color> {
+        match self {
+            Value::Color(color) => Some(color),
+            _ => None,
+        }
+    }
+
+    /// This value as a map, if it is one
+    ///
+    /// An empty list is also an empty map
+    pub fn as_map(&self) -> Option<Cow<'_, SassMap>> {
+        match self {
+            Value::Map(map) => Some(Cow::Borrowed(map)),
+            Value::List(v, ..) if v.is_empty() => Some(Cow::Owned(SassMap::new())),
+            _ => None,
+        }
+    }
+
+    /// The elements of this value as a list
+    ///
+    /// A map is a list of key-value pairs, and any other value that isn't a
+    /// list is a list containing only itself
+    pub fn as_list(&self) -> Vec<Value> {
+        match self {
+            Value::List(v, ..) => v.clone(),
+            Value::Map(map) => map.clone().entries(),
+            Value::Internal(Internal(InternalKind::ArgList(v))) => {
+                v.iter().map(|v| v.node.clone()).collect()
+            }
+            v => vec![v.clone()],
+        }
+    }
+
+    /// This value as a number, or an error naming the argument `name` that
+    /// can be returned from a host function
+    pub fn assert_number(&self, name: &str) -> Result<(&Number, &Unit), String> {
+        self.as_number()
+            .ok_or_else(|| format!("${}: {:#} is not a number.", name, self))
+    }
+
+    pub fn assert_string(&self, name: &str) -> Result<&str, String> {
+        self.as_str()
+            .ok_or_else(|| format!("${}: {:#} is not a string.", name, self))
+    }
+
+    pub fn assert_color(&self, name: &str) -> Result<&Color, String> {
+        self.as_color()
+            .ok_or_else(|| format!("${}: {:#} is not a color.", name, self))
+    }
+
+    pub fn assert_map(&self, name: &str) -> Result<Cow<'_, SassMap>, String> {
+        self.as_map()
+            .ok_or_else(|| format!("${}: {:#} is not a map.", name, self))
+    }
+}
+
+/// Values are written as they would be in CSS, except for those that aren't
+/// valid CSS such as maps, which are written as by `inspect()`
+///
+/// With the alternate flag, `{:#}`, every value is written as by `inspect()`.
+/// A value that can't be written either way, such as a map containing
+/// another map, is written as by `Debug`
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let span = *NO_SPAN;
+        let value = if f.alternate() {
+            self.inspect(span)
+        } else {
+            self.to_css_string(span).or_else(|_| self.inspect(span))
+        };
+        match value {
+            Ok(value) => f.write_str(&value),
+            Err(..) => write!(f, "{:?}", self),
+        }
+    }
+}
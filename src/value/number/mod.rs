@@ -8,88 +8,123 @@ use std::ops::{
 
 use num_bigint::BigInt;
 use num_rational::{BigRational, Rational64};
-use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Num, One, Signed, Zero};
+use num_traits::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Num, One, Signed, ToPrimitive, Zero,
+};
 
 use integer::Integer;
 
-mod integer;
+use crate::context::precision;
 
-const PRECISION: usize = 10;
+mod integer;
 
+/// A SassScript number, without its unit
+///
+/// Numbers are exact rationals, so arithmetic never loses precision. They
+/// are created from integers and floats with `From`, and read with
+/// [`Number::to_f64`]
 #[derive(Clone, Eq, PartialEq, Ord)]
-pub(crate) enum Number {
+pub struct Number(Repr);
+
+#[derive(Clone, Eq, PartialEq, PartialOrd, Ord)]
+enum Repr {
     Machine(Rational64),
     Big(BigRational),
 }
 
 impl Number {
-    pub const fn new_machine(val: Rational64) -> Number {
-        Number::Machine(val)
+    pub(crate) const fn new_machine(val: Rational64) -> Number {
+        Number(Repr::Machine(val))
     }
 
-    pub const fn new_big(val: BigRational) -> Number {
-        Number::Big(val)
+    pub(crate) const fn new_big(val: BigRational) -> Number {
+        Number(Repr::Big(val))
     }
 
-    pub fn to_integer(&self) -> Integer {
-        match self {
-            Self::Machine(val) => Integer::Machine(val.to_integer()),
-            Self::Big(val) => Integer::Big(val.to_integer()),
+    pub(crate) fn to_integer(&self) -> Integer {
+        match &self.0 {
+            Repr::Machine(val) => Integer::Machine(val.to_integer()),
+            Repr::Big(val) => Integer::Big(val.to_integer()),
         }
     }
 
-    pub fn machine_ratio<A: Into<i64>, B: Into<i64>>(a: A, b: B) -> Self {
+    pub(crate) fn machine_ratio<A: Into<i64>, B: Into<i64>>(a: A, b: B) -> Self {
         Number::new_machine(Rational64::new(a.into(), b.into()))
     }
 
     #[allow(dead_code)]
-    pub fn big_ratio<A: Into<BigInt>, B: Into<BigInt>>(a: A, b: B) -> Self {
+    pub(crate) fn big_ratio<A: Into<BigInt>, B: Into<BigInt>>(a: A, b: B) -> Self {
         Number::new_big(BigRational::new(a.into(), b.into()))
     }
 
-    pub fn round(&self) -> Self {
-        match self {
-            Self::Machine(val) => Self::Machine(val.round()),
-            Self::Big(val) => Self::Big(val.round()),
+    pub(crate) fn round(&self) -> Self {
+        match &self.0 {
+            Repr::Machine(val) => Number::new_machine(val.round()),
+            Repr::Big(val) => Number::new_big(val.round()),
+        }
+    }
+
+    pub(crate) fn ceil(&self) -> Self {
+        match &self.0 {
+            Repr::Machine(val) => Number::new_machine(val.ceil()),
+            Repr::Big(val) => Number::new_big(val.ceil()),
+        }
+    }
+
+    pub(crate) fn floor(&self) -> Self {
+        match &self.0 {
+            Repr::Machine(val) => Number::new_machine(val.floor()),
+            Repr::Big(val) => Number::new_big(val.floor()),
         }
     }
 
-    pub fn ceil(&self) -> Self {
-        match self {
-            Self::Machine(val) => Self::Machine(val.ceil()),
-            Self::Big(val) => Self::Big(val.ceil()),
+    pub(crate) fn abs(&self) -> Self {
+        match &self.0 {
+            Repr::Machine(val) => Number::new_machine(val.abs()),
+            Repr::Big(val) => Number::new_big(val.abs()),
         }
     }
 
-    pub fn floor(&self) -> Self {
-        match self {
-            Self::Machine(val) => Self::Machine(val.floor()),
-            Self::Big(val) => Self::Big(val.floor()),
+    /// Format this number without a leading zero, as in compressed output
+    ///
+    /// `0.5` is written as `.5`
+    pub(crate) fn to_compressed_string(&self) -> String {
+        let number = self.to_string();
+        if let Some(rest) = number.strip_prefix("0.") {
+            format!(".{}", rest)
+        } else if let Some(rest) = number.strip_prefix("-0.") {
+            format!("-.{}", rest)
+        } else {
+            number
         }
     }
 
-    pub fn abs(&self) -> Self {
-        match self {
-            Self::Machine(val) => Self::Machine(val.abs()),
-            Self::Big(val) => Self::Big(val.abs()),
+    /// The nearest floating point number to this number
+    pub fn to_f64(&self) -> f64 {
+        match &self.0 {
+            Repr::Machine(val) => *val.numer() as f64 / *val.denom() as f64,
+            Repr::Big(val) => match (val.numer().to_f64(), val.denom().to_f64()) {
+                (Some(numer), Some(denom)) => numer / denom,
+                _ => f64::NAN,
+            },
         }
     }
 
-    pub fn is_decimal(&self) -> bool {
-        match self {
-            Self::Machine(v) => !v.is_integer(),
-            Self::Big(v) => !v.is_integer(),
+    pub(crate) fn is_decimal(&self) -> bool {
+        match &self.0 {
+            Repr::Machine(v) => !v.is_integer(),
+            Repr::Big(v) => !v.is_integer(),
         }
     }
 
-    pub fn fract(&mut self) -> Number {
-        match self {
-            Self::Machine(v) => Number::new_machine(v.fract()),
-            Self::Big(v) => Number::new_big(v.fract()),
+    pub(crate) fn fract(&mut self) -> Number {
+        match &self.0 {
+            Repr::Machine(v) => Number::new_machine(v.fract()),
+            Repr::Big(v) => Number::new_big(v.fract()),
         }
     }
 
-    pub fn clamp<A: Into<Number> + Zero, B: Into<Number>>(self, min: A, max: B) -> Self {
+    pub(crate) fn clamp<A: Into<Number> + Zero, B: Into<Number>>(self, min: A, max: B) -> Self {
         let max = max.into();
         if self > max {
             return max;
@@ -120,9 +155,9 @@ impl Zero for Number {
     }
 
     fn is_zero(&self) -> bool {
-        match self {
-            Self::Machine(v) => v.is_zero(),
-            Self::Big(v) => v.is_zero(),
+        match &self.0 {
+            Repr::Machine(v) => v.is_zero(),
+            Repr::Big(v) => v.is_zero(),
         }
     }
 }
@@ -133,9 +168,9 @@ impl One for Number {
     }
 
     fn is_one(&self) -> bool {
-        match self {
-            Self::Machine(v) => v.is_one(),
-            Self::Big(v) => v.is_one(),
+        match &self.0 {
+            Repr::Machine(v) => v.is_one(),
+            Repr::Big(v) => v.is_one(),
         }
     }
 }
@@ -170,16 +205,16 @@ impl Signed for Number {
     }
 
     fn is_positive(&self) -> bool {
-        match self {
-            Self::Machine(v) => v.is_positive(),
-            Self::Big(v) => v.is_positive(),
+        match &self.0 {
+            Repr::Machine(v) => v.is_positive(),
+            Repr::Big(v) => v.is_positive(),
         }
     }
 
     fn is_negative(&self) -> bool {
-        match self {
-            Self::Machine(v) => v.is_negative(),
-            Self::Big(v) => v.is_negative(),
+        match &self.0 {
+            Repr::Machine(v) => v.is_negative(),
+            Repr::Big(v) => v.is_negative(),
         }
     }
 }
@@ -189,9 +224,9 @@ macro_rules! from_integer {
         impl From<$ty> for Number {
             fn from(b: $ty) -> Self {
                 if let Ok(v) = i64::try_from(b) {
-                    Number::Machine(Rational64::from_integer(v))
+                    Number::new_machine(Rational64::from_integer(v))
                 } else {
-                    Number::Big(BigRational::from_integer(BigInt::from(b)))
+                    Number::new_big(BigRational::from_integer(BigInt::from(b)))
                 }
             }
         }
@@ -217,7 +252,7 @@ impl From<i64> for Number {
 // todo: implement std::convertTryFrom instead
 impl From<f64> for Number {
     fn from(b: f64) -> Self {
-        Number::Big(BigRational::from_float(b).unwrap())
+        Number::new_big(BigRational::from_float(b).unwrap())
     }
 }
 
@@ -238,9 +273,10 @@ impl Display for Number {
         let mut whole = self.to_integer().abs();
         let has_decimal = self.is_decimal();
         let mut frac = self.abs().fract();
-        let mut dec = String::with_capacity(if has_decimal { PRECISION + 1 } else { 0 });
+        let precision = precision();
+        let mut dec = String::with_capacity(if has_decimal { precision + 1 } else { 0 });
         if has_decimal {
-            for _ in 0..(PRECISION - 1) {
+            for _ in 0..(precision - 1) {
                 frac *= Self::from(10);
                 write!(dec, "{}", frac.to_integer())?;
                 frac = frac.fract();
@@ -295,24 +331,24 @@ impl Display for Number {
 
 impl PartialOrd for Number {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self {
-            Self::Machine(val1) => match other {
-                Self::Machine(val2) => val1.partial_cmp(val2),
-                Self::Big(val2) => {
+        match &self.0 {
+            Repr::Machine(val1) => match &other.0 {
+                Repr::Machine(val2) => val1.partial_cmp(val2),
+                Repr::Big(val2) => {
                     let tuple: (i64, i64) = (*val1).into();
                     BigRational::new_raw(BigInt::from(tuple.0), BigInt::from(tuple.1))
                         .partial_cmp(val2)
                 }
             },
-            Self::Big(val1) => match other {
-                Self::Machine(val2) => {
+            Repr::Big(val1) => match &other.0 {
+                Repr::Machine(val2) => {
                     let tuple: (i64, i64) = (*val2).into();
                     val1.partial_cmp(&BigRational::new_raw(
                         BigInt::from(tuple.0),
                         BigInt::from(tuple.1),
                     ))
                 }
-                Self::Big(val2) => val1.partial_cmp(val2),
+                Repr::Big(val2) => val1.partial_cmp(val2),
             },
         }
     }
@@ -322,14 +358,14 @@ impl Add for Number {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        match self {
-            Self::Machine(val1) => match other {
-                Self::Machine(val2) => match val1.checked_add(&val2) {
-                    Some(v) => Self::Machine(v),
+        match self.0 {
+            Repr::Machine(val1) => match other.0 {
+                Repr::Machine(val2) => match val1.checked_add(&val2) {
+                    Some(v) => Number::new_machine(v),
                     None => {
                         let tuple1: (i64, i64) = val1.into();
                         let tuple2: (i64, i64) = val2.into();
-                        Self::Big(
+                        Number::new_big(
                             BigRational::new_raw(BigInt::from(tuple1.0), BigInt::from(tuple1.1))
                                 + BigRational::new_raw(
                                     BigInt::from(tuple2.0),
@@ -338,18 +374,18 @@ impl Add for Number {
                         )
                     }
                 },
-                Self::Big(val2) => {
+                Repr::Big(val2) => {
                     let tuple: (i64, i64) = val1.into();
-                    Self::Big(
+                    Number::new_big(
                         BigRational::new_raw(BigInt::from(tuple.0), BigInt::from(tuple.1)) + val2,
                     )
                 }
             },
-            Self::Big(val1) => match other {
-                Self::Big(val2) => Self::Big(val1 + val2),
-                Self::Machine(val2) => {
+            Repr::Big(val1) => match other.0 {
+                Repr::Big(val2) => Number::new_big(val1 + val2),
+                Repr::Machine(val2) => {
                     let tuple: (i64, i64) = val2.into();
-                    Self::Big(
+                    Number::new_big(
                         val1 + BigRational::new_raw(BigInt::from(tuple.0), BigInt::from(tuple.1)),
                     )
                 }
@@ -362,14 +398,14 @@ impl Add<&Self> for Number {
     type Output = Self;
 
     fn add(self, other: &Self) -> Self {
-        match self {
-            Self::Machine(val1) => match other {
-                Self::Machine(val2) => match val1.checked_add(val2) {
-                    Some(v) => Self::Machine(v),
+        match self.0 {
+            Repr::Machine(val1) => match &other.0 {
+                Repr::Machine(val2) => match val1.checked_add(val2) {
+                    Some(v) => Number::new_machine(v),
                     None => {
                         let tuple1: (i64, i64) = val1.into();
                         let tuple2: (i64, i64) = (*val2).into();
-                        Self::Big(
+                        Number::new_big(
                             BigRational::new_raw(BigInt::from(tuple1.0), BigInt::from(tuple1.1))
                                 + BigRational::new_raw(
                                     BigInt::from(tuple2.0),
@@ -378,18 +414,18 @@ impl Add<&Self> for Number {
                         )
                     }
                 },
-                Self::Big(val2) => {
+                Repr::Big(val2) => {
                     let tuple: (i64, i64) = val1.into();
-                    Self::Big(
+                    Number::new_big(
                         BigRational::new_raw(BigInt::from(tuple.0), BigInt::from(tuple.1)) + val2,
                     )
                 }
             },
-            Self::Big(val1) => match other {
-                Self::Big(val2) => Self::Big(val1 + val2),
-                Self::Machine(val2) => {
+            Repr::Big(val1) => match &other.0 {
+                Repr::Big(val2) => Number::new_big(val1 + val2),
+                Repr::Machine(val2) => {
                     let tuple: (i64, i64) = (*val2).into();
-                    Self::Big(
+                    Number::new_big(
                         val1 + BigRational::new_raw(BigInt::from(tuple.0), BigInt::from(tuple.1)),
                     )
                 }
@@ -409,14 +445,14 @@ impl Sub for Number {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        match self {
-            Self::Machine(val1) => match other {
-                Self::Machine(val2) => match val1.checked_sub(&val2) {
-                    Some(v) => Self::Machine(v),
+        match self.0 {
+            Repr::Machine(val1) => match other.0 {
+                Repr::Machine(val2) => match val1.checked_sub(&val2) {
+                    Some(v) => Number::new_machine(v),
                     None => {
                         let tuple1: (i64, i64) = val1.into();
                         let tuple2: (i64, i64) = val2.into();
-                        Self::Big(
+                        Number::new_big(
                             BigRational::new_raw(BigInt::from(tuple1.0), BigInt::from(tuple1.1))
                                 - BigRational::new_raw(
                                     BigInt::from(tuple2.0),
@@ -425,18 +461,18 @@ impl Sub for Number {
                         )
                     }
                 },
-                Self::Big(val2) => {
+                Repr::Big(val2) => {
                     let tuple: (i64, i64) = val1.into();
-                    Self::Big(
+                    Number::new_big(
                         BigRational::new_raw(BigInt::from(tuple.0), BigInt::from(tuple.1)) - val2,
                     )
                 }
             },
-            Self::Big(val1) => match other {
-                Self::Big(val2) => Self::Big(val1 - val2),
-                Self::Machine(val2) => {
+            Repr::Big(val1) => match other.0 {
+                Repr::Big(val2) => Number::new_big(val1 - val2),
+                Repr::Machine(val2) => {
                     let tuple: (i64, i64) = val2.into();
-                    Self::Big(
+                    Number::new_big(
                         val1 - BigRational::new_raw(BigInt::from(tuple.0), BigInt::from(tuple.1)),
                     )
                 }
@@ -456,14 +492,14 @@ impl Mul for Number {
     type Output = Self;
 
     fn mul(self, other: Self) -> Self {
-        match self {
-            Self::Machine(val1) => match other {
-                Self::Machine(val2) => match val1.checked_mul(&val2) {
-                    Some(v) => Self::Machine(v),
+        match self.0 {
+            Repr::Machine(val1) => match other.0 {
+                Repr::Machine(val2) => match val1.checked_mul(&val2) {
+                    Some(v) => Number::new_machine(v),
                     None => {
                         let tuple1: (i64, i64) = val1.into();
                         let tuple2: (i64, i64) = val2.into();
-                        Self::Big(
+                        Number::new_big(
                             BigRational::new_raw(BigInt::from(tuple1.0), BigInt::from(tuple1.1))
                                 * BigRational::new_raw(
                                     BigInt::from(tuple2.0),
@@ -472,18 +508,18 @@ impl Mul for Number {
                         )
                     }
                 },
-                Self::Big(val2) => {
+                Repr::Big(val2) => {
                     let tuple: (i64, i64) = val1.into();
-                    Self::Big(
+                    Number::new_big(
                         BigRational::new_raw(BigInt::from(tuple.0), BigInt::from(tuple.1)) * val2,
                     )
                 }
             },
-            Self::Big(val1) => match other {
-                Self::Big(val2) => Self::Big(val1 * val2),
-                Self::Machine(val2) => {
+            Repr::Big(val1) => match other.0 {
+                Repr::Big(val2) => Number::new_big(val1 * val2),
+                Repr::Machine(val2) => {
                     let tuple: (i64, i64) = val2.into();
-                    Self::Big(
+                    Number::new_big(
                         val1 * BigRational::new_raw(BigInt::from(tuple.0), BigInt::from(tuple.1)),
                     )
                 }
@@ -503,14 +539,14 @@ impl Div for Number {
     type Output = Self;
 
     fn div(self, other: Self) -> Self {
-        match self {
-            Self::Machine(val1) => match other {
-                Self::Machine(val2) => match val1.checked_div(&val2) {
-                    Some(v) => Self::Machine(v),
+        match self.0 {
+            Repr::Machine(val1) => match other.0 {
+                Repr::Machine(val2) => match val1.checked_div(&val2) {
+                    Some(v) => Number::new_machine(v),
                     None => {
                         let tuple1: (i64, i64) = val1.into();
                         let tuple2: (i64, i64) = val2.into();
-                        Self::Big(
+                        Number::new_big(
                             BigRational::new_raw(BigInt::from(tuple1.0), BigInt::from(tuple1.1))
                                 / BigRational::new_raw(
                                     BigInt::from(tuple2.0),
@@ -519,18 +555,18 @@ impl Div for Number {
                         )
                     }
                 },
-                Self::Big(val2) => {
+                Repr::Big(val2) => {
                     let tuple: (i64, i64) = val1.into();
-                    Self::Big(
+                    Number::new_big(
                         BigRational::new_raw(BigInt::from(tuple.0), BigInt::from(tuple.1)) / val2,
                     )
                 }
             },
-            Self::Big(val1) => match other {
-                Self::Big(val2) => Self::Big(val1 / val2),
-                Self::Machine(val2) => {
+            Repr::Big(val1) => match other.0 {
+                Repr::Big(val2) => Number::new_big(val1 / val2),
+                Repr::Machine(val2) => {
                     let tuple: (i64, i64) = val2.into();
-                    Self::Big(
+                    Number::new_big(
                         val1 / BigRational::new_raw(BigInt::from(tuple.0), BigInt::from(tuple.1)),
                     )
                 }
@@ -550,29 +586,29 @@ impl Rem for Number {
     type Output = Self;
 
     fn rem(self, other: Self) -> Self {
-        match self {
-            Self::Machine(val1) => match other {
+        match self.0 {
+            Repr::Machine(val1) => match other.0 {
                 // todo: checked_rem for ratio?
-                Self::Machine(val2) => {
+                Repr::Machine(val2) => {
                     let tuple1: (i64, i64) = val1.into();
                     let tuple2: (i64, i64) = val2.into();
-                    Self::Big(
+                    Number::new_big(
                         BigRational::new_raw(BigInt::from(tuple1.0), BigInt::from(tuple1.1))
                             % BigRational::new_raw(BigInt::from(tuple2.0), BigInt::from(tuple2.1)),
                     )
                 }
-                Self::Big(val2) => {
+                Repr::Big(val2) => {
                     let tuple: (i64, i64) = val1.into();
-                    Self::Big(
+                    Number::new_big(
                         BigRational::new_raw(BigInt::from(tuple.0), BigInt::from(tuple.1)) % val2,
                     )
                 }
             },
-            Self::Big(val1) => match other {
-                Self::Big(val2) => Self::Big(val1 % val2),
-                Self::Machine(val2) => {
+            Repr::Big(val1) => match other.0 {
+                Repr::Big(val2) => Number::new_big(val1 % val2),
+                Repr::Machine(val2) => {
                     let tuple: (i64, i64) = val2.into();
-                    Self::Big(
+                    Number::new_big(
                         val1 % BigRational::new_raw(BigInt::from(tuple.0), BigInt::from(tuple.1)),
                     )
                 }
@@ -592,9 +628,9 @@ impl Neg for Number {
     type Output = Self;
 
     fn neg(self) -> Self {
-        match self {
-            Self::Machine(v) => Self::Machine(-v),
-            Self::Big(v) => Self::Big(-v),
+        match self.0 {
+            Repr::Machine(v) => Number::new_machine(-v),
+            Repr::Big(v) => Number::new_big(-v),
         }
     }
 }
@@ -2,14 +2,14 @@ use std::cmp::Ordering;
 
 use codemap::{Span, Spanned};
 
-use crate::common::{Op, QuoteKind};
-use crate::error::SassResult;
+use crate::common::{Brackets, ListSeparator, Op, QuoteKind};
+use crate::error::{SassError, SassResult};
 use crate::unit::{Unit, UNIT_CONVERSION_TABLE};
-use crate::value::Value;
+use crate::value::{Internal, InternalKind, SassMap, Value};
 
 impl Value {
-    pub fn equals(self, mut other: Value, span: Span) -> SassResult<Spanned<Value>> {
-        if let Self::Paren(..) = other {
+    pub(crate) fn equals(self, mut other: Value, span: Span) -> SassResult<Spanned<Value>> {
+        if let Self::Internal(Internal(InternalKind::Paren(..))) = other {
             other = other.eval(span)?.node
         }
 
@@ -37,15 +37,15 @@ impl Value {
                 }
                 _ => false,
             },
-            Self::BinaryOp(left, op2, right) => {
+            Self::Internal(Internal(InternalKind::BinaryOp(left, op2, right))) => {
                 if op2.precedence() >= precedence {
-                    Self::BinaryOp(left, op2, right).eval(span)?.node == other
+                    Self::binary_op(left, op2, right).eval(span)?.node == other
                 } else {
-                    return Self::BinaryOp(
+                    return Self::binary_op(
                         left,
                         op2,
                         Box::new(
-                            Self::BinaryOp(right, Op::Equal, Box::new(other))
+                            Self::binary_op(right, Op::Equal, Box::new(other))
                                 .eval(span)?
                                 .node,
                         ),
@@ -58,8 +58,8 @@ impl Value {
         .span(span))
     }
 
-    pub fn not_equals(self, mut other: Value, span: Span) -> SassResult<Spanned<Value>> {
-        if let Self::Paren(..) = other {
+    pub(crate) fn not_equals(self, mut other: Value, span: Span) -> SassResult<Spanned<Value>> {
+        if let Self::Internal(Internal(InternalKind::Paren(..))) = other {
             other = other.eval(span)?.node
         }
 
@@ -87,15 +87,15 @@ impl Value {
                 }
                 _ => true,
             },
-            Self::BinaryOp(left, op2, right) => {
+            Self::Internal(Internal(InternalKind::BinaryOp(left, op2, right))) => {
                 if op2.precedence() >= precedence {
-                    Self::BinaryOp(left, op2, right).eval(span)?.node != other
+                    Self::binary_op(left, op2, right).eval(span)?.node != other
                 } else {
-                    return Self::BinaryOp(
+                    return Self::binary_op(
                         left,
                         op2,
                         Box::new(
-                            Self::BinaryOp(right, Op::NotEqual, Box::new(other))
+                            Self::binary_op(right, Op::NotEqual, Box::new(other))
                                 .eval(span)?
                                 .node,
                         ),
@@ -108,16 +108,16 @@ impl Value {
         .span(span))
     }
 
-    pub fn unary_op_plus(self, span: Span) -> SassResult<Self> {
+    pub(crate) fn unary_op_plus(self, span: Span) -> SassResult<Self> {
         Ok(match self.eval(span)?.node {
             v @ Value::Dimension(..) => v,
             v => Value::String(format!("+{}", v.to_css_string(span)?), QuoteKind::None),
         })
     }
 
-    pub fn eval(self, span: Span) -> SassResult<Spanned<Self>> {
+    pub(crate) fn eval(self, span: Span) -> SassResult<Spanned<Self>> {
         Ok(match self {
-            Self::BinaryOp(lhs, op, rhs) => match op {
+            Self::Internal(Internal(InternalKind::BinaryOp(lhs, op, rhs))) => match op {
                 Op::Plus => lhs.add(*rhs, span)?,
                 Op::Minus => lhs.sub(*rhs, span)?,
                 Op::Equal => lhs.equals(*rhs, span)?.node,
@@ -144,8 +144,8 @@ impl Value {
                     }
                 }
             },
-            Self::Paren(v) => v.eval(span)?.node,
-            Self::UnaryOp(op, val) => match op {
+            Self::Internal(Internal(InternalKind::Paren(v))) => v.eval(span)?.node,
+            Self::Internal(Internal(InternalKind::UnaryOp(op, val))) => match op {
                 Op::Plus => val.unary_op_plus(span)?,
                 Op::Minus => val.neg(span)?,
                 Op::Not => Self::bool(!val.eval(span)?.is_true(span)?),
@@ -156,8 +156,40 @@ impl Value {
         .span(span))
     }
 
-    pub fn cmp(self, mut other: Self, op: Op, span: Span) -> SassResult<Spanned<Value>> {
-        if let Self::Paren(..) = other {
+    /// Evaluate this value along with every value inside of it, so that no
+    /// operations or argument lists are left anywhere in the result
+    ///
+    /// Used for the values handed to host functions
+    pub(crate) fn eval_all(self, span: Span) -> SassResult<Self> {
+        Ok(match self.eval(span)?.node {
+            Self::List(items, separator, brackets) => Self::List(
+                items
+                    .into_iter()
+                    .map(|item| item.eval_all(span))
+                    .collect::<SassResult<Vec<Value>>>()?,
+                separator,
+                brackets,
+            ),
+            Self::Map(map) => {
+                let mut evaluated = SassMap::new();
+                for (key, value) in map {
+                    evaluated.insert(key.eval_all(span)?, value.eval_all(span)?);
+                }
+                Self::Map(evaluated)
+            }
+            Self::Internal(Internal(InternalKind::ArgList(args))) => Self::List(
+                args.into_iter()
+                    .map(|arg| arg.node.eval_all(arg.span))
+                    .collect::<SassResult<Vec<Value>>>()?,
+                ListSeparator::Comma,
+                Brackets::None,
+            ),
+            v => v,
+        })
+    }
+
+    pub(crate) fn cmp(self, mut other: Self, op: Op, span: Span) -> SassResult<Spanned<Value>> {
+        if let Self::Internal(Internal(InternalKind::Paren(..))) = other {
             other = other.eval(span)?.node
         }
         let precedence = op.precedence();
@@ -165,9 +197,10 @@ impl Value {
             Self::Dimension(num, unit) => match &other {
                 Self::Dimension(num2, unit2) => {
                     if !unit.comparable(unit2) {
-                        return Err(
-                            (format!("Incompatible units {} and {}.", unit2, unit), span).into(),
-                        );
+                        return Err(SassError::runtime(
+                            format!("Incompatible units {} and {}.", unit2, unit),
+                            span,
+                        ));
                     }
                     if &unit == unit2 || unit == Unit::None || unit2 == &Unit::None {
                         num.cmp(num2)
@@ -180,9 +213,9 @@ impl Value {
                         )
                     }
                 }
-                Self::BinaryOp(..) => todo!(),
+                Self::Internal(Internal(InternalKind::BinaryOp(..))) => todo!(),
                 v => {
-                    return Err((
+                    return Err(SassError::runtime(
                         format!(
                             "Undefined operation \"{} {} {}\".",
                             v.inspect(span)?,
@@ -190,30 +223,29 @@ impl Value {
                             other.inspect(span)?
                         ),
                         span,
-                    )
-                        .into())
+                    ))
                 }
             },
-            Self::BinaryOp(left, op2, right) => {
+            Self::Internal(Internal(InternalKind::BinaryOp(left, op2, right))) => {
                 return if op2.precedence() >= precedence {
-                    Self::BinaryOp(left, op2, right)
+                    Self::binary_op(left, op2, right)
                         .eval(span)?
                         .node
                         .cmp(other, op, span)
                 } else {
-                    Self::BinaryOp(
+                    Self::binary_op(
                         left,
                         op2,
-                        Box::new(Self::BinaryOp(right, op, Box::new(other)).eval(span)?.node),
+                        Box::new(Self::binary_op(right, op, Box::new(other)).eval(span)?.node),
                     )
                     .eval(span)
                 }
             }
-            Self::UnaryOp(..) | Self::Paren(..) => {
+            Self::Internal(Internal(InternalKind::UnaryOp(..) | InternalKind::Paren(..))) => {
                 return self.eval(span)?.node.cmp(other, op, span)
             }
             _ => {
-                return Err((
+                return Err(SassError::runtime(
                     format!(
                         "Undefined operation \"{} {} {}\".",
                         self.inspect(span)?,
@@ -221,8 +253,7 @@ impl Value {
                         other.inspect(span)?
                     ),
                     span,
-                )
-                    .into())
+                ))
             }
         };
         Ok(match op {
@@ -247,22 +278,21 @@ impl Value {
         .span(span))
     }
 
-    pub fn add(self, mut other: Self, span: Span) -> SassResult<Self> {
-        if let Self::Paren(..) = other {
+    pub(crate) fn add(self, mut other: Self, span: Span) -> SassResult<Self> {
+        if let Self::Internal(Internal(InternalKind::Paren(..))) = other {
             other = other.eval(span)?.node
-        } else if let Self::UnaryOp(..) = other {
+        } else if let Self::Internal(Internal(InternalKind::UnaryOp(..))) = other {
             other = other.eval(span)?.node
         }
         let precedence = Op::Plus.precedence();
         Ok(match self {
             Self::Map(..) | Self::Function(..) => {
-                return Err((
+                return Err(SassError::runtime(
                     format!("{} isn't a valid CSS value.", self.inspect(span)?),
                     span,
-                )
-                    .into())
+                ))
             }
-            Self::ArgList(..) => todo!(),
+            Self::Internal(Internal(InternalKind::ArgList(..))) => todo!(),
             Self::Important | Self::True | Self::False => match other {
                 Self::String(s, QuoteKind::Quoted) => Value::String(
                     format!("{}{}", self.to_css_string(span)?, s),
@@ -285,9 +315,10 @@ impl Value {
             Self::Dimension(num, unit) => match other {
                 Self::Dimension(num2, unit2) => {
                     if !unit.comparable(&unit2) {
-                        return Err(
-                            (format!("Incompatible units {} and {}.", unit2, unit), span).into(),
-                        );
+                        return Err(SassError::runtime(
+                            format!("Incompatible units {} and {}.", unit2, unit),
+                            span,
+                        ));
                     }
                     if unit == unit2 {
                         Value::Dimension(num + num2, unit)
@@ -316,14 +347,13 @@ impl Value {
                     QuoteKind::None,
                 ),
                 Self::Map(..) | Self::Function(..) => {
-                    return Err((
+                    return Err(SassError::runtime(
                         format!("{} isn't a valid CSS value.", other.inspect(span)?),
                         span,
-                    )
-                        .into())
+                    ))
                 }
                 _ => {
-                    return Err((
+                    return Err(SassError::runtime(
                         format!(
                             "Undefined operation \"{}{} + {}\".",
                             num,
@@ -331,8 +361,7 @@ impl Value {
                             other.inspect(span)?
                         ),
                         span,
-                    )
-                        .into())
+                    ))
                 }
             },
             Self::Color(c) => match other {
@@ -343,25 +372,24 @@ impl Value {
                     QuoteKind::None,
                 ),
                 _ => {
-                    return Err((
+                    return Err(SassError::runtime(
                         format!("Undefined operation \"{} + {}\".", c, other.inspect(span)?),
                         span,
-                    )
-                        .into())
+                    ))
                 }
             },
-            Self::BinaryOp(left, op, right) => {
+            Self::Internal(Internal(InternalKind::BinaryOp(left, op, right))) => {
                 if op.precedence() >= precedence {
-                    Self::BinaryOp(left, op, right)
+                    Self::binary_op(left, op, right)
                         .eval(span)?
                         .node
                         .add(other, span)?
                 } else {
-                    Self::BinaryOp(
+                    Self::binary_op(
                         left,
                         op,
                         Box::new(
-                            Self::BinaryOp(right, Op::Plus, Box::new(other))
+                            Self::binary_op(right, Op::Plus, Box::new(other))
                                 .eval(span)?
                                 .node,
                         ),
@@ -370,7 +398,9 @@ impl Value {
                     .node
                 }
             }
-            Self::UnaryOp(..) | Self::Paren(..) => self.eval(span)?.node.add(other, span)?,
+            Self::Internal(Internal(InternalKind::UnaryOp(..) | InternalKind::Paren(..))) => {
+                self.eval(span)?.node.add(other, span)?
+            }
             Self::String(text, quotes) => match other {
                 Self::String(text2, ..) => Self::String(text + &text2, quotes),
                 _ => Value::String(text + &other.to_css_string(span)?, quotes),
@@ -379,7 +409,9 @@ impl Value {
                 Self::String(s, q) => {
                     Value::String(format!("{}{}", self.to_css_string(span)?, s), q)
                 }
-                Self::Paren(..) => (self.add(other.eval(span)?.node, span))?,
+                Self::Internal(Internal(InternalKind::Paren(..))) => {
+                    (self.add(other.eval(span)?.node, span))?
+                }
                 _ => Value::String(
                     format!(
                         "{}{}",
@@ -392,8 +424,8 @@ impl Value {
         })
     }
 
-    pub fn sub(self, mut other: Self, span: Span) -> SassResult<Self> {
-        if let Self::Paren(..) = other {
+    pub(crate) fn sub(self, mut other: Self, span: Span) -> SassResult<Self> {
+        if let Self::Internal(Internal(InternalKind::Paren(..))) = other {
             other = other.eval(span)?.node
         }
         let precedence = Op::Mul.precedence();
@@ -404,9 +436,10 @@ impl Value {
             Self::Dimension(num, unit) => match other {
                 Self::Dimension(num2, unit2) => {
                     if !unit.comparable(&unit2) {
-                        return Err(
-                            (format!("Incompatible units {} and {}.", unit2, unit), span).into(),
-                        );
+                        return Err(SassError::runtime(
+                            format!("Incompatible units {} and {}.", unit2, unit),
+                            span,
+                        ));
                     }
                     if unit == unit2 {
                         Value::Dimension(num - num2, unit)
@@ -433,11 +466,10 @@ impl Value {
                     QuoteKind::None,
                 ),
                 Self::Map(..) | Self::Function(..) => {
-                    return Err((
+                    return Err(SassError::runtime(
                         format!("{} isn't a valid CSS value.", other.inspect(span)?),
                         span,
-                    )
-                        .into())
+                    ))
                 }
                 _ => todo!(),
             },
@@ -447,29 +479,28 @@ impl Value {
                 }
                 Self::Null => Value::String(format!("{}-", c), QuoteKind::None),
                 Self::Dimension(..) | Self::Color(..) => {
-                    return Err((
+                    return Err(SassError::runtime(
                         format!("Undefined operation \"{} - {}\".", c, other.inspect(span)?),
                         span,
-                    )
-                        .into())
+                    ))
                 }
                 _ => Value::String(
                     format!("{}-{}", c, other.to_css_string(span)?),
                     QuoteKind::None,
                 ),
             },
-            Self::BinaryOp(left, op, right) => {
+            Self::Internal(Internal(InternalKind::BinaryOp(left, op, right))) => {
                 if op.precedence() >= precedence {
-                    Self::BinaryOp(left, op, right)
+                    Self::binary_op(left, op, right)
                         .eval(span)?
                         .node
                         .sub(other, span)?
                 } else {
-                    Self::BinaryOp(
+                    Self::binary_op(
                         left,
                         op,
                         Box::new(
-                            Self::BinaryOp(right, Op::Minus, Box::new(other))
+                            Self::binary_op(right, Op::Minus, Box::new(other))
                                 .eval(span)?
                                 .node,
                         ),
@@ -478,7 +509,9 @@ impl Value {
                     .node
                 }
             }
-            Self::Paren(..) => self.eval(span)?.node.sub(other, span)?,
+            Self::Internal(Internal(InternalKind::Paren(..))) => {
+                self.eval(span)?.node.sub(other, span)?
+            }
             Self::String(..) => Self::String(
                 format!(
                     "{}-{}",
@@ -521,8 +554,8 @@ impl Value {
         })
     }
 
-    pub fn mul(self, mut other: Self, span: Span) -> SassResult<Self> {
-        if let Self::Paren(..) = other {
+    pub(crate) fn mul(self, mut other: Self, span: Span) -> SassResult<Self> {
+        if let Self::Internal(Internal(InternalKind::Paren(..))) = other {
             other = other.eval(span)?.node
         }
         let precedence = Op::Mul.precedence();
@@ -546,7 +579,7 @@ impl Value {
                     }
                 }
                 _ => {
-                    return Err((
+                    return Err(SassError::runtime(
                         format!(
                             "Undefined operation \"{}{} * {}\".",
                             num,
@@ -554,22 +587,21 @@ impl Value {
                             other.inspect(span)?
                         ),
                         span,
-                    )
-                        .into())
+                    ))
                 }
             },
-            Self::BinaryOp(left, op, right) => {
+            Self::Internal(Internal(InternalKind::BinaryOp(left, op, right))) => {
                 if op.precedence() >= precedence {
-                    Self::BinaryOp(left, op, right)
+                    Self::binary_op(left, op, right)
                         .eval(span)?
                         .node
                         .mul(other, span)?
                 } else {
-                    Self::BinaryOp(
+                    Self::binary_op(
                         left,
                         op,
                         Box::new(
-                            Self::BinaryOp(right, Op::Mul, Box::new(other))
+                            Self::binary_op(right, Op::Mul, Box::new(other))
                                 .eval(span)?
                                 .node,
                         ),
@@ -578,31 +610,33 @@ impl Value {
                     .node
                 }
             }
-            Self::UnaryOp(..) | Self::Paren(..) => self.eval(span)?.node.mul(other, span)?,
+            Self::Internal(Internal(InternalKind::UnaryOp(..) | InternalKind::Paren(..))) => {
+                self.eval(span)?.node.mul(other, span)?
+            }
             _ => {
-                return Err((
+                return Err(SassError::runtime(
                     format!(
                         "Undefined operation \"{} * {}\".",
                         self.inspect(span)?,
                         other.inspect(span)?
                     ),
                     span,
-                )
-                    .into())
+                ))
             }
         })
     }
 
-    pub fn div(self, other: Self, span: Span) -> SassResult<Self> {
+    pub(crate) fn div(self, other: Self, span: Span) -> SassResult<Self> {
         let precedence = Op::Div.precedence();
         Ok(match self {
             Self::Null => todo!(),
             Self::Dimension(num, unit) => match other {
                 Self::Dimension(num2, unit2) => {
                     if !unit.comparable(&unit2) {
-                        return Err(
-                            (format!("Incompatible units {} and {}.", unit2, unit), span).into(),
-                        );
+                        return Err(SassError::runtime(
+                            format!("Incompatible units {} and {}.", unit2, unit),
+                            span,
+                        ));
                     }
                     if unit == unit2 {
                         Value::Dimension(num / num2, Unit::None)
@@ -623,9 +657,11 @@ impl Value {
                 Self::String(s, q) => {
                     Value::String(format!("{}{}/{}{}{}", num, unit, q, s, q), QuoteKind::None)
                 }
-                Self::BinaryOp(..) | Self::Paren(..) | Self::UnaryOp(..) => {
-                    Self::Dimension(num, unit).div(other.eval(span)?.node, span)?
-                }
+                Self::Internal(Internal(
+                    InternalKind::BinaryOp(..)
+                    | InternalKind::Paren(..)
+                    | InternalKind::UnaryOp(..),
+                )) => Self::Dimension(num, unit).div(other.eval(span)?.node, span)?,
                 Self::List(..) | Self::True | Self::False | Self::Important | Self::Color(..) => {
                     Value::String(
                         format!("{}{}/{}", num, unit, other.to_css_string(span)?),
@@ -634,13 +670,12 @@ impl Value {
                 }
                 Self::Null => Value::String(format!("{}{}/", num, unit), QuoteKind::None),
                 Self::Map(..) | Self::Function(..) => {
-                    return Err((
+                    return Err(SassError::runtime(
                         format!("{} isn't a valid CSS value.", other.inspect(span)?),
                         span,
-                    )
-                        .into())
+                    ))
                 }
-                Self::ArgList(..) => todo!(),
+                Self::Internal(Internal(InternalKind::ArgList(..))) => todo!(),
             },
             Self::Color(c) => match other {
                 Self::String(s, q) => {
@@ -648,29 +683,28 @@ impl Value {
                 }
                 Self::Null => Value::String(format!("{}/", c), QuoteKind::None),
                 Self::Dimension(..) | Self::Color(..) => {
-                    return Err((
+                    return Err(SassError::runtime(
                         format!("Undefined operation \"{} / {}\".", c, other.inspect(span)?),
                         span,
-                    )
-                        .into())
+                    ))
                 }
                 _ => Value::String(
                     format!("{}/{}", c, other.to_css_string(span)?),
                     QuoteKind::None,
                 ),
             },
-            Self::BinaryOp(left, op, right) => {
+            Self::Internal(Internal(InternalKind::BinaryOp(left, op, right))) => {
                 if op.precedence() >= precedence {
-                    Self::BinaryOp(left, op, right)
+                    Self::binary_op(left, op, right)
                         .eval(span)?
                         .node
                         .div(other, span)?
                 } else {
-                    Self::BinaryOp(
+                    Self::binary_op(
                         left,
                         op,
                         Box::new(
-                            Self::BinaryOp(right, Op::Div, Box::new(other))
+                            Self::binary_op(right, Op::Div, Box::new(other))
                                 .eval(span)?
                                 .node,
                         ),
@@ -679,7 +713,9 @@ impl Value {
                     .node
                 }
             }
-            Self::Paren(..) => self.eval(span)?.node.div(other, span)?,
+            Self::Internal(Internal(InternalKind::Paren(..))) => {
+                self.eval(span)?.node.div(other, span)?
+            }
             Self::String(s1, q1) => match other {
                 Self::String(s2, q2) => Value::String(
                     format!("{}{}{}/{}{}{}", q1, s1, q1, q2, s2, q2),
@@ -716,12 +752,15 @@ impl Value {
         })
     }
 
-    pub fn rem(self, other: Self, span: Span) -> SassResult<Self> {
+    pub(crate) fn rem(self, other: Self, span: Span) -> SassResult<Self> {
         Ok(match self {
             Value::Dimension(n, u) => match other {
                 Value::Dimension(n2, u2) => {
                     if !u.comparable(&u2) {
-                        return Err((format!("Incompatible units {} and {}.", u2, u), span).into());
+                        return Err(SassError::runtime(
+                            format!("Incompatible units {} and {}.", u2, u),
+                            span,
+                        ));
                     }
                     if u == u2 {
                         Value::Dimension(n % n2, u)
@@ -734,32 +773,30 @@ impl Value {
                     }
                 }
                 _ => {
-                    return Err((
+                    return Err(SassError::runtime(
                         format!(
                             "Undefined operation \"{} % {}\".",
                             Value::Dimension(n, u).inspect(span)?,
                             other.inspect(span)?
                         ),
                         span,
-                    )
-                        .into())
+                    ))
                 }
             },
             _ => {
-                return Err((
+                return Err(SassError::runtime(
                     format!(
                         "Undefined operation \"{} % {}\".",
                         self.inspect(span)?,
                         other.inspect(span)?
                     ),
                     span,
-                )
-                    .into())
+                ))
             }
         })
     }
 
-    pub fn neg(self, span: Span) -> SassResult<Self> {
+    pub(crate) fn neg(self, span: Span) -> SassResult<Self> {
         Ok(match self.eval(span)?.node {
             Value::Dimension(n, u) => Value::Dimension(-n, u),
             v => Value::String(format!("-{}", v.to_css_string(span)?), QuoteKind::None),
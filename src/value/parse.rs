@@ -12,16 +12,17 @@ use peekmore::{PeekMore, PeekMoreIterator};
 use super::css_function::{eat_calc_args, eat_progid, try_eat_url};
 
 use crate::args::eat_call_args;
-use crate::builtin::GLOBAL_FUNCTIONS;
+use crate::builtin::global_fn;
 use crate::color::{Color, NAMED_COLORS};
 use crate::common::{Brackets, Identifier, ListSeparator, Op, QuoteKind};
 use crate::error::SassResult;
+use crate::modules::{get_global_module_fn, get_module};
 use crate::scope::Scope;
 use crate::selector::Selector;
 use crate::unit::Unit;
 use crate::utils::{
     devour_whitespace, eat_comment, eat_ident, eat_ident_no_interpolation, eat_number,
-    parse_quoted_string, read_until_char, read_until_closing_paren,
+    is_name_start, parse_quoted_string, read_until_char, read_until_closing_paren,
     read_until_closing_square_brace, read_until_newline, IsWhitespace,
 };
 use crate::value::Value;
@@ -198,7 +199,7 @@ fn parse_paren(
 
     if paren_toks.peek().is_none() {
         return Ok(Spanned {
-            node: Value::Paren(Box::new(key.node)),
+            node: Value::paren(Box::new(key.node)),
             span: key.span,
         });
     }
@@ -263,7 +264,7 @@ fn eat_op<I: Iterator<Item = Token>>(
             devour_whitespace(iter);
             let right = single_value(iter, scope, super_selector, op.span)?;
             space_separated.push(Spanned {
-                node: Value::UnaryOp(op.node, Box::new(right.node)),
+                node: Value::unary_op(op.node, Box::new(right.node)),
                 span: right.span,
             });
         }
@@ -272,7 +273,7 @@ fn eat_op<I: Iterator<Item = Token>>(
             let right = single_value(iter, scope, super_selector, op.span)?;
             if let Some(left) = space_separated.pop() {
                 space_separated.push(Spanned {
-                    node: Value::BinaryOp(Box::new(left.node), op.node, Box::new(right.node)),
+                    node: Value::binary_op(Box::new(left.node), op.node, Box::new(right.node)),
                     span: left.span.merge(right.span),
                 });
             } else {
@@ -291,14 +292,14 @@ fn eat_op<I: Iterator<Item = Token>>(
                 devour_whitespace(iter);
                 let right = single_value(iter, scope, super_selector, op.span)?;
                 space_separated.push(Spanned {
-                    node: Value::BinaryOp(Box::new(left.node), op.node, Box::new(right.node)),
+                    node: Value::binary_op(Box::new(left.node), op.node, Box::new(right.node)),
                     span: left.span.merge(right.span),
                 });
             } else {
                 devour_whitespace(iter);
                 let right = single_value(iter, scope, super_selector, op.span)?;
                 space_separated.push(Spanned {
-                    node: Value::UnaryOp(op.node, Box::new(right.node)),
+                    node: Value::unary_op(op.node, Box::new(right.node)),
                     span: right.span,
                 });
             }
@@ -308,15 +309,15 @@ fn eat_op<I: Iterator<Item = Token>>(
                 let right = single_value(iter, scope, super_selector, op.span)?;
                 if let Some(left) = space_separated.pop() {
                     space_separated.push(Spanned {
-                        node: Value::BinaryOp(Box::new(left.node), op.node, Box::new(right.node)),
+                        node: Value::binary_op(Box::new(left.node), op.node, Box::new(right.node)),
                         span: left.span.merge(right.span),
                     });
                 } else {
-                    space_separated.push(right.map_node(|n| Value::UnaryOp(op.node, Box::new(n))));
+                    space_separated.push(right.map_node(|n| Value::unary_op(op.node, Box::new(n))));
                 }
             } else {
                 let right = single_value(iter, scope, super_selector, op.span)?;
-                space_separated.push(right.map_node(|n| Value::UnaryOp(op.node, Box::new(n))));
+                space_separated.push(right.map_node(|n| Value::unary_op(op.node, Box::new(n))));
             }
         }
         Op::And | Op::Or => {
@@ -328,7 +329,7 @@ fn eat_op<I: Iterator<Item = Token>>(
                 devour_whitespace(iter);
                 let right = single_value(iter, scope, super_selector, left.span)?;
                 space_separated.push(
-                    Value::BinaryOp(Box::new(left.node), op.node, Box::new(right.node))
+                    Value::binary_op(Box::new(left.node), op.node, Box::new(right.node))
                         .span(left.span.merge(right.span)),
                 );
             } else {
@@ -340,7 +341,7 @@ fn eat_op<I: Iterator<Item = Token>>(
                 devour_whitespace(iter);
                 let right = single_value(iter, scope, super_selector, left.span)?;
                 space_separated.push(
-                    Value::BinaryOp(Box::new(left.node), op.node, Box::new(right.node))
+                    Value::binary_op(Box::new(left.node), op.node, Box::new(right.node))
                         .span(left.span.merge(right.span)),
                 );
             } else {
@@ -373,7 +374,7 @@ fn single_value<I: Iterator<Item = Token>>(
                 devour_whitespace(iter);
                 let val = single_value(iter, scope, super_selector, span)?;
                 Spanned {
-                    node: Value::UnaryOp(Op::Not, Box::new(val.node)),
+                    node: Value::unary_op(Op::Not, Box::new(val.node)),
                     span: next.span.merge(val.span),
                 }
             }
@@ -424,7 +425,7 @@ fn single_value<I: Iterator<Item = Token>>(
                 super_selector,
             )?;
             Spanned {
-                node: Value::Paren(Box::new(val.node)),
+                node: Value::paren(Box::new(val.node)),
                 span: val.span,
             }
         }
@@ -432,7 +433,7 @@ fn single_value<I: Iterator<Item = Token>>(
 }
 
 impl Value {
-    pub fn from_tokens<I: Iterator<Item = Token>>(
+    pub(crate) fn from_tokens<I: Iterator<Item = Token>>(
         toks: &mut PeekMoreIterator<I>,
         scope: &Scope,
         super_selector: &Selector,
@@ -566,7 +567,7 @@ impl Value {
         })
     }
 
-    pub fn from_vec(
+    pub(crate) fn from_vec(
         toks: Vec<Token>,
         scope: &Scope,
         super_selector: &Selector,
@@ -591,6 +592,18 @@ impl Value {
     ) -> SassResult<Spanned<IntermediateValue>> {
         let Spanned { node: mut s, span } = eat_ident(toks, scope, super_selector, span_before)?;
 
+        if let Some(Token { kind: '.', .. }) = toks.peek() {
+            let is_member = match toks.peek_forward(1) {
+                Some(Token { kind, .. }) => *kind == '$' || is_name_start(*kind),
+                None => false,
+            };
+            toks.reset_view();
+            if is_member {
+                toks.next();
+                return Self::module_member(toks, scope, super_selector, Spanned { node: s, span });
+            }
+        }
+
         let lower = s.to_ascii_lowercase();
 
         if lower == "progid" && toks.peek().is_some() && toks.peek().unwrap().kind == ':' {
@@ -613,44 +626,62 @@ impl Value {
                 span,
             }) {
                 Ok(f) => f,
-                Err(_) => match GLOBAL_FUNCTIONS.get(as_ident.into_inner().as_str()) {
-                    Some(f) => {
-                        return Ok(IntermediateValue::Value(f.0(
+                Err(_) => {
+                    if let Some(f) = get_global_module_fn(&as_ident) {
+                        return Ok(IntermediateValue::Value(f.call(
                             eat_call_args(toks, pos)?,
                             scope,
                             super_selector,
+                            Spanned {
+                                node: &as_ident,
+                                span,
+                            },
                         )?)
-                        .span(span))
+                        .span(span));
                     }
-                    None => {
-                        match lower.as_str() {
-                            "calc" | "element" | "expression" => {
-                                s = lower;
-                                eat_calc_args(toks, scope, super_selector, &mut s)?;
-                            }
-                            // "min" => {}
-                            // "max" => {}
-                            "url" => match try_eat_url(toks, scope, super_selector)? {
-                                Some(val) => s = val,
-                                None => s.push_str(
+                    match global_fn(&as_ident) {
+                        Some(f) => {
+                            return Ok(IntermediateValue::Value(f.call(
+                                eat_call_args(toks, pos)?,
+                                scope,
+                                super_selector,
+                            )?)
+                            .span(span))
+                        }
+                        None => {
+                            match lower.as_str() {
+                                "calc" | "element" | "expression" => {
+                                    s = lower;
+                                    eat_calc_args(toks, scope, super_selector, &mut s)?;
+                                }
+                                // "min" => {}
+                                // "max" => {}
+                                "url" => match try_eat_url(toks, scope, super_selector)? {
+                                    Some(val) => s = val,
+                                    None => s.push_str(
+                                        &eat_call_args(toks, pos)?
+                                            .to_css_string(scope, super_selector)?,
+                                    ),
+                                },
+                                _ => s.push_str(
                                     &eat_call_args(toks, pos)?
                                         .to_css_string(scope, super_selector)?,
                                 ),
-                            },
-                            _ => s.push_str(
-                                &eat_call_args(toks, pos)?.to_css_string(scope, super_selector)?,
-                            ),
+                            }
+                            return Ok(IntermediateValue::Value(Value::String(s, QuoteKind::None))
+                                .span(span));
                         }
-                        return Ok(
-                            IntermediateValue::Value(Value::String(s, QuoteKind::None)).span(span)
-                        );
                     }
-                },
+                }
             };
             return Ok(IntermediateValue::Value(func.eval(
                 eat_call_args(toks, pos)?,
                 scope,
                 super_selector,
+                Spanned {
+                    node: &as_ident,
+                    span,
+                },
             )?)
             .span(span));
         }
@@ -674,6 +705,50 @@ impl Value {
         .span(span))
     }
 
+    /// Parse a member of a module, e.g. `math.$pi` or `math.div(1, 2)`
+    ///
+    /// The namespace and the following `.` have already been consumed
+    fn module_member<I: Iterator<Item = Token>>(
+        toks: &mut PeekMoreIterator<I>,
+        scope: &Scope,
+        super_selector: &Selector,
+        namespace: Spanned<String>,
+    ) -> SassResult<Spanned<IntermediateValue>> {
+        let module = get_module(Spanned {
+            node: &namespace.node,
+            span: namespace.span,
+        })?;
+
+        if let Some(Token { kind: '$', pos }) = toks.peek() {
+            let pos = *pos;
+            toks.next();
+            let name = eat_ident_no_interpolation(toks, false, pos)?;
+            let span = namespace.span.merge(name.span);
+            let value = module.get_var(name.map_node(Into::into))?;
+            return Ok(IntermediateValue::Value(value.node).span(span));
+        }
+
+        let name = eat_ident_no_interpolation(toks, false, namespace.span)?;
+        let span = namespace.span.merge(name.span);
+        let pos = match toks.next() {
+            Some(Token { kind: '(', pos }) => pos,
+            Some(Token { pos, .. }) => return Err(("expected \"(\".", pos).into()),
+            None => return Err(("expected \"(\".", span).into()),
+        };
+        let name = name.map_node(Identifier::from);
+        let func = module.get_fn(name.clone())?;
+        Ok(IntermediateValue::Value(func.call(
+            eat_call_args(toks, pos)?,
+            scope,
+            super_selector,
+            Spanned {
+                node: &name.node,
+                span,
+            },
+        )?)
+        .span(span))
+    }
+
     fn parse_intermediate_value<I: Iterator<Item = Token>>(
         toks: &mut PeekMoreIterator<I>,
         scope: &Scope,
@@ -10,12 +10,16 @@
 //! in the global scope.
 
 use std::fmt;
+use std::path::PathBuf;
+
+use codemap::Spanned;
 
 use crate::args::CallArgs;
 use crate::atrule::Function;
 use crate::builtin::Builtin;
 use crate::common::Identifier;
 use crate::error::SassResult;
+use crate::modules::loaded_module;
 use crate::scope::Scope;
 use crate::selector::Selector;
 use crate::value::Value;
@@ -26,19 +30,37 @@ use crate::value::Value;
 ///
 /// The function name is stored in addition to the body
 /// for use in the builtin function `inspect()`
+///
+/// A user-defined function taken from a module also keeps the canonical path
+/// of that module, so that it is called with the module's global scope. The
+/// module itself is looked up again when the function is called, since it
+/// can't be shared across threads the way values are
+///
+/// Outside of the crate, a function can only be passed along as it is
+#[derive(Clone)]
+pub struct SassFunction(Repr);
+
 #[derive(Clone)]
-pub(crate) enum SassFunction {
+enum Repr {
     Builtin(Builtin, Identifier),
-    UserDefined(Box<Function>, Identifier),
+    UserDefined(Box<Function>, Identifier, Option<PathBuf>),
 }
 
 impl SassFunction {
+    pub(crate) fn builtin(f: Builtin, name: Identifier) -> Self {
+        SassFunction(Repr::Builtin(f, name))
+    }
+
+    pub(crate) fn user_defined(f: Function, name: Identifier, module: Option<PathBuf>) -> Self {
+        SassFunction(Repr::UserDefined(Box::new(f), name, module))
+    }
+
     /// Get the name of the function referenced
     ///
     /// Used mainly in debugging and `inspect()`
-    pub fn name(&self) -> &Identifier {
-        match self {
-            Self::Builtin(_, name) | Self::UserDefined(_, name) => name,
+    pub(crate) fn name(&self) -> &Identifier {
+        match &self.0 {
+            Repr::Builtin(_, name) | Repr::UserDefined(_, name, ..) => name,
         }
     }
 
@@ -46,21 +68,28 @@ impl SassFunction {
     ///
     /// Used only in `std::fmt::Debug` for `SassFunction`
     fn kind(&self) -> &'static str {
-        match &self {
-            Self::Builtin(..) => "Builtin",
-            Self::UserDefined(..) => "UserDefined",
+        match &self.0 {
+            Repr::Builtin(..) => "Builtin",
+            Repr::UserDefined(..) => "UserDefined",
         }
     }
 
-    pub fn call(
+    pub(crate) fn call(
         self,
         args: CallArgs,
         scope: &Scope,
         super_selector: &Selector,
     ) -> SassResult<Value> {
-        match self {
-            Self::Builtin(f, ..) => f.0(args, scope, super_selector),
-            Self::UserDefined(f, ..) => f.eval(args, scope, super_selector),
+        match self.0 {
+            Repr::Builtin(f, ..) => f.call(args, scope, super_selector),
+            Repr::UserDefined(f, name, module) => {
+                let span = args.span();
+                let name = Spanned { node: &name, span };
+                match module.as_deref().and_then(loaded_module) {
+                    Some(module) => f.eval_in_module(args, scope, super_selector, &module, name),
+                    None => f.eval(args, scope, super_selector, name),
+                }
+            }
         }
     }
 }
@@ -76,14 +105,14 @@ impl fmt::Debug for SassFunction {
 
 impl PartialEq for SassFunction {
     fn eq(&self, other: &Self) -> bool {
-        match self {
-            Self::UserDefined(f, ..) => match other {
-                Self::UserDefined(f2, ..) => f == f2,
-                Self::Builtin(..) => false,
+        match &self.0 {
+            Repr::UserDefined(f, ..) => match &other.0 {
+                Repr::UserDefined(f2, ..) => f == f2,
+                Repr::Builtin(..) => false,
             },
-            Self::Builtin(f, ..) => match other {
-                Self::UserDefined(..) => false,
-                Self::Builtin(f2, ..) => f == f2,
+            Repr::Builtin(f, ..) => match &other.0 {
+                Repr::UserDefined(..) => false,
+                Repr::Builtin(f2, ..) => f == f2,
             },
         }
     }
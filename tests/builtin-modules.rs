@@ -0,0 +1,131 @@
+#![cfg(test)]
+
+#[macro_use]
+mod macros;
+
+test!(
+    math_div,
+    "@use \"sass:math\";\na {\n  color: math.div(10px, 4);\n}\n",
+    "a {\n  color: 2.5px;\n}\n"
+);
+test!(
+    math_div_same_units,
+    "@use \"sass:math\";\na {\n  color: math.div(10px, 5px);\n}\n",
+    "a {\n  color: 2;\n}\n"
+);
+test!(
+    math_pi,
+    "@use \"sass:math\";\na {\n  color: math.$pi;\n}\n",
+    "a {\n  color: 3.1415926536;\n}\n"
+);
+test!(
+    math_e,
+    "@use \"sass:math\";\na {\n  color: math.$e;\n}\n",
+    "a {\n  color: 2.7182818285;\n}\n"
+);
+test!(
+    math_renamed_functions,
+    "@use \"sass:math\";\na {\n  color: math.is-unitless(1px) math.compatible(1px, 1in) math.unit(1px);\n}\n",
+    "a {\n  color: false true \"px\";\n}\n"
+);
+test!(
+    math_shared_functions,
+    "@use \"sass:math\";\na {\n  color: math.round(1.5) math.abs(-2) math.percentage(0.5);\n}\n",
+    "a {\n  color: 2 2 50%;\n}\n"
+);
+test!(
+    math_explicit_namespace,
+    "@use \"sass:math\" as m;\na {\n  color: m.div(1, 4);\n}\n",
+    "a {\n  color: 0.25;\n}\n"
+);
+test!(
+    math_global_namespace,
+    "@use \"sass:math\" as *;\na {\n  color: div(1, 4) $pi;\n}\n",
+    "a {\n  color: 0.25 3.1415926536;\n}\n"
+);
+test!(
+    list_slash,
+    "@use \"sass:list\";\na {\n  color: list.slash(1px, 2px, 3px);\n}\n",
+    "a {\n  color: 1px / 2px / 3px;\n}\n"
+);
+test!(
+    list_slash_separator,
+    "@use \"sass:list\";\na {\n  color: list.separator(list.slash(1px, 2px));\n}\n",
+    "a {\n  color: slash;\n}\n"
+);
+test!(
+    list_shared_functions,
+    "@use \"sass:list\";\na {\n  color: list.length(1 2 3) list.nth(a b c, 2);\n}\n",
+    "a {\n  color: 3 b;\n}\n"
+);
+test!(
+    map_get,
+    "@use \"sass:map\";\n$a: (b: c);\na {\n  color: map.get($a, b);\n}\n",
+    "a {\n  color: c;\n}\n"
+);
+test!(
+    map_deep_merge,
+    "@use \"sass:map\";\n$a1: (b: 1, c: 2);\n$a2: (c: 3);\n$m1: (a: $a1);\n$m2: (a: $a2);\n$merged: map.get(map.deep-merge($m1, $m2), a);\na {\n  color: map.get($merged, b) map.get($merged, c);\n}\n",
+    "a {\n  color: 1 3;\n}\n"
+);
+test!(
+    map_deep_merge_non_map_value,
+    "@use \"sass:map\";\n$a1: (b: 1);\n$m1: (a: $a1);\n$m2: (a: 2);\na {\n  color: map.get(map.deep-merge($m1, $m2), a);\n}\n",
+    "a {\n  color: 2;\n}\n"
+);
+test!(
+    color_channel_rgb,
+    "@use \"sass:color\";\na {\n  color: color.channel(#ff8000, \"green\");\n}\n",
+    "a {\n  color: 128;\n}\n"
+);
+test!(
+    color_channel_hsl,
+    "@use \"sass:color\";\na {\n  color: color.channel(#ff0000, \"lightness\", $space: hsl);\n}\n",
+    "a {\n  color: 50%;\n}\n"
+);
+test!(
+    color_renamed_functions,
+    "@use \"sass:color\";\na {\n  color: color.adjust(#000, $red: 255);\n}\n",
+    "a {\n  color: red;\n}\n"
+);
+test!(
+    string_renamed_functions,
+    "@use \"sass:string\";\na {\n  color: string.length(\"abc\") string.index(\"abc\", \"b\");\n}\n",
+    "a {\n  color: 3 2;\n}\n"
+);
+test!(
+    meta_function_exists_in_module,
+    "@use \"sass:meta\";\n@use \"sass:math\";\na {\n  color: meta.function-exists(\"div\", \"math\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    meta_get_function_in_module,
+    "@use \"sass:meta\";\n@use \"sass:math\";\na {\n  color: meta.call(meta.get-function(\"div\", $module: \"math\"), 1, 2);\n}\n",
+    "a {\n  color: 0.5;\n}\n"
+);
+error!(
+    global_name_not_in_module,
+    "@use \"sass:map\";\na {\n  color: map.map-get((a: b), a);\n}\n", "Error: Undefined function."
+);
+test!(
+    module_only_function_not_global,
+    "a {\n  color: div(1, 2);\n}\n"
+);
+error!(
+    unknown_builtin_module,
+    "@use \"sass:foo\";\n", "Error: Can't find stylesheet to import."
+);
+error!(
+    builtin_module_configured,
+    "@use \"sass:math\" with ($a: 1);\n", "Error: Built-in modules can't be configured."
+);
+error!(
+    list_slash_too_few_elements,
+    "@use \"sass:list\";\na {\n  color: list.slash(1px);\n}\n",
+    "Error: At least two elements are required."
+);
+error!(
+    color_channel_unknown,
+    "@use \"sass:color\";\na {\n  color: color.channel(red, \"foo\");\n}\n",
+    "Error: $channel: Color red has no channel named foo."
+);
@@ -0,0 +1,156 @@
+#![cfg(test)]
+
+#[macro_use]
+mod macros;
+
+test_compressed!(single_style, "a {\n  color: red;\n}\n", "a{color:red}");
+test_compressed!(
+    trailing_semicolon_dropped,
+    "a {\n  color: red;\n  width: 1px;\n}\n",
+    "a{color:red;width:1px}"
+);
+test_compressed!(
+    no_newline_between_blocks,
+    "a {\n  color: red;\n}\n\nb {\n  color: blue;\n}\n",
+    "a{color:red}b{color:#00f}"
+);
+test_compressed!(
+    nested_selectors,
+    "a {\n  b {\n    color: red;\n  }\n}\n",
+    "a b{color:red}"
+);
+test_compressed!(
+    selector_combinators,
+    "a > b + c ~ d, e {\n  color: red;\n}\n",
+    "a>b+c~d,e{color:red}"
+);
+test_compressed!(
+    selector_attribute_with_spaces_in_quotes,
+    "a[title=\"a > b\"] {\n  color: red;\n}\n",
+    "a[title=\"a > b\"]{color:red}"
+);
+test_compressed!(
+    empty_rule_omitted,
+    "a {}\nb {\n  color: red;\n}\n",
+    "b{color:red}"
+);
+test_compressed!(
+    long_hex_shortened,
+    "a {\n  color: #ffffff;\n}\n",
+    "a{color:#fff}"
+);
+test_compressed!(
+    hex_not_shortened,
+    "a {\n  color: #123457;\n}\n",
+    "a{color:#123457}"
+);
+test_compressed!(
+    hex_to_shorter_name,
+    "a {\n  color: #ff0000;\n}\n",
+    "a{color:red}"
+);
+test_compressed!(
+    name_to_shorter_hex,
+    "a {\n  color: white;\n}\n",
+    "a{color:#fff}"
+);
+test_compressed!(
+    transparent_color,
+    "a {\n  color: rgba(255, 0, 0, 0.5);\n}\n",
+    "a{color:rgba(255,0,0,.5)}"
+);
+test_compressed!(
+    leading_zero_dropped,
+    "a {\n  width: 0.5px;\n}\n",
+    "a{width:.5px}"
+);
+test_compressed!(
+    negative_leading_zero_dropped,
+    "a {\n  width: -0.5px;\n}\n",
+    "a{width:-.5px}"
+);
+test_compressed!(
+    integer_unchanged,
+    "a {\n  width: 10px;\n}\n",
+    "a{width:10px}"
+);
+test_compressed!(
+    evaluated_expression,
+    "a {\n  width: 1px / 2;\n}\n",
+    "a{width:.5px}"
+);
+test_compressed!(
+    comma_list,
+    "a {\n  font-family: a, b, c;\n}\n",
+    "a{font-family:a,b,c}"
+);
+test_compressed!(
+    space_list,
+    "a {\n  margin: 0.5px 1px;\n}\n",
+    "a{margin:.5px 1px}"
+);
+test_compressed!(
+    comments_removed,
+    "/* foo */\na {\n  /* bar */\n  color: red;\n}\n",
+    "a{color:red}"
+);
+test_compressed!(
+    loud_comments_preserved,
+    "/*! foo */\na {\n  color: red;\n  /*! bar */\n  width: 1px;\n}\n",
+    "/*! foo */a{color:red;/*! bar */width:1px}"
+);
+test_compressed!(
+    media_query,
+    "@media screen and (min-width: 100px) {\n  a {\n    color: red;\n  }\n}\n",
+    "@media screen and (min-width:100px){a{color:red}}"
+);
+test_compressed!(
+    media_nested_in_rule,
+    "a {\n  @media print {\n    color: red;\n  }\n}\n",
+    "@media print{a{color:red}}"
+);
+test_compressed!(
+    keyframes,
+    "@keyframes foo {\n  from, 50% {\n    width: 0.5px;\n  }\n  to {\n    width: 1px;\n  }\n}\n",
+    "@keyframes foo{from,50%{width:.5px}to{width:1px}}"
+);
+test_compressed!(
+    unknown_at_rule,
+    "@foo bar {\n  a {\n    color: red;\n  }\n}\n@baz;\n",
+    "@foo bar{a{color:red}}@baz;"
+);
+test_compressed!(
+    css_import,
+    "@import \"foo.css\";\na {\n  color: red;\n}\n",
+    "@import \"foo.css\";a{color:red}"
+);
+test_compressed!(
+    non_ascii_has_byte_order_mark,
+    "a {\n  content: \"é\";\n}\n",
+    "\u{FEFF}a{content:\"é\"}"
+);
+
+#[test]
+fn expanded_is_default() {
+    assert_eq!(grass::OutputStyle::default(), grass::OutputStyle::Expanded);
+}
+test_compressed!(
+    transparent_keyword,
+    "a {\n  color: transparent;\n}\n",
+    "a{color:transparent}"
+);
+test_compressed!(
+    fully_transparent_black_is_keyword,
+    "a {\n  color: rgba(0, 0, 0, 0);\n}\n",
+    "a{color:transparent}"
+);
+test_compressed!(
+    fully_transparent_red_is_rgba,
+    "a {\n  color: rgba(255, 0, 0, 0);\n}\n",
+    "a{color:rgba(255,0,0,0)}"
+);
+test_compressed!(
+    control_flow_in_nested_rule,
+    "@for $i from 1 through 2 {\n  .a-#{$i} {\n    @if $i == 1 {\n      color: red;\n    } @else {\n      color: blue;\n    }\n  }\n}\n",
+    ".a-1{color:red}.a-2{color:#00f}"
+);
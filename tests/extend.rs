@@ -0,0 +1,120 @@
+#![cfg(test)]
+
+#[macro_use]
+mod macros;
+
+test!(
+    extend_class,
+    ".a {\n  color: red;\n}\n\n.b {\n  @extend .a;\n}\n",
+    ".a, .b {\n  color: red;\n}\n"
+);
+test!(
+    extend_before_target,
+    ".b {\n  @extend .a;\n}\n\n.a {\n  color: red;\n}\n",
+    ".a, .b {\n  color: red;\n}\n"
+);
+test!(
+    extend_placeholder,
+    "%a {\n  color: red;\n}\n\n.b {\n  @extend %a;\n}\n",
+    ".b {\n  color: red;\n}\n"
+);
+test!(
+    extend_placeholder_multiple_extenders,
+    "%a {\n  color: red;\n}\n\n.b {\n  @extend %a;\n}\n\n.c {\n  @extend %a;\n}\n",
+    ".b, .c {\n  color: red;\n}\n"
+);
+test!(
+    extend_with_own_styles,
+    ".a {\n  color: red;\n}\n\n.b {\n  @extend .a;\n  color: blue;\n}\n",
+    ".a, .b {\n  color: red;\n}\n\n.b {\n  color: blue;\n}\n"
+);
+test!(
+    extend_within_compound,
+    "a.a {\n  color: red;\n}\n\n.b {\n  @extend .a;\n}\n",
+    "a.a, a.b {\n  color: red;\n}\n"
+);
+test!(
+    extend_list_of_targets,
+    ".a {\n  color: red;\n}\n\n.b {\n  color: blue;\n}\n\n.c {\n  @extend .a, .b;\n}\n",
+    ".a, .c {\n  color: red;\n}\n\n.b, .c {\n  color: blue;\n}\n"
+);
+test!(
+    extend_multiple_extender_selectors,
+    ".a {\n  color: red;\n}\n\n.b, .c {\n  @extend .a;\n}\n",
+    ".a, .b, .c {\n  color: red;\n}\n"
+);
+test!(
+    extend_weaves_descendants,
+    ".a .b {\n  color: red;\n}\n\n.x .y {\n  @extend .b;\n}\n",
+    ".a .b, .a .x .y, .x .a .y {\n  color: red;\n}\n"
+);
+test!(
+    extend_child_combinator,
+    ".a > .b {\n  color: red;\n}\n\n.c {\n  @extend .b;\n}\n",
+    ".a > .b, .a > .c {\n  color: red;\n}\n"
+);
+test!(
+    extend_nested_extender,
+    ".a {\n  color: red;\n}\n\n.b {\n  .c {\n    @extend .a;\n  }\n}\n",
+    ".a, .b .c {\n  color: red;\n}\n"
+);
+test!(
+    extend_transitive,
+    ".a {\n  color: red;\n}\n\n.b {\n  @extend .a;\n}\n\n.c {\n  @extend .b;\n}\n",
+    ".a, .b, .c {\n  color: red;\n}\n"
+);
+test!(
+    extend_unification_fails,
+    "a.a {\n  color: red;\n}\n\nb {\n  @extend .a;\n}\n",
+    "a.a {\n  color: red;\n}\n"
+);
+test!(
+    extend_inside_not,
+    ":not(.a) {\n  color: red;\n}\n\n.b {\n  @extend .a;\n}\n",
+    ":not(.a):not(.b) {\n  color: red;\n}\n"
+);
+test!(
+    extend_optional_missing_target,
+    ".a {\n  @extend .b !optional;\n  color: red;\n}\n",
+    ".a {\n  color: red;\n}\n"
+);
+test!(
+    extend_interpolated_target,
+    ".a {\n  color: red;\n}\n\n.b {\n  @extend #{\".a\"};\n}\n",
+    ".a, .b {\n  color: red;\n}\n"
+);
+test!(
+    extend_within_same_media,
+    "@media screen {\n  .a {\n    color: red;\n  }\n  .b {\n    @extend .a;\n  }\n}\n",
+    "@media screen {\n  .a, .b {\n    color: red;\n  }\n}\n"
+);
+test!(
+    extend_from_toplevel_into_media,
+    "@media screen {\n  .a {\n    color: red;\n  }\n}\n\n.b {\n  @extend .a;\n}\n",
+    "@media screen {\n  .a, .b {\n    color: red;\n  }\n}\n"
+);
+error!(
+    extend_missing_target,
+    ".a {\n  @extend .b;\n}\n", "Error: The target selector was not found."
+);
+error!(
+    extend_outside_style_rule,
+    "@extend .a;\n", "Error: @extend may only be used within style rules."
+);
+error!(
+    extend_complex_selector,
+    ".a {\n  @extend .b .c;\n}\n", "Error: complex selectors may not be extended."
+);
+error!(
+    extend_compound_selector,
+    ".a {\n  @extend .b.c;\n}\n", "Error: compound selectors may no longer be extended."
+);
+error!(
+    extend_invalid_flag,
+    ".a {\n  @extend .b !foo;\n}\n", "Error: Expected \"optional\"."
+);
+error!(
+    extend_across_media,
+    ".a {\n  color: red;\n}\n\n@media screen {\n  .b {\n    @extend .a;\n  }\n}\n",
+    "Error: You may not @extend selectors across media queries."
+);
@@ -68,3 +68,8 @@ test!(
     "@for $i from -1 to -3 {\n    a {\n        color: red;\n    }\n}\n",
     "a {\n  color: red;\n}\n\na {\n  color: red;\n}\n"
 );
+test!(
+    control_flow_in_nested_rule,
+    "@for $i from 1 through 2 {\n  .a-#{$i} {\n    @if $i == 1 {\n      color: red;\n    }\n    @each $p in top, left {\n      #{$p}: $i;\n    }\n  }\n}\n",
+    ".a-1 {\n  color: red;\n  top: 1;\n  left: 1;\n}\n\n.a-2 {\n  top: 2;\n  left: 2;\n}\n"
+);
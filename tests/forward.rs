@@ -0,0 +1,224 @@
+#![cfg(test)]
+
+use grass::StyleSheet;
+
+#[macro_use]
+mod macros;
+
+error!(
+    forward_after_style_rule,
+    "a { color: red; }\n@forward \"foo\";",
+    "Error: @forward rules must be written before any other rules."
+);
+error!(
+    forward_inside_style_rule,
+    "a { @forward \"foo\"; }", "Error: This at-rule is not allowed here."
+);
+error!(
+    forward_nonexistent_file,
+    "@forward \"forward_nonexistent_file\";", "Error: Can't find stylesheet to import."
+);
+error!(
+    forward_prefix_missing_star,
+    "@forward \"foo\" as foo-;", "Error: expected \"*\"."
+);
+
+#[test]
+fn forward_all_members() {
+    let input =
+        "@use \"forward_all_members__lib\" as lib;\na {\n color: lib.$a;\n width: lib.f();\n}";
+    tempfile!(
+        "forward_all_members__lib.scss",
+        "@forward \"forward_all_members__src\";"
+    );
+    tempfile!(
+        "forward_all_members__src.scss",
+        "$a: red;\n@function f() { @return 1px; }"
+    );
+    assert_eq!(
+        "a {\n  color: red;\n  width: 1px;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn forward_not_visible_to_forwarding_module() {
+    let input = "@use \"forward_not_visible_to_forwarding_module__lib\";";
+    tempfile!(
+        "forward_not_visible_to_forwarding_module__lib.scss",
+        "@forward \"forward_not_visible_to_forwarding_module__src\";\na {\n color: $a;\n}"
+    );
+    tempfile!(
+        "forward_not_visible_to_forwarding_module__src.scss",
+        "$a: red;"
+    );
+    match StyleSheet::new(input.to_string()) {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: Undefined variable.",
+            e.to_string()
+                .chars()
+                .take_while(|c| *c != '\n')
+                .collect::<String>()
+                .as_str()
+        ),
+    }
+}
+
+#[test]
+fn forward_show() {
+    let input = "@use \"forward_show__lib\" as lib;\na {\n color: lib.$a;\n width: lib.$b;\n}";
+    tempfile!(
+        "forward_show__lib.scss",
+        "@forward \"forward_show__src\" show $a;"
+    );
+    tempfile!("forward_show__src.scss", "$a: red;\n$b: 1px;");
+    match StyleSheet::new(input.to_string()) {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: Undefined variable.",
+            e.to_string()
+                .chars()
+                .take_while(|c| *c != '\n')
+                .collect::<String>()
+                .as_str()
+        ),
+    }
+}
+
+#[test]
+fn forward_hide() {
+    let input = "@use \"forward_hide__lib\" as lib;\na {\n color: lib.$a;\n @include lib.m;\n}";
+    tempfile!(
+        "forward_hide__lib.scss",
+        "@forward \"forward_hide__src\" hide f, $b;"
+    );
+    tempfile!(
+        "forward_hide__src.scss",
+        "$a: red;\n$b: 1px;\n@function f() { @return 1px; }\n@mixin m { width: $b; }"
+    );
+    assert_eq!(
+        "a {\n  color: red;\n  width: 1px;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn forward_hide_function() {
+    let input = "@use \"forward_hide_function__lib\" as lib;\na {\n width: lib.f();\n}";
+    tempfile!(
+        "forward_hide_function__lib.scss",
+        "@forward \"forward_hide_function__src\" hide f;"
+    );
+    tempfile!(
+        "forward_hide_function__src.scss",
+        "@function f() { @return 1px; }"
+    );
+    match StyleSheet::new(input.to_string()) {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: Undefined function.",
+            e.to_string()
+                .chars()
+                .take_while(|c| *c != '\n')
+                .collect::<String>()
+                .as_str()
+        ),
+    }
+}
+
+#[test]
+fn forward_prefix() {
+    let input = "@use \"forward_prefix__lib\" as lib;\na {\n color: lib.$btn-color;\n @include lib.btn-m;\n}";
+    tempfile!(
+        "forward_prefix__lib.scss",
+        "@forward \"forward_prefix__src\" as btn-*;"
+    );
+    tempfile!(
+        "forward_prefix__src.scss",
+        "$color: red;\n@mixin m { width: 1px; }"
+    );
+    assert_eq!(
+        "a {\n  color: red;\n  width: 1px;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn forward_prefix_with_show() {
+    let input = "@use \"forward_prefix_with_show__lib\" as lib;\na {\n color: lib.$btn-color;\n}";
+    tempfile!(
+        "forward_prefix_with_show__lib.scss",
+        "@forward \"forward_prefix_with_show__src\" as btn-* show $btn-color;"
+    );
+    tempfile!("forward_prefix_with_show__src.scss", "$color: red;");
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn forward_with_configuration() {
+    let input = "@use \"forward_with_configuration__lib\" as lib;\na {\n color: lib.$a;\n}";
+    tempfile!(
+        "forward_with_configuration__lib.scss",
+        "@forward \"forward_with_configuration__src\" with ($a: blue);"
+    );
+    tempfile!("forward_with_configuration__src.scss", "$a: red !default;");
+    assert_eq!(
+        "a {\n  color: blue;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn forward_passes_through_configuration() {
+    let input = "@use \"forward_passes_through_configuration__lib\" as lib with ($btn-a: green);\na {\n color: lib.$btn-a;\n width: lib.$btn-b;\n}";
+    tempfile!(
+        "forward_passes_through_configuration__lib.scss",
+        "@forward \"forward_passes_through_configuration__src\" as btn-* with ($a: blue !default, $b: 1px !default);"
+    );
+    tempfile!(
+        "forward_passes_through_configuration__src.scss",
+        "$a: red !default;\n$b: 2px !default;"
+    );
+    assert_eq!(
+        "a {\n  color: green;\n  width: 1px;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn forward_mixin_uses_its_own_module() {
+    let input = "@use \"forward_mixin_uses_its_own_module__lib\" as lib;\na {\n @include lib.m;\n}";
+    tempfile!(
+        "forward_mixin_uses_its_own_module__lib.scss",
+        "@forward \"forward_mixin_uses_its_own_module__src\";\n$a: blue;"
+    );
+    tempfile!(
+        "forward_mixin_uses_its_own_module__src.scss",
+        "$a: red;\n@mixin m { color: $a; }"
+    );
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn assign_forwarded_variable_with_prefix() {
+    let input = "@use \"assign_forwarded_variable__lib\" as lib;\nlib.$src-a: blue;\na {\n color: lib.$src-a;\n width: lib.src-f();\n}";
+    tempfile!(
+        "assign_forwarded_variable__lib.scss",
+        "@forward \"assign_forwarded_variable__src\" as src-*;"
+    );
+    tempfile!(
+        "assign_forwarded_variable__src.scss",
+        "$a: red;\n@function f() { @return $a; }"
+    );
+    assert_eq!(
+        "a {\n  color: blue;\n  width: blue;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
@@ -0,0 +1,142 @@
+#![cfg(test)]
+
+use grass::{Options, StyleSheet, Value};
+
+#[macro_use]
+mod macros;
+
+fn compile(input: &str, options: &Options) -> grass::SassResult<String> {
+    StyleSheet::new_with_options(input.to_string(), options)
+}
+
+#[test]
+fn value_from_rust() {
+    assert_eq!(
+        "a {\n  content: \"acme\";\n}\n",
+        compile(
+            "a {\n  content: $brand;\n}\n",
+            &Options::default().variable("brand", Value::quoted_string("acme"))
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn dollar_sign_optional() {
+    assert_eq!(
+        "a {\n  width: 1px;\n}\n",
+        compile(
+            "a {\n  width: $width;\n}\n",
+            &Options::default().define("$width", "1px")
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn not_replaced_by_default() {
+    assert_eq!(
+        "a {\n  content: \"acme\";\n}\n",
+        compile(
+            "$brand: \"default\" !default;\na {\n  content: $brand;\n}\n",
+            &Options::default().define("brand", "\"acme\"")
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn replaced_without_default() {
+    assert_eq!(
+        "a {\n  content: \"default\";\n}\n",
+        compile(
+            "$brand: \"default\";\na {\n  content: $brand;\n}\n",
+            &Options::default().define("brand", "\"acme\"")
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn map_of_tokens() {
+    assert_eq!(
+        "a {\n  color: red;\n  background: blue;\n}\n",
+        compile(
+            "a {\n  color: map-get($tokens, primary);\n  background: map-get($tokens, secondary);\n}\n",
+            &Options::default().define("tokens", "(primary: red, secondary: blue)")
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn expression_is_evaluated() {
+    assert_eq!(
+        "a {\n  width: 3px;\n}\n",
+        compile(
+            "a {\n  width: $width;\n}\n",
+            &Options::default().define("width", "1px + 2px")
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn refers_to_earlier_definition() {
+    assert_eq!(
+        "a {\n  width: 4px;\n}\n",
+        compile(
+            "a {\n  width: $double;\n}\n",
+            &Options::default()
+                .define("base", "2px")
+                .define("double", "$base * 2")
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn global_variable_exists() {
+    assert_eq!(
+        "a {\n  color: true;\n}\n",
+        compile(
+            "a {\n  color: global-variable-exists(brand);\n}\n",
+            &Options::default().define("brand", "acme")
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn visible_to_imports() {
+    tempfile!(
+        "global_variables_import.scss",
+        "$brand: \"default\" !default;\na {\n  content: $brand;\n}\n"
+    );
+    assert_eq!(
+        "a {\n  content: \"acme\";\n}\n",
+        compile(
+            "@import \"global_variables_import\";",
+            &Options::default().define("brand", "\"acme\"")
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn invalid_expression() {
+    let err = compile("a {}", &Options::default().define("brand", "1 +"))
+        .unwrap_err()
+        .to_string();
+    assert_eq!("Error: Expected expression.", err.lines().next().unwrap());
+    assert!(err.contains("$brand"), "{}", err);
+}
+
+#[test]
+fn not_defined_in_other_compilations() {
+    compile("a {}", &Options::default().define("brand", "acme")).unwrap();
+    assert_eq!(
+        "a {\n  color: false;\n}\n",
+        StyleSheet::new("a {\n  color: global-variable-exists(brand);\n}\n".to_string()).unwrap()
+    );
+}
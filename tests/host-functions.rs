@@ -0,0 +1,253 @@
+#![cfg(test)]
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use grass::{Options, QuoteKind, StyleSheet, Value};
+
+#[macro_use]
+mod macros;
+
+fn asset_url(args: &[Value]) -> Result<Value, String> {
+    let path = match &args[0] {
+        Value::String(path, _) => path,
+        _ => return Err("$path: expected a string.".to_owned()),
+    };
+    let url = if args[1] == Value::True {
+        format!("/assets/{}?v=abc123", path)
+    } else {
+        format!("/assets/{}", path)
+    };
+    Ok(Value::String(format!("url(\"{}\")", url), QuoteKind::None))
+}
+
+fn compile(input: &str) -> grass::SassResult<String> {
+    StyleSheet::new_with_options(
+        input.to_string(),
+        &Options::default().function("asset-url($path, $hash: true)", asset_url),
+    )
+}
+
+#[test]
+fn positional_argument_and_default() {
+    assert_eq!(
+        "a {\n  background: url(\"/assets/a.png?v=abc123\");\n}\n",
+        compile("a {\n  background: asset-url(\"a.png\");\n}\n").unwrap()
+    );
+}
+
+#[test]
+fn named_argument() {
+    assert_eq!(
+        "a {\n  background: url(\"/assets/a.png\");\n}\n",
+        compile("a {\n  background: asset-url($hash: false, $path: \"a.png\");\n}\n").unwrap()
+    );
+}
+
+#[test]
+fn arguments_are_evaluated() {
+    assert_eq!(
+        "a {\n  background: url(\"/assets/img/a.png\");\n}\n",
+        compile("$dir: img;\na {\n  background: asset-url(\"#{$dir}/a.png\", 1 == 2);\n}\n")
+            .unwrap()
+    );
+}
+
+#[test]
+fn underscores_and_hyphens_are_equivalent() {
+    assert_eq!(
+        "a {\n  background: url(\"/assets/a.png\");\n}\n",
+        compile("a {\n  background: asset_url(\"a.png\", false);\n}\n").unwrap()
+    );
+}
+
+#[test]
+fn callable_from_function() {
+    assert_eq!(
+        "a {\n  background: url(\"/assets/a.png\");\n}\n",
+        compile(
+            "@function icon($name) {\n  @return asset-url(\"#{$name}.png\", false);\n}\na {\n  background: icon(a);\n}\n"
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn function_exists() {
+    assert_eq!(
+        "a {\n  color: true;\n}\n",
+        compile("a {\n  color: function-exists(asset-url);\n}\n").unwrap()
+    );
+}
+
+#[test]
+fn get_function_and_call() {
+    assert_eq!(
+        "a {\n  background: url(\"/assets/a.png\");\n}\n",
+        compile("a {\n  background: call(get-function(asset-url), \"a.png\", false);\n}\n")
+            .unwrap()
+    );
+}
+
+#[test]
+fn stylesheet_function_takes_precedence() {
+    assert_eq!(
+        "a {\n  background: none;\n}\n",
+        compile("@function asset-url($path) {\n  @return none;\n}\na {\n  background: asset-url(\"a.png\");\n}\n")
+            .unwrap()
+    );
+}
+
+#[test]
+fn overrides_builtin_function() {
+    assert_eq!(
+        "a {\n  color: overridden;\n}\n",
+        StyleSheet::new_with_options(
+            "a {\n  color: darken(red, 10%);\n}\n".to_string(),
+            &Options::default().function("darken($color, $amount)", |_| {
+                Ok(Value::String("overridden".to_owned(), QuoteKind::None))
+            })
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn error_reported_at_call() {
+    let err = compile("a {\n  background: asset-url(1px);\n}\n")
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        "Error: $path: expected a string.",
+        err.lines().next().unwrap()
+    );
+    assert!(err.contains("background: asset-url(1px);"), "{}", err);
+}
+
+#[test]
+fn missing_argument() {
+    assert_eq!(
+        "Error: Missing argument $path.",
+        first_line!(compile("a {\n  background: asset-url();\n}\n").unwrap_err())
+    );
+}
+
+#[test]
+fn too_many_arguments() {
+    assert_eq!(
+        "Error: Only 2 arguments allowed, but 3 were passed.",
+        first_line!(compile("a {\n  background: asset-url(a, b, c);\n}\n").unwrap_err())
+    );
+}
+
+#[test]
+fn unknown_named_argument() {
+    assert_eq!(
+        "Error: No argument named $foo.",
+        first_line!(compile("a {\n  background: asset-url(a, $foo: b);\n}\n").unwrap_err())
+    );
+}
+
+#[test]
+fn variable_arguments() {
+    assert_eq!(
+        "a {\n  color: 3;\n}\n",
+        StyleSheet::new_with_options(
+            "a {\n  color: count(a, b, c);\n}\n".to_string(),
+            &Options::default().function("count($args...)", |args| match &args[0] {
+                Value::List(items, ..) =>
+                    Ok(Value::String(items.len().to_string(), QuoteKind::None)),
+                _ => Err("expected a list.".to_owned()),
+            })
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn nested_operations_are_evaluated() {
+    assert_eq!(
+        "a {\n  color: 3px 12;\n}\n",
+        StyleSheet::new_with_options(
+            "a {\n  color: same((1px + 2) (3 * 4));\n}\n".to_string(),
+            &Options::default().function("same($value)", |args| match &args[0] {
+                Value::List(items, ..) if items.iter().all(|item| item.as_number().is_some()) => {
+                    Ok(args[0].clone())
+                }
+                _ => Err("expected a list of numbers.".to_owned()),
+            })
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn consults_rust_data() {
+    let flags: Arc<HashSet<String>> = Arc::new(vec!["new-header".to_owned()].into_iter().collect());
+    let options = Options::default().function("feature-flag($name)", move |args| match &args[0] {
+        Value::String(name, _) if flags.contains(name) => Ok(Value::True),
+        Value::String(..) => Ok(Value::False),
+        _ => Err("$name: expected a string.".to_owned()),
+    });
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        StyleSheet::new_with_options(
+            "a {\n  @if feature-flag(new-header) {\n    color: red;\n  }\n  @if feature-flag(old-header) {\n    color: blue;\n  }\n}\n"
+                .to_string(),
+            &options
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn invalid_signature() {
+    let err = StyleSheet::new_with_options(
+        "a {}".to_string(),
+        &Options::default().function("foo(bar)", |_| Ok(Value::Null)),
+    )
+    .unwrap_err()
+    .to_string();
+    assert_eq!("Error: expected \")\".", err.lines().next().unwrap());
+    assert!(err.contains("foo(bar)"), "{}", err);
+}
+
+#[test]
+fn not_available_in_other_compilations() {
+    compile("a {}").unwrap();
+    assert_eq!(
+        "a {\n  background: asset-url(\"a.png\");\n}\n",
+        StyleSheet::new("a {\n  background: asset-url(\"a.png\");\n}\n".to_string()).unwrap()
+    );
+}
+
+#[test]
+fn default_refers_to_earlier_argument() {
+    let options = Options::default().function("pair($a, $b: $a)", |args| {
+        Ok(Value::String(
+            format!("{}-{}", args[0], args[1]),
+            QuoteKind::None,
+        ))
+    });
+    assert_eq!(
+        "a {\n  color: x-x;\n  width: x-y;\n}\n",
+        StyleSheet::new_with_options(
+            "a {\n  color: pair(x);\n  width: pair(x, y);\n}\n".to_string(),
+            &options
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn default_does_not_see_caller_locals() {
+    let options = Options::default().function("echo($a: $local)", |args| Ok(args[0].clone()));
+    assert_eq!(
+        "Error: Undefined variable.",
+        first_line!(StyleSheet::new_with_options(
+            "a {\n  $local: red;\n  color: echo();\n}\n".to_string(),
+            &options
+        )
+        .unwrap_err())
+    );
+}
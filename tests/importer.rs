@@ -0,0 +1,321 @@
+#![cfg(test)]
+
+use std::collections::HashMap;
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use grass::{ImportedStylesheet, Importer, MemoryImporter, Options, StyleSheet, Syntax};
+
+#[macro_use]
+mod macros;
+
+fn compile(input: &str, files: &[(&str, &str)]) -> grass::SassResult<String> {
+    let mut importer = MemoryImporter::new();
+    for (url, contents) in files {
+        importer.insert(*url, *contents);
+    }
+    StyleSheet::new_with_options(input.to_string(), &Options::default().importer(importer))
+}
+
+#[test]
+fn import() {
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        compile(
+            "@import \"colors\";\na {\n  color: $a;\n}\n",
+            &[("colors.scss", "$a: red;")]
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn use_with_namespace() {
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        compile(
+            "@use \"colors\";\na {\n  color: colors.$a;\n}\n",
+            &[("colors.scss", "$a: red;")]
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn forward() {
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        compile(
+            "@use \"lib\";\na {\n  color: lib.$a;\n}\n",
+            &[
+                ("lib.scss", "@forward \"colors\";"),
+                ("colors.scss", "$a: red;")
+            ]
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn partial_and_extension_optional() {
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        compile(
+            "@import \"colors\";\na {\n  color: $a;\n}\n",
+            &[("_colors.scss", "$a: red;")]
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn index_file() {
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        compile(
+            "@use \"theme\";\na {\n  color: theme.$a;\n}\n",
+            &[("theme/_index.scss", "$a: red;")]
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn relative_to_importing_stylesheet() {
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        compile(
+            "@use \"theme/main\";\na {\n  color: main.$a;\n}\n",
+            &[
+                ("theme/main.scss", "@forward \"colors\";"),
+                ("theme/colors.scss", "$a: red;"),
+                ("colors.scss", "$a: blue;")
+            ]
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn parent_directory() {
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        compile(
+            "@use \"theme/main\";\na {\n  color: main.$a;\n}\n",
+            &[
+                ("theme/main.scss", "@forward \"../colors\";"),
+                ("colors.scss", "$a: red;")
+            ]
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn syntax_from_extension() {
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        compile(
+            "@import \"indented\";",
+            &[("indented.sass", "a\n  color: red\n")]
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn module_loaded_once() {
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        compile(
+            "@use \"a\";\n@use \"b\";",
+            &[
+                ("a.scss", "@use \"shared\";"),
+                ("b.scss", "@use \"./shared\";"),
+                ("shared.scss", "a {\n  color: red;\n}\n")
+            ]
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn import_loop() {
+    assert_eq!(
+        "Error: This file is already being loaded.",
+        first_line!(compile("@import \"a\";", &[("a.scss", "@import \"a\";")]).unwrap_err())
+    );
+}
+
+#[test]
+fn falls_back_to_filesystem() {
+    tempfile!("importer_falls_back.scss", "$a: red;");
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        compile(
+            "@import \"importer_falls_back\";\na {\n  color: $a;\n}\n",
+            &[("colors.scss", "$a: blue;")]
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn preferred_over_filesystem() {
+    tempfile!("importer_preferred.scss", "$a: blue;");
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        compile(
+            "@import \"importer_preferred\";\na {\n  color: $a;\n}\n",
+            &[("importer_preferred.scss", "$a: red;")]
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn not_found() {
+    assert_eq!(
+        "Error: Can't find stylesheet to import.",
+        first_line!(compile("@import \"importer_missing\";", &[]).unwrap_err())
+    );
+}
+
+#[test]
+fn partial_and_non_partial_ambiguous() {
+    assert_eq!(
+        "Error: It's not clear which file to import. Found:",
+        first_line!(compile(
+            "@import \"a\";",
+            &[("_a.scss", "$a: red;"), ("a.scss", "$a: blue;")]
+        )
+        .unwrap_err())
+    );
+}
+
+#[test]
+fn sass_and_scss_ambiguous() {
+    assert_eq!(
+        "Error: It's not clear which file to import. Found:",
+        first_line!(compile(
+            "@use \"a\";",
+            &[("a.sass", "$a: red"), ("a.scss", "$a: blue;")]
+        )
+        .unwrap_err())
+    );
+}
+
+#[test]
+fn scss_preferred_over_css() {
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        compile(
+            "@import \"colors\";\na {\n  color: $a;\n}\n",
+            &[("colors.scss", "$a: red;"), ("colors.css", "b { c: d; }")]
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn from_hash_map() {
+    let mut files = HashMap::new();
+    files.insert("colors.scss".to_owned(), "$a: red;".to_owned());
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        StyleSheet::new_with_options(
+            "@use \"colors\";\na {\n  color: colors.$a;\n}\n".to_string(),
+            &Options::default().importer(MemoryImporter::from(files))
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn source_map_uses_canonical_url() {
+    let mut importer = MemoryImporter::new();
+    importer.insert("colors.scss", "a {\n  color: red;\n}\n");
+    let (_, map) = StyleSheet::new_with_source_map(
+        "@import \"colors\";".to_string(),
+        &Options::default().importer(importer),
+    )
+    .unwrap();
+    assert_eq!(&["colors.scss".to_string()], map.sources());
+}
+
+/// Counts how many times stylesheets are loaded
+#[derive(Debug, Default)]
+struct Counting(Arc<AtomicUsize>);
+
+impl Importer for Counting {
+    fn canonicalize(&self, url: &str, base: Option<&str>) -> io::Result<Option<String>> {
+        assert_eq!(None, base);
+        Ok(Some(format!("counting:{}", url)))
+    }
+
+    fn load(&self, canonical_url: &str) -> io::Result<ImportedStylesheet> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Ok(ImportedStylesheet {
+            contents: format!("a {{\n  content: \"{}\";\n}}\n", canonical_url),
+            syntax: Syntax::Scss,
+        })
+    }
+}
+
+#[test]
+fn custom_importer() {
+    let loads = Arc::new(AtomicUsize::new(0));
+    assert_eq!(
+        "a {\n  content: \"counting:foo\";\n}\n",
+        StyleSheet::new_with_options(
+            "@use \"foo\";\n@use \"foo\" as bar;".to_string(),
+            &Options::default().importer(Counting(Arc::clone(&loads)))
+        )
+        .unwrap()
+    );
+    assert_eq!(1, loads.load(Ordering::SeqCst));
+}
+
+/// Fails to load every stylesheet
+#[derive(Debug)]
+struct Failing;
+
+impl Importer for Failing {
+    fn canonicalize(&self, url: &str, _: Option<&str>) -> io::Result<Option<String>> {
+        Ok(Some(url.to_owned()))
+    }
+
+    fn load(&self, _: &str) -> io::Result<ImportedStylesheet> {
+        Err(io::Error::new(io::ErrorKind::Other, "Store unavailable."))
+    }
+}
+
+#[test]
+fn load_error_reported_at_rule() {
+    let err = StyleSheet::new_with_options(
+        "// comment\n@use \"foo\";".to_string(),
+        &Options::default().importer(Failing),
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(err.starts_with("Error: Store unavailable."), "{}", err);
+    assert!(err.contains("@use \"foo\""), "{}", err);
+}
+
+#[test]
+fn importers_tried_in_order() {
+    let mut first = MemoryImporter::new();
+    first.insert("colors.scss", "$a: red;");
+    let mut second = MemoryImporter::new();
+    second.insert("colors.scss", "$a: blue;");
+    second.insert("sizes.scss", "$b: 1px;");
+    assert_eq!(
+        "a {\n  color: red;\n  width: 1px;\n}\n",
+        StyleSheet::new_with_options(
+            "@import \"colors\", \"sizes\";\na {\n  color: $a;\n  width: $b;\n}\n".to_string(),
+            &Options::default().importer(first).importer(second)
+        )
+        .unwrap()
+    );
+}
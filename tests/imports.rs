@@ -1,40 +1,9 @@
-use std::io::Write;
-use tempfile::Builder;
-
-use grass::StyleSheet;
-
-/// Create a temporary file with the given name
-/// and contents.
-///
-/// This must be a macro rather than a function
-/// because the tempfile will be deleted when it
-/// exits scope
-macro_rules! tempfile {
-    ($name:literal, $content:literal) => {
-        let mut f = Builder::new()
-            .rand_bytes(0)
-            .prefix("")
-            .suffix($name)
-            .tempfile_in("")
-            .unwrap();
-        write!(f, "{}", $content).unwrap();
-    };
-    ($name:literal, $content:literal, dir=$dir:literal) => {
-        let _d = Builder::new()
-            .rand_bytes(0)
-            .prefix("")
-            .suffix($dir)
-            .tempdir_in("")
-            .unwrap();
-        let mut f = Builder::new()
-            .rand_bytes(0)
-            .prefix("")
-            .suffix($name)
-            .tempfile_in($dir)
-            .unwrap();
-        write!(f, "{}", $content).unwrap();
-    };
-}
+#![cfg(test)]
+
+use grass::{Options, StyleSheet};
+
+#[macro_use]
+mod macros;
 
 #[test]
 fn imports_variable() {
@@ -134,4 +103,356 @@ fn chained_imports_in_directory() {
     );
 }
 
+#[test]
+fn sass_import_alongside_css_import() {
+    let input = "@import \"sass_import_alongside_css_import\", \"foo.css\";\na {\n color: $a;\n}";
+    tempfile!("sass_import_alongside_css_import", "$a: red;");
+    assert_eq!(
+        "@import \"foo.css\";\na {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn import_from_load_path() {
+    tempfile!(
+        "import_from_load_path.scss",
+        "@import \"load_path_partial\";\na {\n color: $a;\n}"
+    );
+    tempfile!(
+        "_load_path_partial.scss",
+        "$a: red;",
+        dir = "import_from_load_path_dir"
+    );
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::from_path_with_options(
+            "import_from_load_path.scss",
+            &Options::default().load_paths(&["import_from_load_path_dir"])
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn import_index_from_load_path() {
+    tempfile!(
+        "import_index_from_load_path.scss",
+        "@import \"load_path_index_dir\";\na {\n color: $a;\n}"
+    );
+    tempfile!("_index.scss", "$a: red;", dir = "load_path_index_dir");
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::from_path_with_options(
+            "import_index_from_load_path.scss",
+            &Options::default().load_paths(&["."])
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn relative_import_preferred_over_load_path() {
+    tempfile!(
+        "relative_import_preferred_over_load_path.scss",
+        "@import \"relative_preferred\";\na {\n color: $a;\n}"
+    );
+    tempfile!("relative_preferred.scss", "$a: red;");
+    tempfile!(
+        "relative_preferred.scss",
+        "$a: blue;",
+        dir = "relative_preferred_dir"
+    );
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::from_path_with_options(
+            "relative_import_preferred_over_load_path.scss",
+            &Options::default().load_paths(&["relative_preferred_dir"])
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn load_paths_searched_in_order() {
+    tempfile!(
+        "load_paths_searched_in_order.scss",
+        "@import \"load_path_order\";\na {\n color: $a;\n}"
+    );
+    tempfile!(
+        "load_path_order.scss",
+        "$a: red;",
+        dir = "load_path_order_first"
+    );
+    tempfile!(
+        "load_path_order.scss",
+        "$a: blue;",
+        dir = "load_path_order_second"
+    );
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::from_path_with_options(
+            "load_paths_searched_in_order.scss",
+            &Options::default().load_paths(&["load_path_order_first", "load_path_order_second"])
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn use_from_load_path() {
+    tempfile!(
+        "use_from_load_path.scss",
+        "@use \"use_load_path\";\na {\n color: use_load_path.$a;\n}"
+    );
+    tempfile!(
+        "use_load_path.scss",
+        "$a: red;",
+        dir = "use_from_load_path_dir"
+    );
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::from_path_with_options(
+            "use_from_load_path.scss",
+            &Options::default().load_paths(&["use_from_load_path_dir"])
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn absolute_import() {
+    tempfile!("absolute_import.scss", "$a: red;");
+    let path = std::env::current_dir().unwrap().join("absolute_import");
+    let input = format!(
+        "@import \"{}\";\na {{\n color: $a;\n}}",
+        path.to_str().unwrap()
+    );
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::new(input.clone()).expect(&input)
+    );
+}
+
+#[test]
+fn import_ambiguous_partial() {
+    let input = "@import \"ambiguous_partial\";";
+    tempfile!("ambiguous_partial.scss", "$a: red;");
+    tempfile!("_ambiguous_partial.scss", "$a: blue;");
+    match StyleSheet::new(input.to_string()) {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: It's not clear which file to import. Found:",
+            e.to_string().lines().next().unwrap()
+        ),
+    }
+}
+
+#[test]
+fn import_ambiguous_extension() {
+    let input = "@import \"ambiguous_extension\";";
+    tempfile!("ambiguous_extension.scss", "$a: red;");
+    tempfile!("ambiguous_extension.sass", "$a: blue");
+    match StyleSheet::new(input.to_string()) {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: It's not clear which file to import. Found:",
+            e.to_string().lines().next().unwrap()
+        ),
+    }
+}
+
+#[test]
+fn import_explicit_extension_not_ambiguous() {
+    let input = "@import \"explicit_extension.scss\";\na {\n color: $a;\n}";
+    tempfile!("explicit_extension.scss", "$a: red;");
+    tempfile!("explicit_extension.sass", "$a: blue");
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn import_css_file_without_extension() {
+    let input = "@import \"css_without_extension\";";
+    tempfile!("css_without_extension.css", "a {\n  color: red;\n}");
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn import_scss_preferred_over_css() {
+    let input = "@import \"scss_over_css\";";
+    tempfile!("scss_over_css.css", "a {\n  color: blue;\n}");
+    tempfile!("scss_over_css.scss", "a {\n  color: red;\n}");
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn import_compiles_single_candidate() {
+    let input = "@import \"single_candidate\";";
+    tempfile!("single_candidate.scss", "a {\n  color: red;\n}");
+    tempfile!(
+        "_index.scss",
+        "a {\n  color: blue;\n}",
+        dir = "single_candidate"
+    );
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+error!(
+    import_not_found,
+    "@import \"this_file_does_not_exist\";", "Error: Can't find stylesheet to import."
+);
+#[test]
+fn import_self() {
+    tempfile!("import_self.scss", "@import \"import_self\";");
+    match StyleSheet::from_path("import_self.scss") {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: This file is already being loaded.",
+            e.to_string().lines().next().unwrap()
+        ),
+    }
+}
+
+#[test]
+fn circular_import() {
+    tempfile!("circular_import_a.scss", "@import \"circular_import_b\";");
+    tempfile!("circular_import_b.scss", "@import \"circular_import_a\";");
+    match StyleSheet::from_path("circular_import_a.scss") {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => {
+            let e = e.to_string();
+            let mut lines = e.lines();
+            assert_eq!(
+                Some("Error: This file is already being loaded."),
+                lines.next()
+            );
+            assert_eq!(Some("  circular_import_a.scss"), lines.next());
+            assert_eq!(Some("  circular_import_b.scss"), lines.next());
+            assert_eq!(Some("  circular_import_a.scss"), lines.next());
+        }
+    }
+}
+
+#[test]
+fn circular_import_from_stdin() {
+    let input = "@import \"circular_stdin_a\";";
+    tempfile!("circular_stdin_a.scss", "@import \"circular_stdin_b\";");
+    tempfile!("circular_stdin_b.scss", "@import \"circular_stdin_a\";");
+    match StyleSheet::new(input.to_string()) {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: This file is already being loaded.",
+            e.to_string().lines().next().unwrap()
+        ),
+    }
+}
+
+#[test]
+fn same_file_imported_twice() {
+    let input = "@import \"imported_twice\";\n@import \"imported_twice\";";
+    tempfile!("imported_twice.scss", "a {\n  color: red;\n}");
+    assert_eq!(
+        "a {\n  color: red;\n}\n\na {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+test!(
+    plain_css_import,
+    "@import \"foo.css\";\n",
+    "@import \"foo.css\";\n"
+);
+test!(
+    plain_css_import_single_quotes,
+    "@import 'foo.css';\n",
+    "@import 'foo.css';\n"
+);
+test!(
+    plain_css_import_url,
+    "@import url(foo);\n",
+    "@import url(foo);\n"
+);
+test!(
+    plain_css_import_url_quoted,
+    "@import url(\"foo.css\");\n",
+    "@import url(\"foo.css\");\n"
+);
+test!(
+    plain_css_import_http,
+    "@import \"http://example.com/foo\";\n",
+    "@import \"http://example.com/foo\";\n"
+);
+test!(
+    plain_css_import_protocol_relative,
+    "@import \"//example.com/foo\";\n",
+    "@import \"//example.com/foo\";\n"
+);
+test!(
+    plain_css_import_media_query,
+    "@import \"a\" screen;\n",
+    "@import \"a\" screen;\n"
+);
+test!(
+    plain_css_import_media_query_with_parens,
+    "@import \"a.css\" screen and (min-width: 100px);\n",
+    "@import \"a.css\" screen and (min-width: 100px);\n"
+);
+test!(
+    plain_css_import_interpolated_media_query,
+    "$media: print;\n@import \"a\" #{$media};\n",
+    "@import \"a\" print;\n"
+);
+test!(
+    plain_css_import_list,
+    "@import \"a.css\", url(b), \"c\" print;\n",
+    "@import \"a.css\";\n@import url(b);\n@import \"c\" print;\n"
+);
+test!(
+    plain_css_import_hoisted,
+    "a {\n  color: red;\n}\n\n@import \"foo.css\";\n\nb {\n  color: red;\n}\n",
+    "@import \"foo.css\";\na {\n  color: red;\n}\n\nb {\n  color: red;\n}\n"
+);
+test!(
+    plain_css_import_hoisted_below_leading_comment,
+    "/* license */\na {\n  color: red;\n}\n\n@import \"foo.css\";\n",
+    "/* license */\n@import \"foo.css\";\na {\n  color: red;\n}\n"
+);
+test!(
+    plain_css_import_stays_below_comment,
+    "/* license */\n@import \"foo.css\";\na {\n  color: red;\n}\n",
+    "/* license */\n@import \"foo.css\";\na {\n  color: red;\n}\n"
+);
+test!(
+    plain_css_import_in_style_rule,
+    "a {\n  @import \"foo.css\";\n  color: red;\n}\n",
+    "a {\n  @import \"foo.css\";\n  color: red;\n}\n"
+);
+test!(
+    plain_css_import_in_media,
+    "a {\n  color: red;\n}\n\n@media screen {\n  @import \"foo.css\";\n}\n",
+    "a {\n  color: red;\n}\n\n@media screen {\n  @import \"foo.css\";\n}\n"
+);
+error!(
+    nested_sass_import,
+    "a {\n  @import \"foo\";\n}\n",
+    "Error: Nested imports of Sass stylesheets aren't supported yet."
+);
+error!(
+    sass_import_in_mixin,
+    "@mixin foo {\n  @import \"foo\";\n}\na {\n  @include foo;\n}\n",
+    "Error: Nested imports of Sass stylesheets aren't supported yet."
+);
+
 // todo: test for calling paths, e.g. `grass b\index.scss`
@@ -0,0 +1,204 @@
+#![cfg(test)]
+
+use grass::StyleSheet;
+
+#[macro_use]
+mod macros;
+
+test_indented!(single_style, "a\n  color: red\n", "a {\n  color: red;\n}\n");
+test_indented!(
+    no_trailing_newline,
+    "a\n  color: red",
+    "a {\n  color: red;\n}\n"
+);
+test_indented!(
+    nested_rulesets,
+    "a\n  color: red\n  b\n    color: blue\n",
+    "a {\n  color: red;\n}\na b {\n  color: blue;\n}\n"
+);
+test_indented!(
+    dedent_several_levels,
+    "a\n  b\n    c\n      color: red\nd\n  color: blue\n",
+    "a b c {\n  color: red;\n}\n\nd {\n  color: blue;\n}\n"
+);
+test_indented!(
+    tab_indentation,
+    "a\n\tcolor: red\n\tb\n\t\tcolor: blue\n",
+    "a {\n  color: red;\n}\na b {\n  color: blue;\n}\n"
+);
+test_indented!(
+    blank_lines_ignored,
+    "a\n\n  color: red\n\n\n  width: 1px\n",
+    "a {\n  color: red;\n  width: 1px;\n}\n"
+);
+test_indented!(
+    variable_declaration,
+    "$a: red\na\n  color: $a\n",
+    "a {\n  color: red;\n}\n"
+);
+test_indented!(
+    silent_comment,
+    "// a comment\na\n  color: red // another comment\n",
+    "a {\n  color: red;\n}\n"
+);
+test_indented!(
+    silent_comment_indented_continuation,
+    "// a comment\n   that continues\na\n  color: red\n",
+    "a {\n  color: red;\n}\n"
+);
+test_indented!(
+    loud_comment_closed_implicitly,
+    "/* a comment\na\n  color: red\n",
+    "/* a comment */\na {\n  color: red;\n}\n"
+);
+test_indented!(
+    loud_comment_multiline,
+    "/* a comment\n   that continues\na\n  color: red\n",
+    "/* a comment\n   that continues */\na {\n  color: red;\n}\n"
+);
+test_indented!(
+    url_with_double_slash_not_comment,
+    "a\n  background: url(http://example.com/a.png)\n",
+    "a {\n  background: url(http://example.com/a.png);\n}\n"
+);
+test_indented!(
+    mixin_shorthand,
+    "=foo($a)\n  color: $a\na\n  +foo(red)\n",
+    "a {\n  color: red;\n}\n"
+);
+test_indented!(
+    mixin_shorthand_with_content,
+    "=foo\n  a\n    @content\n+foo\n  color: red\n",
+    "a {\n  color: red;\n}\n"
+);
+test_indented!(
+    adjacent_sibling_combinator_not_include,
+    "a\n  + b\n    color: red\n",
+    "a + b {\n  color: red;\n}\n"
+);
+test_indented!(
+    old_property_syntax,
+    "a\n  :color red\n",
+    "a {\n  color: red;\n}\n"
+);
+test_indented!(
+    pseudo_class_selector_with_children,
+    "a\n  &:hover\n    color: red\n",
+    "a:hover {\n  color: red;\n}\n"
+);
+test_indented!(
+    selector_list_across_lines,
+    "a,\nb\n  color: red\n",
+    "a,\nb {\n  color: red;\n}\n"
+);
+test_indented!(
+    parens_across_lines,
+    "a\n  width: (1px +\n    2px)\n",
+    "a {\n  width: 3px;\n}\n"
+);
+test_indented!(
+    if_else,
+    "@if false\n  a\n    color: red\n@else\n  a\n    color: blue\n",
+    "a {\n  color: blue;\n}\n"
+);
+test_indented!(
+    media_query,
+    "a\n  @media screen\n    color: red\n",
+    "@media screen {\n  a {\n    color: red;\n  }\n}\n"
+);
+test_indented!(
+    function_definition,
+    "@function double($a)\n  @return $a * 2\na\n  width: double(1px)\n",
+    "a {\n  width: 2px;\n}\n"
+);
+test_indented!(
+    each_loop,
+    "@each $a in b, c\n  .#{$a}\n    color: red\n",
+    ".b {\n  color: red;\n}\n\n.c {\n  color: red;\n}\n"
+);
+test_indented!(
+    unquoted_css_import,
+    "@import foo.css\n",
+    "@import \"foo.css\";\n"
+);
+error_indented!(
+    indented_first_line,
+    "  a\n    color: red\n",
+    "Error: Indenting at the beginning of the document is illegal."
+);
+error_indented!(
+    inconsistent_indentation,
+    "a\n    b\n      color: red\n  color: red\n",
+    "Error: Inconsistent indentation, expected 4 spaces."
+);
+error_indented!(
+    inconsistent_indentation_tabs,
+    "a\n\t\tb\n\t\t\tcolor: red\n\tcolor: red\n",
+    "Error: Inconsistent indentation, expected 2 tabs."
+);
+error_indented!(
+    inconsistent_dedent,
+    "a\n  b\n    color: red\n   color: red\n",
+    "Error: Inconsistent indentation, expected 4 spaces."
+);
+error_indented!(
+    tabs_and_spaces_in_one_line,
+    "a\n \tcolor: red\n",
+    "Error: Tabs and spaces may not be mixed."
+);
+error_indented!(
+    spaces_after_tabs,
+    "a\n\tb\n\t\tcolor: red\n  d: e\n",
+    "Error: Expected tabs, was spaces."
+);
+error_indented!(
+    tabs_after_spaces,
+    "a\n  color: red\nb\n\tcolor: red\n",
+    "Error: Expected spaces, was tabs."
+);
+
+#[test]
+fn import_sass_from_scss() {
+    let input = "@import \"import_sass_from_scss\";\na {\n color: $a;\n}";
+    tempfile!("import_sass_from_scss.sass", "$a: red\n");
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn import_sass_partial_with_rules() {
+    let input = "@import \"sass_partial_with_rules\";";
+    tempfile!(
+        "_sass_partial_with_rules.sass",
+        "=red\n  color: red\na\n  +red\n"
+    );
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn use_sass_module() {
+    let input = "@use \"use_sass_module\";\na {\n color: use_sass_module.$a;\n}";
+    tempfile!("use_sass_module.sass", "$a: red\n");
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn import_scss_from_sass() {
+    tempfile!(
+        "import_scss_from_sass.sass",
+        "@import import_scss_from_sass_dep\na\n  color: $a\n"
+    );
+    tempfile!("import_scss_from_sass_dep.scss", "$a: red;");
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::from_path("import_scss_from_sass.sass").unwrap()
+    );
+}
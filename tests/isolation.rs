@@ -0,0 +1,117 @@
+#![cfg(test)]
+
+use std::thread;
+
+use grass::{Options, StyleSheet};
+
+#[macro_use]
+mod macros;
+
+#[test]
+fn variables_do_not_leak_between_compilations() {
+    StyleSheet::new("$isolated-var: red;".to_string()).unwrap();
+    assert_eq!(
+        "Error: Undefined variable.",
+        first_line!(StyleSheet::new("a {\n  color: $isolated-var;\n}\n".to_string()).unwrap_err())
+    );
+}
+
+#[test]
+fn mixins_do_not_leak_between_compilations() {
+    StyleSheet::new("@mixin isolated-mixin {\n  color: red;\n}\n".to_string()).unwrap();
+    assert_eq!(
+        "Error: Undefined mixin.",
+        first_line!(
+            StyleSheet::new("a {\n  @include isolated-mixin;\n}\n".to_string()).unwrap_err()
+        )
+    );
+}
+
+#[test]
+fn functions_do_not_leak_between_compilations() {
+    StyleSheet::new("@function isolated-fn() {\n  @return red;\n}\n".to_string()).unwrap();
+    assert_eq!(
+        "a {\n  color: isolated-fn();\n}\n",
+        StyleSheet::new("a {\n  color: isolated-fn();\n}\n".to_string()).unwrap()
+    );
+}
+
+#[test]
+fn global_variable_exists_is_not_affected_by_earlier_compilations() {
+    StyleSheet::new("$isolated-exists: red;".to_string()).unwrap();
+    assert_eq!(
+        "a {\n  color: false;\n}\n",
+        StyleSheet::new("a {\n  color: global-variable-exists(isolated-exists);\n}\n".to_string())
+            .unwrap()
+    );
+}
+
+#[test]
+fn modules_do_not_leak_between_compilations() {
+    StyleSheet::new("@use \"sass:math\" as isolated-math;".to_string()).unwrap();
+    assert_eq!(
+        "Error: There is no module with the namespace \"isolated-math\".",
+        first_line!(
+            StyleSheet::new("a {\n  width: isolated-math.abs(-1px);\n}\n".to_string()).unwrap_err()
+        )
+    );
+}
+
+#[test]
+fn module_evaluated_again_in_new_compilation() {
+    tempfile!("isolation_module.scss", "a {\n  color: red;\n}\n");
+    let input = "@use \"isolation_module\";";
+    for _ in 0..2 {
+        assert_eq!(
+            "a {\n  color: red;\n}\n",
+            StyleSheet::new(input.to_string()).unwrap()
+        );
+    }
+}
+
+#[test]
+fn failed_compilation_does_not_affect_the_next() {
+    StyleSheet::new("$isolated-failed: red;\na {\n  color: $undefined;\n}\n".to_string())
+        .unwrap_err();
+    assert_eq!(
+        "a {\n  color: blue;\n}\n",
+        StyleSheet::new(
+            "$isolated-failed: blue !default;\na {\n  color: $isolated-failed;\n}\n".to_string()
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn compilation_is_repeatable() {
+    let input = "$a: 1;\n@function inc() {\n  $a: $a + 1 !global;\n  @return $a;\n}\na {\n  width: inc();\n}\n";
+    for _ in 0..3 {
+        assert_eq!(
+            "a {\n  width: 2;\n}\n",
+            StyleSheet::new(input.to_string()).unwrap()
+        );
+    }
+}
+
+#[test]
+fn concurrent_compilations() {
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            thread::spawn(move || {
+                let input = format!(
+                    "$a: {};\n@mixin m {{\n  width: $a * 1px;\n}}\na {{\n  @include m;\n}}\n",
+                    i
+                );
+                let options = Options::default().precision(i + 1);
+                (0..20)
+                    .map(|_| StyleSheet::new_with_options(input.clone(), &options).unwrap())
+                    .collect::<Vec<String>>()
+            })
+        })
+        .collect();
+    for (i, handle) in handles.into_iter().enumerate() {
+        for css in handle.join().unwrap() {
+            assert_eq!(format!("a {{\n  width: {}px;\n}}\n", i), css);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+#![cfg(test)]
+
+#[macro_use]
+mod macros;
+
+test!(
+    keyframes_from_to,
+    "@keyframes fade {\n  from {\n    opacity: 0;\n  }\n  to {\n    opacity: 1;\n  }\n}\n",
+    "@keyframes fade {\n  from {\n    opacity: 0;\n  }\n  to {\n    opacity: 1;\n  }\n}\n"
+);
+test!(
+    keyframes_percentages,
+    "@keyframes fade {\n  0% {\n    opacity: 0;\n  }\n  50.5% {\n    opacity: 0.5;\n  }\n  100% {\n    opacity: 1;\n  }\n}\n",
+    "@keyframes fade {\n  0% {\n    opacity: 0;\n  }\n  50.5% {\n    opacity: 0.5;\n  }\n  100% {\n    opacity: 1;\n  }\n}\n"
+);
+test!(
+    keyframes_selector_list,
+    "@keyframes fade {\n  from,50% {\n    opacity: 0;\n  }\n}\n",
+    "@keyframes fade {\n  from, 50% {\n    opacity: 0;\n  }\n}\n"
+);
+test!(
+    keyframes_uppercase_selector,
+    "@keyframes fade {\n  FROM {\n    opacity: 0;\n  }\n}\n",
+    "@keyframes fade {\n  from {\n    opacity: 0;\n  }\n}\n"
+);
+test!(
+    keyframes_vendor_prefixed,
+    "@-webkit-keyframes fade {\n  to {\n    opacity: 1;\n  }\n}\n",
+    "@-webkit-keyframes fade {\n  to {\n    opacity: 1;\n  }\n}\n"
+);
+test!(
+    keyframes_interpolated_name,
+    "$name: fade;\n@keyframes #{$name}-in {\n  to {\n    opacity: 1;\n  }\n}\n",
+    "@keyframes fade-in {\n  to {\n    opacity: 1;\n  }\n}\n"
+);
+test!(
+    keyframes_computed_percentage,
+    "$step: 25%;\n@keyframes fade {\n  #{$step * 2} {\n    opacity: 0.5;\n  }\n  #{percentage(0.75)} {\n    opacity: 0.75;\n  }\n}\n",
+    "@keyframes fade {\n  50% {\n    opacity: 0.5;\n  }\n  75% {\n    opacity: 0.75;\n  }\n}\n"
+);
+test!(
+    keyframes_sassscript_values,
+    "$o: 0.5;\n@keyframes fade {\n  to {\n    opacity: $o * 2;\n  }\n}\n",
+    "@keyframes fade {\n  to {\n    opacity: 1;\n  }\n}\n"
+);
+test!(
+    keyframes_not_combined_with_parent,
+    "a {\n  color: red;\n  @keyframes fade {\n    to {\n      opacity: 1;\n    }\n  }\n}\n",
+    "a {\n  color: red;\n}\n@keyframes fade {\n  to {\n    opacity: 1;\n  }\n}\n"
+);
+test!(
+    keyframes_within_media,
+    "@media screen {\n  @keyframes fade {\n    to {\n      opacity: 1;\n    }\n  }\n}\n",
+    "@media screen {\n  @keyframes fade {\n    to {\n      opacity: 1;\n    }\n  }\n}\n"
+);
+test!(
+    keyframes_empty,
+    "@keyframes fade {}\n",
+    "@keyframes fade {}\n"
+);
+test!(
+    keyframes_between_rules,
+    "a {\n  color: red;\n}\n\n@keyframes fade {\n  to {\n    opacity: 1;\n  }\n}\n\nb {\n  color: red;\n}\n",
+    "a {\n  color: red;\n}\n\n@keyframes fade {\n  to {\n    opacity: 1;\n  }\n}\n\nb {\n  color: red;\n}\n"
+);
+error!(
+    keyframes_invalid_keyword,
+    "@keyframes fade {\n  middle {\n    opacity: 0;\n  }\n}\n",
+    "Error: Expected \"to\" or \"from\"."
+);
+error!(
+    keyframes_missing_percent,
+    "@keyframes fade {\n  50 {\n    opacity: 0;\n  }\n}\n", "Error: expected \"%\"."
+);
+error!(
+    keyframes_no_name,
+    "@keyframes {\n  to {\n    opacity: 0;\n  }\n}\n", "Error: Expected identifier."
+);
+test!(
+    keyframes_for,
+    "@keyframes grow {\n  @for $i from 0 through 2 {\n    #{$i * 50%} {\n      width: $i * 10px;\n    }\n  }\n}\n",
+    "@keyframes grow {\n  0% {\n    width: 0px;\n  }\n  50% {\n    width: 10px;\n  }\n  100% {\n    width: 20px;\n  }\n}\n"
+);
+test!(
+    keyframes_if,
+    "$fade: true;\n@keyframes fade {\n  @if $fade {\n    to {\n      opacity: 1;\n    }\n  } @else {\n    to {\n      opacity: 0;\n    }\n  }\n}\n",
+    "@keyframes fade {\n  to {\n    opacity: 1;\n  }\n}\n"
+);
+test!(
+    keyframes_each,
+    "@keyframes fade {\n  @each $step, $opacity in (from: 0, to: 1) {\n    #{$step} {\n      opacity: $opacity;\n    }\n  }\n}\n",
+    "@keyframes fade {\n  from {\n    opacity: 0;\n  }\n  to {\n    opacity: 1;\n  }\n}\n"
+);
+test!(
+    keyframes_include_mixin_with_blocks,
+    "@mixin frame($at, $opacity) {\n  #{$at} {\n    opacity: $opacity;\n  }\n}\n@keyframes fade {\n  @include frame(from, 0);\n  @include frame(to, 1);\n}\n",
+    "@keyframes fade {\n  from {\n    opacity: 0;\n  }\n  to {\n    opacity: 1;\n  }\n}\n"
+);
+test!(
+    keyframes_if_within_block,
+    "@keyframes fade {\n  to {\n    @if true {\n      opacity: 1;\n    }\n  }\n}\n",
+    "@keyframes fade {\n  to {\n    opacity: 1;\n  }\n}\n"
+);
+error!(
+    keyframes_style_rule_within_block,
+    "@keyframes fade {\n  to {\n    a {\n      opacity: 1;\n    }\n  }\n}\n",
+    "Error: Style rules may not be used within keyframe blocks."
+);
+error!(
+    keyframes_declaration_outside_block,
+    "@keyframes fade {\n  opacity: 1;\n}\n",
+    "Error: Declarations may only be used within style rules."
+);
+error!(
+    keyframes_media_within_block,
+    "@keyframes fade {\n  to {\n    @media screen {\n      opacity: 1;\n    }\n  }\n}\n",
+    "Error: This at-rule is not allowed here."
+);
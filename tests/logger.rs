@@ -0,0 +1,150 @@
+#![cfg(test)]
+
+use grass::{
+    CollectingLogger, LogKind, LogMessage, Options, SilentLogger, SourceLocation, StyleSheet,
+};
+
+#[macro_use]
+mod macros;
+
+fn log(input: &str) -> Vec<LogMessage> {
+    let logger = CollectingLogger::new();
+    StyleSheet::new_with_options(
+        input.to_string(),
+        &Options::default().logger(logger.clone()),
+    )
+    .unwrap();
+    logger.messages()
+}
+
+#[test]
+fn warn_at_toplevel() {
+    assert_eq!(
+        vec![LogMessage {
+            kind: LogKind::Warning,
+            message: "deprecated".to_owned(),
+            location: SourceLocation {
+                file: "stdin".to_owned(),
+                line: 3,
+                column: 1,
+            },
+        }],
+        log("a {\n}\n@warn deprecated;")
+    );
+}
+
+#[test]
+fn debug_at_toplevel() {
+    let messages = log("@debug 1px + 2px;");
+    assert_eq!(1, messages.len());
+    assert_eq!(LogKind::Debug, messages[0].kind);
+    assert_eq!("3px", messages[0].message);
+}
+
+#[test]
+fn warn_in_style_rule() {
+    let messages = log("a {\n  color: red;\n  @warn in-a-rule;\n}\n");
+    assert_eq!(1, messages.len());
+    assert_eq!("in-a-rule", messages[0].message);
+    assert_eq!(3, messages[0].location.line);
+    assert_eq!(3, messages[0].location.column);
+}
+
+#[test]
+fn warn_in_mixin() {
+    let messages = log("@mixin foo {\n  @warn in-a-mixin;\n}\na {\n  @include foo;\n}\n");
+    assert_eq!(1, messages.len());
+    assert_eq!("in-a-mixin", messages[0].message);
+    assert_eq!(2, messages[0].location.line);
+    assert_eq!(3, messages[0].location.column);
+}
+
+#[test]
+fn debug_in_function() {
+    let messages =
+        log("@function foo($a) {\n  @debug $a;\n  @return $a;\n}\na {\n  color: foo(red);\n}\n");
+    assert_eq!(1, messages.len());
+    assert_eq!(LogKind::Debug, messages[0].kind);
+    assert_eq!("red", messages[0].message);
+    assert_eq!(2, messages[0].location.line);
+    assert_eq!(3, messages[0].location.column);
+}
+
+#[test]
+fn warn_in_function_at_toplevel() {
+    let messages = log("@function foo() {\n  @warn in-a-function;\n  @return 1;\n}\n$a: foo();\n");
+    assert_eq!("in-a-function", messages[0].message);
+}
+
+#[test]
+fn logged_before_error() {
+    let logger = CollectingLogger::new();
+    StyleSheet::new_with_options(
+        "@warn a;\n@error b;".to_string(),
+        &Options::default().logger(logger.clone()),
+    )
+    .unwrap_err();
+    assert_eq!("a", logger.messages()[0].message);
+}
+
+#[test]
+fn messages_in_order() {
+    let messages = log("@debug a;\na {\n  @warn b;\n}\n@debug c;\n");
+    assert_eq!(
+        vec![
+            (LogKind::Debug, "a"),
+            (LogKind::Warning, "b"),
+            (LogKind::Debug, "c")
+        ],
+        messages
+            .iter()
+            .map(|m| (m.kind, m.message.as_str()))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn quiet_logs_nothing() {
+    let logger = CollectingLogger::new();
+    StyleSheet::new_with_options(
+        "@warn a;\n@debug b;".to_string(),
+        &Options::default().logger(logger.clone()).quiet(true),
+    )
+    .unwrap();
+    assert!(logger.messages().is_empty());
+}
+
+#[test]
+fn take_empties_logger() {
+    let logger = CollectingLogger::new();
+    let options = Options::default().logger(logger.clone());
+    StyleSheet::new_with_options("@warn a;".to_string(), &options).unwrap();
+    assert_eq!(1, logger.take().len());
+    StyleSheet::new_with_options("@warn b;".to_string(), &options).unwrap();
+    assert_eq!("b", logger.take()[0].message);
+    assert!(logger.messages().is_empty());
+}
+
+#[test]
+fn file_name_of_path() {
+    tempfile!("logger_file_name.scss", "@warn a;");
+    let logger = CollectingLogger::new();
+    StyleSheet::from_path_with_options(
+        "logger_file_name.scss",
+        &Options::default().logger(logger.clone()),
+    )
+    .unwrap();
+    assert_eq!("logger_file_name.scss", logger.messages()[0].location.file);
+}
+
+#[test]
+fn silent_logger() {
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        StyleSheet::new_with_options(
+            "@warn a;\na {\n  color: red;\n}\n".to_string(),
+            &Options::default().logger(SilentLogger)
+        )
+        .unwrap()
+    );
+}
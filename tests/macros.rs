@@ -51,3 +51,105 @@ macro_rules! error {
         }
     };
 }
+
+/// Verify the output of a stylesheet written in the indented syntax
+#[macro_export]
+macro_rules! test_indented {
+    ($( #[$attr:meta] ),*$func:ident, $input:expr, $output:expr) => {
+        $(#[$attr])*
+        #[test]
+        #[allow(non_snake_case)]
+        fn $func() {
+            let sass = grass::StyleSheet::new_indented($input.to_string())
+                .expect(concat!("failed to parse on ", $input));
+            assert_eq!(
+                String::from($output),
+                sass
+            );
+        }
+    };
+}
+
+/// Verify the error *message* of a stylesheet written in the indented syntax
+#[macro_export]
+macro_rules! error_indented {
+    ($( #[$attr:meta] ),*$func:ident, $input:expr, $err:expr) => {
+        $(#[$attr])*
+        #[test]
+        #[allow(non_snake_case)]
+        fn $func() {
+            match grass::StyleSheet::new_indented($input.to_string()) {
+                Ok(..) => panic!("did not fail"),
+                Err(e) => assert_eq!($err, e.to_string()
+                                                .chars()
+                                                .take_while(|c| *c != '\n')
+                                                .collect::<String>()
+                                                .as_str()
+                ),
+            }
+        }
+    };
+}
+
+/// Create a temporary file with the given name
+/// and contents.
+///
+/// This must be a macro rather than a function
+/// because the tempfile will be deleted when it
+/// exits scope
+#[macro_export]
+macro_rules! tempfile {
+    ($name:literal, $content:literal) => {
+        let mut f = tempfile::Builder::new()
+            .rand_bytes(0)
+            .prefix("")
+            .suffix($name)
+            .tempfile_in("")
+            .unwrap();
+        std::io::Write::write_all(&mut f, $content.as_bytes()).unwrap();
+    };
+    ($name:literal, $content:literal, dir=$dir:literal) => {
+        let _d = tempfile::Builder::new()
+            .rand_bytes(0)
+            .prefix("")
+            .suffix($dir)
+            .tempdir_in("")
+            .unwrap();
+        let mut f = tempfile::Builder::new()
+            .rand_bytes(0)
+            .prefix("")
+            .suffix($name)
+            .tempfile_in($dir)
+            .unwrap();
+        std::io::Write::write_all(&mut f, $content.as_bytes()).unwrap();
+    };
+}
+
+/// Verify the output of a stylesheet in the compressed output style
+#[macro_export]
+macro_rules! test_compressed {
+    ($( #[$attr:meta] ),*$func:ident, $input:expr, $output:expr) => {
+        $(#[$attr])*
+        #[test]
+        #[allow(non_snake_case)]
+        fn $func() {
+            let sass = grass::StyleSheet::new_with_options(
+                $input.to_string(),
+                &grass::Options::default().style(grass::OutputStyle::Compressed),
+            )
+            .expect(concat!("failed to parse on ", $input));
+            assert_eq!(
+                String::from($output),
+                sass
+            );
+        }
+    };
+}
+
+/// The first line of the error `$err`, which holds its message
+#[macro_export]
+macro_rules! first_line {
+    ($err:expr) => {
+        $err.to_string().lines().next().unwrap().to_owned()
+    };
+}
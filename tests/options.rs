@@ -0,0 +1,211 @@
+#![cfg(test)]
+
+use grass::{Options, OutputStyle, SourceMapUrls, StyleSheet, Syntax};
+
+#[macro_use]
+mod macros;
+
+#[test]
+fn default_options_match_new() {
+    let input = "a {\n  color: red;\n}\n";
+    assert_eq!(
+        StyleSheet::new(input.to_string()).unwrap(),
+        StyleSheet::new_with_options(input.to_string(), &Options::default()).unwrap()
+    );
+}
+
+#[test]
+fn compressed_style() {
+    assert_eq!(
+        "a{color:red}",
+        StyleSheet::new_with_options(
+            "a {\n  color: red;\n}\n".to_string(),
+            &Options::default().style(OutputStyle::Compressed)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn quiet_does_not_change_output() {
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        StyleSheet::new_with_options(
+            "@warn \"foo\";\n@debug \"bar\";\na {\n  color: red;\n}\n".to_string(),
+            &Options::default().quiet(true)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn no_charset() {
+    assert_eq!(
+        "a {\n  content: \"é\";\n}\n",
+        StyleSheet::new_with_options(
+            "a {\n  content: \"é\";\n}\n".to_string(),
+            &Options::default().allows_charset(false)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn no_byte_order_mark_when_compressed() {
+    assert_eq!(
+        "a{content:\"é\"}",
+        StyleSheet::new_with_options(
+            "a {\n  content: \"é\";\n}\n".to_string(),
+            &Options::default()
+                .allows_charset(false)
+                .style(OutputStyle::Compressed)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn precision() {
+    assert_eq!(
+        "a {\n  width: 0.333;\n}\n",
+        StyleSheet::new_with_options(
+            "a {\n  width: (1/3);\n}\n".to_string(),
+            &Options::default().precision(3)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn precision_does_not_persist() {
+    StyleSheet::new_with_options(
+        "a {\n  width: (1/3);\n}\n".to_string(),
+        &Options::default().precision(3),
+    )
+    .unwrap();
+    assert_eq!(
+        "a {\n  width: 0.3333333333;\n}\n",
+        StyleSheet::new("a {\n  width: (1/3);\n}\n".to_string()).unwrap()
+    );
+}
+
+#[test]
+fn indented_syntax() {
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        StyleSheet::new_with_options(
+            "a\n  color: red\n".to_string(),
+            &Options::default().syntax(Syntax::Sass)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn css_syntax() {
+    assert_eq!(
+        "a {\n  width: 1px + 2px;\n}\n",
+        StyleSheet::new_with_options(
+            "a {\n  width: 1px + 2px;\n}\n".to_string(),
+            &Options::default().syntax(Syntax::Css)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn syntax_overrides_extension() {
+    tempfile!(
+        "options_syntax_overrides_extension.css",
+        "a\n  color: red\n"
+    );
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        StyleSheet::from_path_with_options(
+            "options_syntax_overrides_extension.css",
+            &Options::default().syntax(Syntax::Sass)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn load_path() {
+    tempfile!(
+        "options_load_path.scss",
+        "@import \"options_load_path_partial\";\na {\n color: $a;\n}"
+    );
+    tempfile!(
+        "_options_load_path_partial.scss",
+        "$a: red;",
+        dir = "options_load_path_dir"
+    );
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        StyleSheet::from_path_with_options(
+            "options_load_path.scss",
+            &Options::default().load_path("options_load_path_dir")
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn embedded_source_map() {
+    let css = StyleSheet::new_with_options(
+        "a {\n  color: red;\n}\n".to_string(),
+        &Options::default().embed_source_map(true),
+    )
+    .unwrap();
+    assert!(css.starts_with(
+        "a {\n  color: red;\n}\n\n/*# sourceMappingURL=data:application/json;charset=utf-8;base64,"
+    ));
+    assert!(css.ends_with(" */\n"));
+}
+
+#[test]
+fn source_map_not_linked_by_default() {
+    let (css, _) =
+        StyleSheet::new_with_source_map("a {\n  color: red;\n}\n".to_string(), &Options::default())
+            .unwrap();
+    assert_eq!("a {\n  color: red;\n}\n", css);
+}
+
+#[test]
+fn source_map_relative_to_output_path() {
+    tempfile!(
+        "options_relative_source_map.scss",
+        "a {\n  color: red;\n}\n"
+    );
+    let (css, map) = StyleSheet::from_path_with_source_map(
+        "options_relative_source_map.scss",
+        &Options::default().output_path("options_relative_source_map_out/out.css"),
+    )
+    .unwrap();
+    assert_eq!(
+        "a {\n  color: red;\n}\n\n/*# sourceMappingURL=out.css.map */\n",
+        css
+    );
+    assert_eq!(
+        "{\"version\":3,\"file\":\"out.css\",\"sources\":[\"../options_relative_source_map.scss\"],\"names\":[],\"mappings\":\"AAAA;EACE\"}",
+        map.to_json(false)
+    );
+}
+
+#[test]
+fn absolute_source_map_urls() {
+    tempfile!(
+        "options_absolute_source_map.scss",
+        "a {\n  color: red;\n}\n"
+    );
+    let (css, map) = StyleSheet::from_path_with_source_map(
+        "options_absolute_source_map.scss",
+        &Options::default()
+            .output_path("out.css")
+            .source_map_urls(SourceMapUrls::Absolute),
+    )
+    .unwrap();
+    assert!(css.contains("/*# sourceMappingURL=file://"));
+    assert!(map.sources()[0].starts_with("file://"));
+    assert!(map.sources()[0].ends_with("/options_absolute_source_map.scss"));
+}
@@ -0,0 +1,172 @@
+#![cfg(test)]
+
+use grass::StyleSheet;
+
+#[macro_use]
+mod macros;
+
+/// Compile `$input` as the `.css` file `$name`
+macro_rules! css {
+    ($name:literal, $input:literal) => {{
+        tempfile!($name, $input);
+        StyleSheet::from_path($name)
+    }};
+}
+
+#[test]
+fn functions_not_evaluated() {
+    assert_eq!(
+        "a {\n  color: rgb(0, 0, 0);\n  width: min(1px, 2px);\n  height: calc(100% - 2px);\n}\n",
+        css!(
+            "plain_css_functions_not_evaluated.css",
+            "a {\n  color: rgb(0, 0, 0);\n  width: min(1px, 2px);\n  height: calc(100%  -  2px);\n}\n"
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn operators_not_evaluated() {
+    assert_eq!(
+        "a {\n  font: 12px/1.5 \"Helvetica Neue\", sans-serif;\n  width: 1px + 2px;\n}\n",
+        css!(
+            "plain_css_operators_not_evaluated.css",
+            "a {\n  font: 12px/1.5 \"Helvetica Neue\", sans-serif;\n  width: 1px + 2px;\n}\n"
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn at_rules_passed_through() {
+    assert_eq!(
+        "@import url(foo.css) screen;\n@media screen {\n  a {\n    color: red;\n  }\n}\n\n@font-face {\n  font-family: \"Foo\";\n}\n",
+        css!(
+            "plain_css_at_rules_passed_through.css",
+            "@import url(foo.css) screen;\n@media screen {\n  a { color: red }\n}\n@font-face {\n  font-family: \"Foo\";\n}\n"
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn keyframes() {
+    assert_eq!(
+        "@keyframes spin {\n  from {\n    transform: rotate(0deg);\n  }\n  to {\n    transform: rotate(360deg);\n  }\n}\n",
+        css!(
+            "plain_css_keyframes.css",
+            "@keyframes spin {\n  from { transform: rotate(0deg) }\n  to { transform: rotate(360deg) }\n}\n"
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn custom_property_with_braces() {
+    assert_eq!(
+        "a {\n  --foo: { a: b };\n}\n",
+        css!(
+            "plain_css_custom_property.css",
+            "a {\n  --foo: { a: b };\n}\n"
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn loud_comment_kept() {
+    assert_eq!(
+        "/* foo */\na {\n  color: red;\n}\n",
+        css!(
+            "plain_css_loud_comment.css",
+            "/* foo */\na {\n  color: red;\n}\n"
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn variable_declaration_not_allowed() {
+    assert_eq!(
+        "Error: Sass variables aren't allowed in plain CSS.",
+        first_line!(css!("plain_css_variable_declaration.css", "$a: red;").unwrap_err())
+    );
+}
+
+#[test]
+fn variable_reference_not_allowed() {
+    assert_eq!(
+        "Error: Sass variables aren't allowed in plain CSS.",
+        first_line!(
+            css!("plain_css_variable_reference.css", "a {\n  color: $a;\n}\n").unwrap_err()
+        )
+    );
+}
+
+#[test]
+fn mixin_not_allowed() {
+    assert_eq!(
+        "Error: This at-rule isn't allowed in plain CSS.",
+        first_line!(css!("plain_css_mixin.css", "@mixin foo {}\n").unwrap_err())
+    );
+}
+
+#[test]
+fn nesting_not_allowed() {
+    assert_eq!(
+        "Error: Nesting isn't allowed in plain CSS.",
+        first_line!(css!(
+            "plain_css_nesting.css",
+            "a {\n  b {\n    color: red;\n  }\n}\n"
+        )
+        .unwrap_err())
+    );
+}
+
+#[test]
+fn silent_comment_not_allowed() {
+    assert_eq!(
+        "Error: Silent comments aren't allowed in plain CSS.",
+        first_line!(css!(
+            "plain_css_silent_comment.css",
+            "// foo\na {\n  color: red;\n}\n"
+        )
+        .unwrap_err())
+    );
+}
+
+#[test]
+fn interpolation_not_allowed() {
+    assert_eq!(
+        "Error: Interpolation isn't allowed in plain CSS.",
+        first_line!(css!("plain_css_interpolation.css", "a {\n  color: #{red};\n}\n").unwrap_err())
+    );
+}
+
+#[test]
+fn placeholder_not_allowed() {
+    assert_eq!(
+        "Error: Placeholder selectors aren't allowed in plain CSS.",
+        first_line!(css!("plain_css_placeholder.css", "%a {\n  color: red;\n}\n").unwrap_err())
+    );
+}
+
+#[test]
+fn import_css_file_from_scss() {
+    let input = "@import \"plain_css_imported\";\na {\n color: red;\n}";
+    tempfile!("plain_css_imported.css", "b {\n  color: rgb(0, 0, 0);\n}\n");
+    assert_eq!(
+        "b {\n  color: rgb(0, 0, 0);\n}\n\na {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn use_css_file_from_scss() {
+    let input = "@use \"plain_css_used\";";
+    tempfile!("plain_css_used.css", "b {\n  width: min(1px, 2px);\n}\n");
+    assert_eq!(
+        "b {\n  width: min(1px, 2px);\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
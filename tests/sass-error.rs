@@ -0,0 +1,191 @@
+#![cfg(test)]
+
+use std::error::Error;
+
+use grass::{MemoryImporter, Options, SassError, SassErrorKind, SourceLocation, StyleSheet};
+
+#[macro_use]
+mod macros;
+
+fn compile_err(input: &str) -> SassError {
+    StyleSheet::new(input.to_string()).unwrap_err()
+}
+
+#[test]
+fn parse_error() {
+    let err = compile_err("a {\n  color: red\n");
+    assert_eq!(SassErrorKind::Parse, err.kind());
+    assert_eq!("expected more input.", err.message());
+}
+
+#[test]
+fn location_and_source_line() {
+    let err = compile_err("a {\n  color: $foo;\n}\n");
+    assert_eq!(SassErrorKind::Runtime, err.kind());
+    assert_eq!("Undefined variable.", err.message());
+    assert_eq!(
+        Some(&SourceLocation {
+            file: "stdin".to_owned(),
+            line: 2,
+            column: 11,
+        }),
+        err.location()
+    );
+    assert_eq!(
+        Some(&SourceLocation {
+            file: "stdin".to_owned(),
+            line: 2,
+            column: 15,
+        }),
+        err.end_location()
+    );
+    assert_eq!(Some("  color: $foo;"), err.source_line());
+}
+
+#[test]
+fn display_unchanged() {
+    assert_eq!(
+        "Error: Undefined variable.\n  |\n2 |   color: $foo;\n  |           ^^^^\n  |\n./stdin:2:11\n",
+        compile_err("a {\n  color: $foo;\n}\n").to_string()
+    );
+}
+
+#[test]
+fn at_error_is_runtime() {
+    let err = compile_err("@error \"oops\";");
+    assert_eq!(SassErrorKind::Runtime, err.kind());
+    assert_eq!("\"oops\"", err.message());
+}
+
+#[test]
+fn builtin_function_error_is_runtime() {
+    let err = compile_err("a {\n  color: darken(1px, 10%);\n}\n");
+    assert_eq!(SassErrorKind::Runtime, err.kind());
+    assert_eq!("$color: 1px is not a color.", err.message());
+    assert_eq!(2, err.location().unwrap().line);
+}
+
+#[test]
+fn operation_error_is_runtime() {
+    let err = compile_err("a {\n  width: 1px + 1s;\n}\n");
+    assert_eq!(SassErrorKind::Runtime, err.kind());
+    assert_eq!("Incompatible units s and px.", err.message());
+}
+
+#[test]
+fn host_function_error_is_runtime() {
+    let err = StyleSheet::new_with_options(
+        "a {\n  color: fail();\n}\n".to_string(),
+        &Options::default().function("fail()", |_| Err("failed.".to_owned())),
+    )
+    .unwrap_err();
+    assert_eq!(SassErrorKind::Runtime, err.kind());
+    assert_eq!("failed.", err.message());
+}
+
+#[test]
+fn missing_import_is_runtime() {
+    let err = compile_err("@import \"sass_error_missing\";");
+    assert_eq!(SassErrorKind::Runtime, err.kind());
+    assert_eq!("Can't find stylesheet to import.", err.message());
+}
+
+#[test]
+fn module_error_is_runtime() {
+    let err = compile_err("@use \"sass:math\";\n@use \"sass:color\" as math;");
+    assert_eq!(SassErrorKind::Runtime, err.kind());
+    assert!(err
+        .message()
+        .starts_with("There's already a module with namespace"));
+}
+
+#[test]
+fn private_member_error_is_runtime() {
+    let mut importer = MemoryImporter::new();
+    importer.insert("lib.scss", "$-a: red;");
+    let err = StyleSheet::new_with_options(
+        "@use \"lib\";\na {\n  color: lib.$-a;\n}\n".to_string(),
+        &Options::default().importer(importer),
+    )
+    .unwrap_err();
+    assert_eq!(SassErrorKind::Runtime, err.kind());
+}
+
+#[test]
+fn extend_error_is_runtime() {
+    let err = compile_err("a {\n  @extend .missing;\n}\n");
+    assert_eq!(SassErrorKind::Runtime, err.kind());
+    assert!(err
+        .message()
+        .starts_with("The target selector was not found."));
+}
+
+#[test]
+fn ambiguous_import_is_runtime() {
+    let mut importer = MemoryImporter::new();
+    importer.insert("_a.scss", "");
+    importer.insert("a.scss", "");
+    let err = StyleSheet::new_with_options(
+        "@import \"a\";".to_string(),
+        &Options::default().importer(importer),
+    )
+    .unwrap_err();
+    assert_eq!(SassErrorKind::Runtime, err.kind());
+    assert!(err
+        .message()
+        .starts_with("It's not clear which file to import."));
+}
+
+#[test]
+fn file_name_in_location() {
+    tempfile!("sass_error_file_name.scss", "a {\n  color: $foo;\n}\n");
+    let err = StyleSheet::from_path("sass_error_file_name.scss").unwrap_err();
+    assert_eq!("sass_error_file_name.scss", err.location().unwrap().file);
+}
+
+#[test]
+fn io_error() {
+    let err = StyleSheet::from_path("sass_error_does_not_exist.scss").unwrap_err();
+    assert_eq!(SassErrorKind::Io, err.kind());
+    assert!(err.location().is_none());
+    assert!(err.source_line().is_none());
+    assert!(err.source().is_some());
+    assert_eq!(Some("sass_error_does_not_exist.scss"), err.file());
+}
+
+#[test]
+fn encoding_error() {
+    let mut f = tempfile::Builder::new()
+        .rand_bytes(0)
+        .prefix("")
+        .suffix("sass_error_encoding.scss")
+        .tempfile_in("")
+        .unwrap();
+    std::io::Write::write_all(&mut f, b"a {\n  color: \xff;\n}\n").unwrap();
+    let err = StyleSheet::from_path("sass_error_encoding.scss").unwrap_err();
+    assert_eq!(SassErrorKind::Encoding, err.kind());
+    assert!(err.message().starts_with("Invalid UTF-8 character"));
+    assert_eq!(Some("sass_error_encoding.scss"), err.file());
+}
+
+#[test]
+fn encoding_error_in_import() {
+    let mut f = tempfile::Builder::new()
+        .rand_bytes(0)
+        .prefix("")
+        .suffix("sass_error_imported_encoding.scss")
+        .tempfile_in("")
+        .unwrap();
+    std::io::Write::write_all(&mut f, b"a {\n  color: \xff;\n}\n").unwrap();
+    let err = compile_err("@import \"sass_error_imported_encoding\";");
+    assert_eq!(SassErrorKind::Encoding, err.kind());
+    assert_eq!(Some("sass_error_imported_encoding.scss"), err.file());
+}
+
+#[test]
+fn file_of_located_error() {
+    assert_eq!(
+        Some("stdin"),
+        compile_err("a {\n  color: $foo;\n}\n").file()
+    );
+}
@@ -0,0 +1,138 @@
+#![cfg(test)]
+
+#[macro_use]
+mod macros;
+
+test!(
+    selector_nest_two,
+    "a {\n  color: selector-nest(\".a\", \".b\");\n}\n",
+    "a {\n  color: .a .b;\n}\n"
+);
+test!(
+    selector_nest_parent,
+    "a {\n  color: selector-nest(\".a\", \"&:hover\");\n}\n",
+    "a {\n  color: .a:hover;\n}\n"
+);
+test!(
+    selector_nest_lists,
+    "a {\n  color: selector-nest(\".a, .b\", \".c\");\n}\n",
+    "a {\n  color: .a .c, .b .c;\n}\n"
+);
+test!(
+    selector_append_suffix,
+    "a {\n  color: selector-append(\".a\", \"__icon\");\n}\n",
+    "a {\n  color: .a__icon;\n}\n"
+);
+test!(
+    selector_append_parent_selector,
+    ".block {\n  color: selector-append(&, \"__icon\");\n}\n",
+    ".block {\n  color: .block__icon;\n}\n"
+);
+test!(
+    selector_append_compound,
+    "a {\n  color: selector-append(\".a\", \".b\", \":hover\");\n}\n",
+    "a {\n  color: .a.b:hover;\n}\n"
+);
+test!(
+    selector_append_lists,
+    "a {\n  color: selector-append(\".a, .b\", \".c\");\n}\n",
+    "a {\n  color: .a.c, .b.c;\n}\n"
+);
+test!(
+    selector_extend,
+    "a {\n  color: selector-extend(\"a.b\", \".b\", \".c\");\n}\n",
+    "a {\n  color: a.b, a.c;\n}\n"
+);
+test!(
+    selector_extend_no_match,
+    "a {\n  color: selector-extend(\".a\", \".b\", \".c\");\n}\n",
+    "a {\n  color: .a;\n}\n"
+);
+test!(
+    selector_replace,
+    "a {\n  color: selector-replace(\"a.b\", \".b\", \".c\");\n}\n",
+    "a {\n  color: a.c;\n}\n"
+);
+test!(
+    selector_replace_compound_target,
+    "a {\n  color: selector-replace(\".a.b.c\", \".a.b\", \".d\");\n}\n",
+    "a {\n  color: .c.d;\n}\n"
+);
+test!(
+    selector_unify,
+    "a {\n  color: selector-unify(\"a\", \".b\");\n}\n",
+    "a {\n  color: a.b;\n}\n"
+);
+test!(
+    selector_unify_impossible,
+    "a {\n  color: inspect(selector-unify(\"a\", \"b\"));\n}\n",
+    "a {\n  color: null;\n}\n"
+);
+test!(
+    is_superselector_true,
+    "a {\n  color: is-superselector(\"a\", \"a.b\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    is_superselector_false,
+    "a {\n  color: is-superselector(\"a.b\", \"a\");\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    is_superselector_descendant,
+    "a {\n  color: is-superselector(\".a\", \".b .a\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    simple_selectors,
+    "a {\n  color: simple-selectors(\"a.b:hover\");\n}\n",
+    "a {\n  color: a, .b, :hover;\n}\n"
+);
+test!(
+    selector_parse,
+    "a {\n  color: selector-parse(\".a .b, .c\");\n}\n",
+    "a {\n  color: .a .b, .c;\n}\n"
+);
+test!(
+    selector_parse_is_list,
+    "a {\n  color: length(selector-parse(\".a .b, .c\")) length(nth(selector-parse(\".a .b, .c\"), 1));\n}\n",
+    "a {\n  color: 2 2;\n}\n"
+);
+test!(
+    selector_functions_accept_lists,
+    "a {\n  color: selector-nest((a, b), c d);\n}\n",
+    "a {\n  color: a c d, b c d;\n}\n"
+);
+test!(
+    selector_module,
+    "@use \"sass:selector\";\na {\n  color: selector.append(\".a\", \".b\") selector.is-superselector(\"a\", \"a\");\n}\n",
+    "a {\n  color: .a.b true;\n}\n"
+);
+error!(
+    selector_append_universal,
+    "a {\n  color: selector-append(\".a\", \"*\");\n}\n", "Error: Can't append * to .a."
+);
+error!(
+    selector_append_type,
+    "a {\n  color: selector-append(\".a\", \"b\");\n}\n", "Error: Can't append b to .a."
+);
+error!(
+    selector_nest_no_args,
+    "a {\n  color: selector-nest();\n}\n",
+    "Error: $selectors: At least one selector must be passed."
+);
+error!(
+    selector_parse_invalid_type,
+    "a {\n  color: selector-parse(1);\n}\n",
+    "Error: $selector: 1 is not a valid selector: it must be a string,"
+);
+error!(
+    selector_parse_parent,
+    "a {\n  color: selector-parse(\"&\");\n}\n",
+    "Error: $selector: Parent selectors aren't allowed here."
+);
+error!(
+    selector_extend_complex_target,
+    "a {\n  color: selector-extend(\".a\", \".b .c\", \".d\");\n}\n",
+    "Error: Can't extend complex selector .b .c."
+);
@@ -0,0 +1,188 @@
+#![cfg(test)]
+
+use grass::{Options, OutputStyle, StyleSheet};
+
+#[macro_use]
+mod macros;
+
+fn source_map(input: &str, style: OutputStyle) -> String {
+    StyleSheet::new_with_source_map(input.to_string(), &Options::default().style(style))
+        .unwrap()
+        .1
+        .to_json(false)
+}
+
+fn mappings(input: &str, style: OutputStyle) -> String {
+    let json = source_map(input, style);
+    let start = json.find("\"mappings\":\"").unwrap() + "\"mappings\":\"".len();
+    json[start..json.len() - 2].to_owned()
+}
+
+#[test]
+fn single_rule() {
+    assert_eq!(
+        "{\"version\":3,\"sources\":[\"stdin\"],\"names\":[],\"mappings\":\"AAAA;EACE\"}",
+        source_map("a {\n  color: red;\n}\n", OutputStyle::Expanded)
+    );
+}
+
+#[test]
+fn css_is_unchanged() {
+    let (css, _) =
+        StyleSheet::new_with_source_map("a {\n  color: red;\n}\n".to_string(), &Options::default())
+            .unwrap();
+    assert_eq!("a {\n  color: red;\n}\n", css);
+}
+
+#[test]
+fn multiple_declarations() {
+    assert_eq!(
+        "AAAA;EACE;EACA",
+        mappings(
+            "a {\n  color: red;\n  width: 1px;\n}\n",
+            OutputStyle::Expanded
+        )
+    );
+}
+
+#[test]
+fn source_indentation_differs_from_output() {
+    assert_eq!(
+        "AAAA;EACI",
+        mappings("a {\n    color: red;\n}\n", OutputStyle::Expanded)
+    );
+}
+
+#[test]
+fn nested_rule() {
+    assert_eq!(
+        "AAAA;EACE;;AACA;EACE",
+        mappings(
+            "a {\n  color: red;\n  b {\n    color: red;\n  }\n}\n",
+            OutputStyle::Expanded
+        )
+    );
+}
+
+#[test]
+fn blank_line_between_rules() {
+    assert_eq!(
+        "AAAA;EAAI;;;AACJ;EAAI",
+        mappings(
+            "a { color: red; }\nb { color: red; }\n",
+            OutputStyle::Expanded
+        )
+    );
+}
+
+#[test]
+fn media_query() {
+    assert_eq!(
+        "AAAA;EACE;IACE",
+        mappings(
+            "@media print {\n  a {\n    color: red;\n  }\n}\n",
+            OutputStyle::Expanded
+        )
+    );
+}
+
+#[test]
+fn compressed_single_line() {
+    assert_eq!(
+        "AAAA,EACE,UACA",
+        mappings(
+            "a {\n  color: red;\n  width: 1px;\n}\n",
+            OutputStyle::Compressed
+        )
+    );
+}
+
+#[test]
+fn compressed_multiple_rules() {
+    assert_eq!(
+        "AAAA,EAAI,UACJ,EAAI",
+        mappings(
+            "a { color: red; }\nb { color: red; }\n",
+            OutputStyle::Compressed
+        )
+    );
+}
+
+#[test]
+fn multiple_digit_vlq() {
+    assert_eq!(
+        "AAoBA;EAAI",
+        mappings(
+            &format!("{}a {{ color: red; }}\n", "\n".repeat(20)),
+            OutputStyle::Expanded
+        )
+    );
+}
+
+#[test]
+fn charset_shifts_mappings() {
+    assert_eq!(
+        ";AAAA;EACE",
+        mappings("a {\n  content: \"é\";\n}\n", OutputStyle::Expanded)
+    );
+}
+
+#[test]
+fn embedded_sources() {
+    let (_, map) = StyleSheet::new_with_source_map(
+        "a {\n  content: \"\\\\\";\n}\n".to_string(),
+        &Options::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        "{\"version\":3,\"sources\":[\"stdin\"],\"sourcesContent\":[\"a {\\n  content: \\\"\\\\\\\\\\\";\\n}\\n\"],\"names\":[],\"mappings\":\"AAAA;EACE\"}",
+        map.to_json(true)
+    );
+}
+
+#[test]
+fn file_and_mapped_sources() {
+    let (_, mut map) =
+        StyleSheet::new_with_source_map("a {\n  color: red;\n}\n".to_string(), &Options::default())
+            .unwrap();
+    map.set_file("out.css".to_string());
+    map.map_sources(|source| format!("../{}.scss", source));
+    assert_eq!(&["../stdin.scss".to_string()], map.sources());
+    assert_eq!(
+        "{\"version\":3,\"file\":\"out.css\",\"sources\":[\"../stdin.scss\"],\"names\":[],\"mappings\":\"AAAA;EACE\"}",
+        map.to_json(false)
+    );
+}
+
+#[test]
+fn data_url() {
+    let (_, map) =
+        StyleSheet::new_with_source_map("a {}".to_string(), &Options::default()).unwrap();
+    assert_eq!(
+        "data:application/json;charset=utf-8;base64,eyJ2ZXJzaW9uIjozLCJzb3VyY2VzIjpbXSwibmFtZXMiOltdLCJtYXBwaW5ncyI6IiJ9",
+        map.to_data_url(false)
+    );
+}
+
+#[test]
+fn imported_file_is_separate_source() {
+    tempfile!("source_map_imported.scss", "a {\n  color: red;\n}\n");
+    tempfile!(
+        "source_map_importer.scss",
+        "@import \"source_map_imported\";\nb {\n  color: red;\n}\n"
+    );
+    let (_, map) =
+        StyleSheet::from_path_with_source_map("source_map_importer.scss", &Options::default())
+            .unwrap();
+    assert_eq!(
+        &[
+            "source_map_imported.scss".to_string(),
+            "source_map_importer.scss".to_string()
+        ],
+        map.sources()
+    );
+    assert_eq!(
+        "{\"version\":3,\"sources\":[\"source_map_imported.scss\",\"source_map_importer.scss\"],\"names\":[],\"mappings\":\"AAAA;EACE;;;ACAF;EACE\"}",
+        map.to_json(false)
+    );
+}
@@ -0,0 +1,127 @@
+#![cfg(test)]
+
+use grass::{MemoryImporter, Options, SassError, SourceLocation, StackFrame, StyleSheet};
+
+#[macro_use]
+mod macros;
+
+fn compile_err(input: &str) -> SassError {
+    StyleSheet::new(input.to_string()).unwrap_err()
+}
+
+fn frame(file: &str, line: usize, column: usize, member: Option<&str>) -> StackFrame {
+    StackFrame {
+        location: SourceLocation {
+            file: file.to_owned(),
+            line,
+            column,
+        },
+        member: member.map(ToOwned::to_owned),
+    }
+}
+
+#[test]
+fn error_in_mixin() {
+    let err = compile_err("@mixin foo {\n  @error \"bar\";\n}\na {\n  @include foo;\n}\n");
+    assert_eq!("\"bar\"", err.message());
+    assert_eq!(
+        &[
+            frame("stdin", 2, 3, Some("foo")),
+            frame("stdin", 5, 12, None)
+        ],
+        err.trace()
+    );
+}
+
+#[test]
+fn error_in_function_in_mixin() {
+    let err = compile_err(
+        "@function double($a) {\n  @error \"#{$a} isn't a number\";\n}\n@mixin foo($a) {\n  width: double($a);\n}\na {\n  @include foo(red);\n}\n",
+    );
+    assert_eq!("\"red isn't a number\"", err.message());
+    assert_eq!(
+        &[
+            frame("stdin", 2, 3, Some("double")),
+            frame("stdin", 5, 10, Some("foo")),
+            frame("stdin", 8, 12, None)
+        ],
+        err.trace()
+    );
+}
+
+#[test]
+fn arithmetic_error_in_nested_function() {
+    let err = compile_err(
+        "@function f($a) {\n  @return $a + 1px + 1deg;\n}\n@function g() {\n  @return f(1px);\n}\na {\n  width: g();\n}\n",
+    );
+    assert_eq!("Incompatible units deg and px.", err.message());
+    let trace = err.trace();
+    assert_eq!(3, trace.len());
+    assert_eq!(Some("f".to_owned()), trace[0].member);
+    assert_eq!(2, trace[0].location.line);
+    assert_eq!(
+        &[
+            frame("stdin", 5, 11, Some("g")),
+            frame("stdin", 8, 10, None)
+        ],
+        &trace[1..]
+    );
+}
+
+#[test]
+fn displayed_beneath_error() {
+    let err = compile_err("@mixin foo {\n  @error \"bar\";\n}\na {\n  @include foo;\n}\n");
+    assert_eq!(
+        "Error: \"bar\"\n  |\n2 |   @error \"bar\";\n  |   ^^^^^^^^^^^^\n  |\n  stdin 2:3   foo()\n  stdin 5:12  root stylesheet\n",
+        err.to_string()
+    );
+}
+
+#[test]
+fn no_trace_outside_calls() {
+    let err = compile_err("a {\n  color: $foo;\n}\n");
+    assert!(err.trace().is_empty());
+    assert!(err.to_string().ends_with("./stdin:2:11\n"), "{}", err);
+}
+
+#[test]
+fn argument_error_reported_at_call() {
+    let err = compile_err("@mixin foo($a) {\n  color: $a;\n}\na {\n  @include foo($b);\n}\n");
+    assert_eq!("Undefined variable.", err.message());
+    assert!(err.trace().is_empty());
+}
+
+#[test]
+fn underscores_normalized() {
+    let err =
+        compile_err("@function foo_bar() {\n  @error \"baz\";\n}\na {\n  color: foo_bar();\n}\n");
+    assert_eq!(Some("foo-bar".to_owned()), err.trace()[0].member);
+}
+
+#[test]
+fn function_called_with_call() {
+    let err = compile_err(
+        "@function foo() {\n  @error \"bar\";\n}\na {\n  color: call(get-function(foo));\n}\n",
+    );
+    assert_eq!(
+        vec![Some("foo".to_owned()), None],
+        err.trace()
+            .iter()
+            .map(|frame| frame.member.clone())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn mixin_in_module() {
+    let mut importer = MemoryImporter::new();
+    importer.insert("lib.scss", "@mixin foo {\n  @error \"bar\";\n}\n");
+    let err = StyleSheet::new_with_options(
+        "@use \"lib\";\na {\n  @include lib.foo;\n}\n".to_string(),
+        &Options::default().importer(importer),
+    )
+    .unwrap_err();
+    assert_eq!(Some("foo".to_owned()), err.trace()[0].member);
+    assert_eq!(2, err.trace()[0].location.line);
+    assert_eq!(frame("stdin", 3, 16, None), err.trace()[1]);
+}
@@ -0,0 +1,101 @@
+#![cfg(test)]
+
+#[macro_use]
+mod macros;
+
+test!(
+    supports_declaration,
+    "@supports (display: grid) {\n  a {\n    color: red;\n  }\n}\n",
+    "@supports (display: grid) {\n  a {\n    color: red;\n  }\n}\n"
+);
+test!(
+    supports_and_not,
+    "@supports (display: grid) and (not (display: inline-grid)) {\n  a {\n    color: red;\n  }\n}\n",
+    "@supports (display: grid) and (not (display: inline-grid)) {\n  a {\n    color: red;\n  }\n}\n"
+);
+test!(
+    supports_not,
+    "@supports not (display: grid) {\n  a {\n    color: red;\n  }\n}\n",
+    "@supports not (display: grid) {\n  a {\n    color: red;\n  }\n}\n"
+);
+test!(
+    supports_or,
+    "@supports (display: grid) or (display: flex) {\n  a {\n    color: red;\n  }\n}\n",
+    "@supports (display: grid) or (display: flex) {\n  a {\n    color: red;\n  }\n}\n"
+);
+test!(
+    supports_normalizes_whitespace,
+    "@supports   (  display :grid  )and(gap: 1px) {\n  a {\n    color: red;\n  }\n}\n",
+    "@supports (display: grid) and (gap: 1px) {\n  a {\n    color: red;\n  }\n}\n"
+);
+test!(
+    supports_sassscript_value,
+    "$display: grid;\n@supports (display: $display) {\n  a {\n    color: red;\n  }\n}\n",
+    "@supports (display: grid) {\n  a {\n    color: red;\n  }\n}\n"
+);
+test!(
+    supports_interpolated_name,
+    "$prop: display;\n@supports (#{$prop}: grid) {\n  a {\n    color: red;\n  }\n}\n",
+    "@supports (display: grid) {\n  a {\n    color: red;\n  }\n}\n"
+);
+test!(
+    supports_interpolated_condition,
+    "$query: \"(display: grid)\";\n@supports #{$query} {\n  a {\n    color: red;\n  }\n}\n",
+    "@supports (display: grid) {\n  a {\n    color: red;\n  }\n}\n"
+);
+test!(
+    supports_function,
+    "@supports selector(a > b) {\n  a {\n    color: red;\n  }\n}\n",
+    "@supports selector(a > b) {\n  a {\n    color: red;\n  }\n}\n"
+);
+test!(
+    supports_bubbles_out_of_style_rule,
+    "a {\n  color: blue;\n  @supports (display: grid) {\n    display: grid;\n  }\n}\n",
+    "a {\n  color: blue;\n}\n@supports (display: grid) {\n  a {\n    display: grid;\n  }\n}\n"
+);
+test!(
+    supports_nested_style_rule,
+    "a {\n  @supports (display: grid) {\n    b {\n      color: red;\n    }\n  }\n}\n",
+    "@supports (display: grid) {\n  a b {\n    color: red;\n  }\n}\n"
+);
+test!(
+    supports_within_media,
+    "@media screen {\n  a {\n    @supports (display: grid) {\n      color: red;\n    }\n  }\n}\n",
+    "@media screen {\n  @supports (display: grid) {\n    a {\n      color: red;\n    }\n  }\n}\n"
+);
+test!(
+    media_within_supports,
+    "a {\n  @supports (display: grid) {\n    @media screen {\n      color: red;\n    }\n  }\n}\n",
+    "@supports (display: grid) {\n  @media screen {\n    a {\n      color: red;\n    }\n  }\n}\n"
+);
+test!(supports_empty_body, "@supports (display: grid) {}\n", "");
+error!(
+    supports_missing_operator,
+    "@supports (a: b) (c: d) {}\n", "Error: Expected identifier."
+);
+error!(
+    supports_invalid_operator,
+    "@supports (a: b) xor (c: d) {}\n", "Error: expected \"and\" or \"or\"."
+);
+error!(
+    supports_missing_colon,
+    "@supports (a) {}\n", "Error: expected \":\"."
+);
+error!(
+    supports_and_then_or,
+    "@supports (a: b) and (c: d) or (e: f) {}\n", "Error: expected \"and\"."
+);
+error!(
+    supports_or_then_and,
+    "@supports (a: b) or (c: d) and (e: f) {}\n", "Error: expected \"or\"."
+);
+test!(
+    supports_and_within_or,
+    "@supports ((a: b) and (c: d)) or (e: f) {\n  a {\n    color: red;\n  }\n}\n",
+    "@supports ((a: b) and (c: d)) or (e: f) {\n  a {\n    color: red;\n  }\n}\n"
+);
+test!(
+    supports_repeated_and,
+    "@supports (a: b) and (c: d) and (e: f) {\n  a {\n    color: red;\n  }\n}\n",
+    "@supports (a: b) and (c: d) and (e: f) {\n  a {\n    color: red;\n  }\n}\n"
+);
@@ -0,0 +1,383 @@
+#![cfg(test)]
+
+use grass::StyleSheet;
+
+#[macro_use]
+mod macros;
+
+error!(
+    use_after_style_rule,
+    "a { color: red; }\n@use \"foo\";", "Error: @use rules must be written before any other rules."
+);
+error!(
+    use_inside_style_rule,
+    "a { @use \"foo\"; }", "Error: This at-rule is not allowed here."
+);
+error!(
+    use_nonexistent_file,
+    "@use \"use_nonexistent_file\";", "Error: Can't find stylesheet to import."
+);
+error!(use_no_quotes, "@use foo;", "Error: Expected string.");
+error!(
+    undefined_namespace,
+    "a { color: foo.$a; }", "Error: There is no module with the namespace \"foo\"."
+);
+
+#[test]
+fn use_variable_default_namespace() {
+    let input = "@use \"use_variable_default_namespace\";\na {\n color: use_variable_default_namespace.$a;\n}";
+    tempfile!("use_variable_default_namespace.scss", "$a: red;");
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn use_default_namespace_strips_underscore() {
+    let input = "@use \"use_strips_underscore\";\na {\n color: use_strips_underscore.$a;\n}";
+    tempfile!("_use_strips_underscore.scss", "$a: red;");
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn use_as_namespace() {
+    let input = "@use \"use_as_namespace\" as foo;\na {\n color: foo.$a;\n}";
+    tempfile!("use_as_namespace.scss", "$a: red;");
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn use_as_star() {
+    let input = "@use \"use_as_star\" as *;\na {\n color: $a;\n  width: double(2px);\n}";
+    tempfile!(
+        "use_as_star.scss",
+        "$a: red; @function double($n) { @return $n * 2; }"
+    );
+    assert_eq!(
+        "a {\n  color: red;\n  width: 4px;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn use_variable_not_global() {
+    let input = "@use \"use_variable_not_global\";\na {\n color: $a;\n}";
+    tempfile!("use_variable_not_global.scss", "$a: red;");
+    match StyleSheet::new(input.to_string()) {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: Undefined variable.",
+            e.to_string()
+                .chars()
+                .take_while(|c| *c != '\n')
+                .collect::<String>()
+                .as_str()
+        ),
+    }
+}
+
+#[test]
+fn use_function_uses_module_scope() {
+    let input = "@use \"use_function_uses_module_scope\" as lib;\n$size: 1px;\na {\n color: lib.double($size);\n}";
+    tempfile!(
+        "use_function_uses_module_scope.scss",
+        "$size: 10px; @function double($n) { @return $n + $size; }"
+    );
+    assert_eq!(
+        "a {\n  color: 11px;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn use_mixin() {
+    let input = "@use \"use_mixin\" as lib;\na {\n @include lib.foo(red);\n}";
+    tempfile!(
+        "use_mixin.scss",
+        "$width: 1px; @mixin foo($color) { color: $color; width: $width; }"
+    );
+    assert_eq!(
+        "a {\n  color: red;\n  width: 1px;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn use_private_variable() {
+    let input = "@use \"use_private_variable\" as lib;\na {\n color: lib.$-a;\n}";
+    tempfile!("use_private_variable.scss", "$-a: red;");
+    match StyleSheet::new(input.to_string()) {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: Private members can't be accessed from outside their modules.",
+            e.to_string()
+                .chars()
+                .take_while(|c| *c != '\n')
+                .collect::<String>()
+                .as_str()
+        ),
+    }
+}
+
+#[test]
+fn use_emits_css() {
+    let input = "@use \"use_emits_css\";\nb {\n color: blue;\n}";
+    tempfile!("use_emits_css.scss", "a { color: red; }");
+    assert_eq!(
+        "a {\n  color: red;\n}\n\nb {\n  color: blue;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn use_loads_module_once() {
+    let input = "@use \"use_loads_module_once__a\";\n@use \"use_loads_module_once__b\";";
+    tempfile!("use_loads_module_once__a.scss", "a { color: red; }");
+    tempfile!(
+        "use_loads_module_once__b.scss",
+        "@use \"use_loads_module_once__a\";"
+    );
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn use_duplicate_namespace() {
+    let input = "@use \"use_duplicate_namespace\" as a;\n@use \"use_duplicate_namespace\" as a;";
+    tempfile!("use_duplicate_namespace.scss", "");
+    match StyleSheet::new(input.to_string()) {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: There's already a module with namespace \"a\".",
+            e.to_string()
+                .chars()
+                .take_while(|c| *c != '\n')
+                .collect::<String>()
+                .as_str()
+        ),
+    }
+}
+
+#[test]
+fn use_with_configures_default_var() {
+    let input = "@use \"use_with_configures_default_var\" with ($a: blue);\nb {\n color: use_with_configures_default_var.$a;\n}";
+    tempfile!("use_with_configures_default_var.scss", "$a: red !default;");
+    assert_eq!(
+        "b {\n  color: blue;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn use_with_unconfigured_default_var() {
+    let input =
+        "@use \"use_with_unconfigured_default_var\" as m with ($a: blue);\nb {\n color: m.$b;\n}";
+    tempfile!(
+        "use_with_unconfigured_default_var.scss",
+        "$a: red !default;\n$b: green !default;"
+    );
+    assert_eq!(
+        "b {\n  color: green;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn use_with_configured_value_used_in_css() {
+    let input = "@use \"use_with_configured_value_used_in_css\" with ($a: blue,);";
+    tempfile!(
+        "use_with_configured_value_used_in_css.scss",
+        "$a: red !default;\na {\n color: $a;\n}"
+    );
+    assert_eq!(
+        "a {\n  color: blue;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn use_with_non_default_var() {
+    let input = "@use \"use_with_non_default_var\" with ($a: blue);";
+    tempfile!("use_with_non_default_var.scss", "$a: red;");
+    match StyleSheet::new(input.to_string()) {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: This variable was not declared with !default in the @used module.",
+            e.to_string()
+                .chars()
+                .take_while(|c| *c != '\n')
+                .collect::<String>()
+                .as_str()
+        ),
+    }
+}
+
+#[test]
+fn use_with_already_loaded() {
+    let input = "@use \"use_with_already_loaded\" as a;\n@use \"use_with_already_loaded\" as b with ($a: blue);";
+    tempfile!("use_with_already_loaded.scss", "$a: red !default;");
+    match StyleSheet::new(input.to_string()) {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: This module was already loaded, so it can't be configured using \"with\".",
+            e.to_string()
+                .chars()
+                .take_while(|c| *c != '\n')
+                .collect::<String>()
+                .as_str()
+        ),
+    }
+}
+
+error!(
+    use_with_duplicate_variable,
+    "@use \"foo\" with ($a: red, $a: blue);",
+    "Error: The same variable may only be configured once."
+);
+error!(
+    use_with_empty_configuration,
+    "@use \"foo\" with ();", "Error: expected \"$\"."
+);
+
+#[test]
+fn use_module_loop() {
+    let input = "@use \"use_module_loop_a\";";
+    tempfile!("use_module_loop_a.scss", "@use \"use_module_loop_b\";");
+    tempfile!("use_module_loop_b.scss", "@use \"use_module_loop_a\";");
+    match StyleSheet::new(input.to_string()) {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: Module loop: this module is already being loaded.",
+            e.to_string().lines().next().unwrap()
+        ),
+    }
+}
+
+#[test]
+fn get_function_from_module_uses_module_globals() {
+    let input = "@use \"sass:meta\";\n@use \"get_function_module_globals\" as m;\na {\n color: meta.call(meta.get-function(\"scale\", $module: \"m\"), 2px);\n}";
+    tempfile!(
+        "get_function_module_globals.scss",
+        "$factor: 3; @function scale($n) { @return $n * $factor; }"
+    );
+    assert_eq!(
+        "a {\n  color: 6px;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn module_mixin_assigns_global() {
+    let input = "@use \"module_mixin_assigns_global\" as m;\n@include m.inc;\n@include m.inc;\na {\n  color: m.$count;\n  width: m.get();\n}";
+    tempfile!(
+        "module_mixin_assigns_global.scss",
+        "$count: 0; @mixin inc { $count: $count + 1 !global; } @function get() { @return $count; }"
+    );
+    assert_eq!(
+        "a {\n  color: 2;\n  width: 2;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn module_function_assigns_global() {
+    let input = "@use \"module_function_assigns_global\" as m;\n$a: m.inc();\n$b: m.inc();\na {\n  color: m.$count;\n  width: $b;\n}";
+    tempfile!(
+        "module_function_assigns_global.scss",
+        "$count: 0; @function inc() { $count: $count + 1 !global; @return $count; }"
+    );
+    assert_eq!(
+        "a {\n  color: 2;\n  width: 2;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn assign_module_variable() {
+    let input = "@use \"assign_module_variable\" as lib;\nlib.$a: blue;\na {\n color: lib.$a;\n  background: lib.get-a();\n}";
+    tempfile!(
+        "assign_module_variable.scss",
+        "$a: red; @function get-a() { @return $a; }"
+    );
+    assert_eq!(
+        "a {\n  color: blue;\n  background: blue;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn assign_module_variable_in_rule() {
+    let input = "@use \"assign_module_variable_in_rule\" as lib;\na {\n  lib.$a: blue;\n  color: lib.$a;\n}\nb {\n  color: lib.$a;\n}";
+    tempfile!("assign_module_variable_in_rule.scss", "$a: red;");
+    assert_eq!(
+        "a {\n  color: blue;\n}\n\nb {\n  color: blue;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn assign_module_variable_default() {
+    let input = "@use \"assign_module_variable_default\" as lib;\nlib.$a: blue !default;\nlib.$b: blue !default;\na {\n  color: lib.$a;\n  background: lib.$b;\n}";
+    tempfile!("assign_module_variable_default.scss", "$a: red; $b: null;");
+    assert_eq!(
+        "a {\n  color: red;\n  background: blue;\n}\n",
+        &StyleSheet::new(input.to_string()).expect(input)
+    );
+}
+
+#[test]
+fn assign_undeclared_module_variable() {
+    let input = "@use \"assign_undeclared_module_variable\" as lib;\nlib.$b: blue;";
+    tempfile!("assign_undeclared_module_variable.scss", "$a: red;");
+    match StyleSheet::new(input.to_string()) {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: Undefined variable.",
+            e.to_string().lines().next().unwrap()
+        ),
+    }
+}
+
+#[test]
+fn assign_private_module_variable() {
+    let input = "@use \"assign_private_module_variable\" as lib;\nlib.$-a: blue;";
+    tempfile!("assign_private_module_variable.scss", "$-a: red;");
+    match StyleSheet::new(input.to_string()) {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: Private members can't be accessed from outside their modules.",
+            e.to_string().lines().next().unwrap()
+        ),
+    }
+}
+
+#[test]
+fn assign_module_variable_global() {
+    let input = "@use \"assign_module_variable_global\" as lib;\nlib.$a: blue !global;";
+    tempfile!("assign_module_variable_global.scss", "$a: red;");
+    match StyleSheet::new(input.to_string()) {
+        Ok(..) => panic!("did not fail"),
+        Err(e) => assert_eq!(
+            "Error: !global isn't allowed for variables in other modules.",
+            e.to_string().lines().next().unwrap()
+        ),
+    }
+}
+
+error!(
+    assign_builtin_module_variable,
+    "@use \"sass:math\";\nmath.$pi: 0;", "Error: Cannot modify built-in variable."
+);
+error!(
+    assign_variable_of_unknown_namespace,
+    "lib.$a: blue;", "Error: There is no module with the namespace \"lib\"."
+);
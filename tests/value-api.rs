@@ -0,0 +1,206 @@
+#![cfg(test)]
+
+use grass::{
+    Brackets, Color, ListSeparator, Number, Options, QuoteKind, SassMap, StyleSheet, Unit, Value,
+};
+
+#[macro_use]
+mod macros;
+
+fn list(items: Vec<Value>) -> Value {
+    Value::List(items, ListSeparator::Comma, Brackets::None)
+}
+
+#[test]
+fn display_number() {
+    assert_eq!("1.5px", Value::number(1.5, Unit::Px).to_string());
+    assert_eq!("10%", Value::number(10, Unit::Percent).to_string());
+    assert_eq!(
+        "0.3333333333",
+        Value::number(Number::from(1) / Number::from(3), Unit::None).to_string()
+    );
+}
+
+#[test]
+fn display_strings() {
+    assert_eq!("\"foo\"", Value::quoted_string("foo").to_string());
+    assert_eq!("foo", Value::unquoted_string("foo").to_string());
+}
+
+#[test]
+fn display_list() {
+    assert_eq!(
+        "a, b",
+        list(vec![
+            Value::unquoted_string("a"),
+            Value::unquoted_string("b")
+        ])
+        .to_string()
+    );
+}
+
+#[test]
+fn display_color() {
+    let red = Color::from_rgba(255.into(), 0.into(), 0.into(), 1.into());
+    assert_eq!("red", Value::Color(Box::new(red)).to_string());
+}
+
+#[test]
+fn display_map_is_inspected() {
+    let mut map = SassMap::new();
+    map.insert(Value::unquoted_string("a"), Value::number(1, Unit::Px));
+    assert_eq!("(a: 1px)", Value::Map(map).to_string());
+}
+
+#[test]
+fn display_never_fails() {
+    let mut inner = SassMap::new();
+    inner.insert(Value::unquoted_string("b"), Value::True);
+    let mut map = SassMap::new();
+    map.insert(Value::unquoted_string("a"), Value::Map(inner));
+    let map = Value::Map(map);
+    assert!(!map.to_string().is_empty());
+    assert!(!format!("{:#}", map).is_empty());
+}
+
+#[test]
+fn alternate_display_is_inspected() {
+    assert_eq!("()", format!("{:#}", list(Vec::new())));
+    assert_eq!("null", format!("{:#}", Value::Null));
+    assert_eq!("", Value::Null.to_string());
+}
+
+#[test]
+fn number_converted_between_units() {
+    let value = Value::number(1, Unit::In);
+    assert_eq!(Some(Number::from(96)), value.number_in(&Unit::Px));
+    assert_eq!(Some(Number::from(1)), value.number_in(&Unit::In));
+    assert_eq!(None, value.number_in(&Unit::Deg));
+    assert_eq!(None, Value::Null.number_in(&Unit::Px));
+}
+
+#[test]
+fn unitless_number_compatible_with_any_unit() {
+    assert_eq!(
+        Some(Number::from(2)),
+        Value::number(2, Unit::None).number_in(&Unit::Em)
+    );
+}
+
+#[test]
+fn number_to_f64() {
+    assert_eq!(0.25, (Number::from(1) / Number::from(4)).to_f64());
+    assert_eq!(1.5, Number::from(1.5).to_f64());
+}
+
+#[test]
+fn accessors() {
+    assert_eq!(Some("a"), Value::quoted_string("a").as_str());
+    assert_eq!(None, Value::True.as_str());
+    assert_eq!(
+        Some((&Number::from(1), &Unit::Px)),
+        Value::number(1, Unit::Px).as_number()
+    );
+    assert!(Value::Null.as_color().is_none());
+    assert!(Value::number(0, Unit::None).is_truthy());
+    assert!(!Value::False.is_truthy());
+    assert!(!Value::Null.is_truthy());
+}
+
+#[test]
+fn single_value_is_list_of_itself() {
+    assert_eq!(vec![Value::True], Value::True.as_list());
+}
+
+#[test]
+fn map_is_list_of_pairs() {
+    let mut map = SassMap::new();
+    map.insert(Value::unquoted_string("a"), Value::True);
+    assert_eq!(
+        vec![Value::List(
+            vec![Value::unquoted_string("a"), Value::True],
+            ListSeparator::Space,
+            Brackets::None
+        )],
+        Value::Map(map).as_list()
+    );
+}
+
+#[test]
+fn empty_list_is_empty_map() {
+    assert!(list(Vec::new()).as_map().unwrap().is_empty());
+    assert!(list(vec![Value::True]).as_map().is_none());
+}
+
+#[test]
+fn map_get_uses_sass_equality() {
+    let mut map = SassMap::new();
+    map.insert(Value::number(96, Unit::Px), Value::True);
+    map.insert(Value::quoted_string("b"), Value::False);
+    assert_eq!(Some(&Value::True), map.get(&Value::number(1, Unit::In)));
+    assert_eq!(Some(&Value::False), map.get(&Value::unquoted_string("b")));
+    assert_eq!(None, map.get(&Value::Null));
+    assert_eq!(2, map.iter().count());
+}
+
+#[test]
+fn assert_error_messages() {
+    assert_eq!(
+        Err("$color: \"red\" is not a color.".to_owned()),
+        Value::quoted_string("red")
+            .assert_color("color")
+            .map(|_| ())
+    );
+    assert_eq!(
+        Err("$n: a, b is not a number.".to_owned()),
+        list(vec![
+            Value::unquoted_string("a"),
+            Value::unquoted_string("b")
+        ])
+        .assert_number("n")
+        .map(|_| ())
+    );
+    assert_eq!(Ok("a"), Value::unquoted_string("a").assert_string("s"));
+}
+
+#[test]
+fn host_function_using_value_api() {
+    let options = Options::default().function("lighter($color, $amount)", |args| {
+        let color = args[0].assert_color("color")?;
+        let amount = args[1].assert_number("amount")?.0.clone();
+        Ok(Value::Color(Box::new(Color::from_hsla(
+            color.hue(),
+            color.saturation() / Number::from(100),
+            (color.lightness() + amount) / Number::from(100),
+            color.alpha(),
+        ))))
+    });
+    assert_eq!(
+        "a {\n  color: #ff3333;\n}\n",
+        StyleSheet::new_with_options(
+            "a {\n  color: lighter(red, 10%);\n}\n".to_string(),
+            &options
+        )
+        .unwrap()
+    );
+    assert_eq!(
+        "Error: $color: 1px is not a color.",
+        StyleSheet::new_with_options(
+            "a {\n  color: lighter(1px, 10%);\n}\n".to_string(),
+            &options
+        )
+        .unwrap_err()
+        .to_string()
+        .lines()
+        .next()
+        .unwrap()
+    );
+}
+
+#[test]
+fn quote_kind_preserved() {
+    assert_eq!(
+        Value::String("a".to_owned(), QuoteKind::Quoted),
+        Value::quoted_string("a")
+    );
+}